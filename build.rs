@@ -1,9 +1,160 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
+/// The `lang-*` features, the leading words of the Unicode names of the characters whose
+/// rules in the rule files they control, and the languages written in these characters
+/// that are romanized by rules without an `::lcode`.
+type LanguageGroup = (&'static str, &'static [&'static str], &'static [&'static str]);
+
+const LANGUAGE_GROUPS: [LanguageGroup; 6] = [
+    ("LANG_ZHO", &["CJK"], &["cmn", "yue", "zho"]),
+    (
+        "LANG_JPN",
+        &["HIRAGANA", "KATAKANA", "KATAKANA-HIRAGANA", "HENTAIGANA", "HALFWIDTH KATAKANA"],
+        &["jpn"],
+    ),
+    ("LANG_KOR", &["HANGUL", "HALFWIDTH HANGUL"], &["kor"]),
+    ("LANG_ARA", &["ARABIC"], &["ara", "snd", "urd"]),
+    ("LANG_CYRILLIC", &["CYRILLIC"], &[]),
+    (
+        "LANG_INDIC",
+        &[
+            "DEVANAGARI", "BENGALI", "GURMUKHI", "GUJARATI", "ORIYA", "TAMIL", "TELUGU",
+            "KANNADA", "MALAYALAM", "SINHALA",
+        ],
+        &[
+            "asm", "ben", "guj", "hin", "kan", "mal", "mar", "nep", "ori", "pan", "san", "sin",
+            "tam", "tel",
+        ],
+    ),
+];
+
+/// Returns the Unicode names of the characters listed in `data/UnicodeData.txt`.
+///
+/// The characters of a range such as `<CJK Ideograph, First>` get the uppercase first
+/// word of the range name (e.g., `CJK`).
+fn char_names() -> HashMap<char, String> {
+    let mut names = HashMap::new();
+    let mut range_start = None;
+    let input = BufReader::new(File::open("data/UnicodeData.txt").unwrap());
+    for line in input.lines() {
+        let line = line.unwrap();
+        let fields: Vec<&str> = line.split(';').collect();
+        let (Some(cp), Some(name)) = (
+            fields.first().and_then(|cp| u32::from_str_radix(cp, 16).ok()),
+            fields.get(1),
+        ) else {
+            continue;
+        };
+
+        if let Some(range) = name.strip_prefix('<').and_then(|n| n.strip_suffix(", First>")) {
+            range_start = Some((cp, range.split(' ').next().unwrap_or("").to_uppercase()));
+        } else if name.ends_with(", Last>") {
+            if let Some((start, range)) = range_start.take() {
+                names.extend((start..=cp).filter_map(char::from_u32).map(|c| (c, range.clone())));
+            }
+        } else if let Some(c) = char::from_u32(cp) {
+            names.insert(c, name.to_string());
+        }
+    }
+    names
+}
+
+/// Returns `true` if the Unicode name of `c` starts with one of the `prefixes`.
+fn has_name_prefix(c: char, names: &HashMap<char, String>, prefixes: &[&str]) -> bool {
+    let name = names.get(&c).map_or("", String::as_str);
+    prefixes.iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+/// Writes the rule file `data/{file_name}` to `OUT_DIR` without the rules whose source
+/// starts with a character of a disabled `lang-*` feature.
+///
+/// `languages` records, for each `::lcode` of a rule, whether it has rules for the
+/// script of a disabled feature and whether it has rules for another script. The script
+/// of a rule is that of the first letter of its source; rules for Latin letters, and
+/// rules without a letter, are not counted.
+fn write_rule_file(
+    file_name: &str,
+    names: &HashMap<char, String>,
+    excluded: &[&str],
+    languages: &mut HashMap<String, (bool, bool)>,
+) {
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join(file_name);
+    let mut out = BufWriter::new(File::create(out_path).unwrap());
+    let input = BufReader::new(File::open(Path::new("data").join(file_name)).unwrap());
+    for line in input.lines() {
+        let line = line.unwrap();
+        let source = line
+            .split_once("::s ")
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .unwrap_or("");
+        let is_excluded = source
+            .chars()
+            .next()
+            .is_some_and(|c| has_name_prefix(c, names, excluded));
+        if !is_excluded {
+            writeln!(out, "{line}").unwrap();
+        }
+
+        let script_char = source.chars().find(|c| c.is_alphabetic());
+        let Some(script_char) = script_char
+            .filter(|&c| !c.is_ascii() && !has_name_prefix(c, names, &["LATIN"]))
+        else {
+            continue;
+        };
+        let has_excluded_script = is_excluded || has_name_prefix(script_char, names, excluded);
+        let lcodes = line
+            .split_once("::lcode ")
+            .map_or("", |(_, rest)| rest.split("::").next().unwrap_or(""));
+        for lcode in lcodes.split(',').map(str::trim).filter(|lcode| !lcode.is_empty()) {
+            let (excluded_rules, kept_rules) = languages.entry(lcode.to_string()).or_default();
+            *excluded_rules |= has_excluded_script;
+            *kept_rules |= !has_excluded_script;
+        }
+    }
+}
+
+fn write_rule_files() {
+    let disabled: Vec<&LanguageGroup> = LANGUAGE_GROUPS
+        .iter()
+        .filter(|(feature, _, _)| env::var_os(format!("CARGO_FEATURE_{feature}")).is_none())
+        .collect();
+    let excluded: Vec<&str> = disabled
+        .iter()
+        .flat_map(|(_, prefixes, _)| prefixes.iter().copied())
+        .collect();
+
+    let names = if excluded.is_empty() { HashMap::new() } else { char_names() };
+    let mut languages = HashMap::new();
+    write_rule_file("romanization-auto-table.txt", &names, &excluded, &mut languages);
+    write_rule_file("romanization-table.txt", &names, &excluded, &mut languages);
+
+    // The languages whose own script is only written by left-out rules are not
+    // supported by the build.
+    let mut excluded_languages: Vec<&str> = languages
+        .iter()
+        .filter(|(_, (excluded_rules, kept_rules))| *excluded_rules && !*kept_rules)
+        .map(|(lcode, _)| lcode.as_str())
+        .chain(disabled.iter().flat_map(|(_, _, lcodes)| lcodes.iter().copied()))
+        .collect();
+    excluded_languages.sort();
+    excluded_languages.dedup();
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("excluded-languages.txt");
+    let mut out = BufWriter::new(File::create(out_path).unwrap());
+    for lcode in excluded_languages {
+        writeln!(out, "{lcode}").unwrap();
+    }
+}
+
 fn main() {
+    write_rule_files();
+
     if env::var("CARGO_CFG_DOC").is_ok() {
         println!("cargo:warning=Skipping decomposition map generation during rustdoc build.");
         return;
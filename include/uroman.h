@@ -0,0 +1,34 @@
+/* C bindings for uroman-rs, available with the `ffi` feature. */
+
+#ifndef UROMAN_H
+#define UROMAN_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A romanizer, created by `uroman_new` and released by `uroman_free`. */
+typedef struct Uroman Uroman;
+
+/* Creates a romanizer with the default options, or returns NULL on failure. */
+Uroman *uroman_new(void);
+
+/*
+ * Romanizes the null-terminated UTF-8 string `text`, with the ISO 639-3 language
+ * code `lcode` or NULL for none. Returns a new null-terminated UTF-8 string, to be
+ * released with `uroman_string_free`, or NULL if an argument is NULL or not valid
+ * UTF-8, or if romanization fails.
+ */
+char *uroman_romanize(const Uroman *uroman, const char *text, const char *lcode);
+
+/* Releases a string returned by `uroman_romanize`. Does nothing for NULL. */
+void uroman_string_free(char *s);
+
+/* Releases a romanizer returned by `uroman_new`. Does nothing for NULL. */
+void uroman_free(Uroman *uroman);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* UROMAN_H */
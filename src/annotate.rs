@@ -0,0 +1,76 @@
+//! Renders a romanization with the source text in parentheses after it.
+
+use std::ops::Range;
+
+use crate::ruby::is_word;
+use crate::sentence::split_sentences;
+
+/// How `Uroman::romanize_annotated` groups the text that is followed by its source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AnnotationGranularity {
+    /// Each run of letters, marks and digits (e.g., `Kyiv (Київ) i Lviv (Львів)`).
+    ///
+    /// Since Chinese and Japanese are written without spaces, a token runs up to the next
+    /// space or punctuation mark.
+    #[default]
+    Token,
+    /// Each sentence, with its punctuation (e.g., `Kyiv i Lviv. (Київ і Львів.)`).
+    Sentence,
+}
+
+/// Returns the romanization of `s` with the source of each group of `alignment` that
+/// has letters or digits and is changed by the romanization in parentheses after it.
+pub(crate) fn annotate(
+    s: &str,
+    lcode: Option<&str>,
+    romanized: &str,
+    alignment: &[(Range<usize>, Range<usize>)],
+    granularity: AnnotationGranularity,
+) -> String {
+    let sentences: Vec<Range<usize>> = match granularity {
+        AnnotationGranularity::Token => Vec::new(),
+        AnnotationGranularity::Sentence => split_sentences(s, lcode)
+            .into_iter()
+            .map(|sentence| {
+                let start = sentence.as_ptr().addr() - s.as_ptr().addr();
+                start..start + sentence.len()
+            })
+            .collect(),
+    };
+    let sentence_of = |source: &Range<usize>| {
+        sentences
+            .iter()
+            .position(|sentence| sentence.contains(&source.start))
+    };
+
+    let mut groups: Vec<(Range<usize>, Range<usize>)> = Vec::with_capacity(alignment.len());
+    for (source, output) in alignment {
+        if let Some((last_source, last_output)) = groups.last_mut()
+            && match granularity {
+                AnnotationGranularity::Token => {
+                    is_word(&s[last_source.clone()]) && is_word(&s[source.clone()])
+                }
+                AnnotationGranularity::Sentence => {
+                    sentence_of(source).is_some() && sentence_of(last_source) == sentence_of(source)
+                }
+            }
+        {
+            last_source.end = source.end;
+            last_output.end = output.end;
+        } else {
+            groups.push((source.clone(), output.clone()));
+        }
+    }
+
+    let mut annotated = String::with_capacity(s.len() + romanized.len() * 2);
+    for (source, output) in groups {
+        let (source, output) = (&s[source], &romanized[output]);
+        annotated.push_str(output);
+        if source.chars().any(char::is_alphanumeric) && source != output {
+            annotated.push_str(" (");
+            annotated.push_str(source);
+            annotated.push(')');
+        }
+    }
+    annotated
+}
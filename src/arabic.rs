@@ -0,0 +1,334 @@
+//! Romanizes Arabic according to a selectable `ArabicScheme`.
+//!
+//! With `ArabicScheme::AlaLc`, every run of Arabic letters is romanized as a word
+//! here and replaces the edges of the romanization tables in the lattice.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Arabic, used when `lcode` is `ara`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArabicScheme {
+    /// The rules of the romanization tables (`alshms` for الشمس), with the definite article
+    /// assimilated to sun letters if `arabic_assimilate_article` is set (`ash-shms`).
+    #[default]
+    Default,
+    /// ALA-LC, with dotted emphatics (`ḥ`, `ṣ`, `ḍ`, `ṭ`, `ẓ`), `ʼ` for hamza, `ʻ` for ʿayn,
+    /// `h` for tāʼ marbūṭah and a hyphenated article that is assimilated to sun letters
+    /// (`ash-shams` for الشَّمْس).
+    ///
+    /// Short vowels are only written where the source is vocalized with harakat;
+    /// unvocalized text keeps its consonant skeleton (`ash-shms` for الشمس).
+    AlaLc,
+}
+
+const FATHA: char = '\u{064E}';
+const DAMMA: char = '\u{064F}';
+const KASRA: char = '\u{0650}';
+const SHADDA: char = '\u{0651}';
+const SUPERSCRIPT_ALEF: char = '\u{0670}';
+
+/// The letters the definite article `al-` assimilates to.
+const SUN_LETTERS: &str = "تثدذرزسشصضطظلن";
+
+/// A letter together with the marks that follow it.
+pub(crate) struct Unit {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) letter: char,
+    pub(crate) marks: Vec<char>,
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every run of Arabic letters with their romanization under `scheme`.
+    pub fn apply_arabic_scheme(&mut self, scheme: ArabicScheme) {
+        if scheme == ArabicScheme::Default {
+            return;
+        }
+
+        for (start, end) in arabic_words(&self.s_chars) {
+            let edges = romanize_ala_lc(&self.s_chars[start..end])
+                .into_iter()
+                .map(|(s, e, txt)| {
+                    Edge::new_regular(start + s, start + e, txt, "rom ala-lc".to_string())
+                })
+                .collect();
+            self.replace_edges_in_span(start, end, edges);
+        }
+    }
+
+    /// Writes the definite article at the start of a word as `al-` before moon letters and
+    /// assimilates it to sun letters, as in `ash-shms` for الشمس and `al-qmr` for القمر.
+    ///
+    /// The article is `ال` or `ٱل` followed by at least two letters; the rest of the word
+    /// keeps the rules of the romanization tables.
+    pub fn apply_arabic_article(&mut self) {
+        for (start, end) in arabic_words(&self.s_chars) {
+            let units = split_units(&self.s_chars[start..end]);
+            let [alif, lam, next, _, ..] = &units[..] else {
+                continue;
+            };
+            if !matches!(alif.letter, 'ا' | 'ٱ') || lam.letter != 'ل' {
+                continue;
+            }
+
+            let txt = if SUN_LETTERS.contains(next.letter) {
+                let pos = start + next.start;
+                let Some(sun) = self.best_edge_in_span(pos, pos + 1, true) else {
+                    continue;
+                };
+                format!("a{}-", sun.txt())
+            } else {
+                "al-".to_string()
+            };
+            let (article_start, article_end) = (start + alif.start, start + lam.end);
+            let edge =
+                Edge::new_regular(article_start, article_end, txt, "rom article".to_string());
+            self.replace_edges_in_span(article_start, article_end, vec![edge]);
+        }
+    }
+
+    /// Writes the harakat of vocalized Arabic words that the romanization tables drop:
+    /// shaddah doubles its consonant, tanwīn is written as `an`, `un` or `in`, tāʼ marbūṭah
+    /// becomes `t` before a vowel, and a long vowel letter after its short vowel is merged
+    /// into it (e.g., مَدِينَةٌ becomes `madinatun` rather than `madiynaa`).
+    ///
+    /// Words without harakat are left unchanged.
+    pub fn apply_arabic_diacritics(&mut self) {
+        for (start, end) in arabic_words(&self.s_chars) {
+            let units = split_units(&self.s_chars[start..end]);
+            if !units.iter().any(|unit| vowel(&unit.marks).is_some()) {
+                continue;
+            }
+
+            // The shaddah of a sun letter after an assimilated article is already written by
+            // the article.
+            let assimilated = units.len() > 2
+                && self
+                    .edge_lattice
+                    .get(&(start + units[0].start, start + units[1].end))
+                    .is_some_and(|edges| edges.iter().any(|edge| edge.r#type() == "rom article"));
+
+            let mut edges = Vec::new();
+            for (i, unit) in units.iter().enumerate() {
+                let pos = start + unit.start;
+                let prev_vowel = i.checked_sub(1).and_then(|prev| vowel(&units[prev].marks));
+                let txt = match unit.letter {
+                    'ا' if matches!(prev_vowel, Some("a" | "an")) => Some(String::new()),
+                    'ى' if prev_vowel == Some("a") => Some(String::new()),
+                    'و' if unit.marks.is_empty() && prev_vowel == Some("u") => Some(String::new()),
+                    'ي' if unit.marks.is_empty() && prev_vowel == Some("i") => Some(String::new()),
+                    'ة' if vowel(&unit.marks).is_some() => Some("t".to_string()),
+                    _ if unit.marks.contains(&SHADDA) && !(assimilated && i == 2) => self
+                        .best_edge_in_span(pos, pos + 1, true)
+                        .map(|edge| edge.txt().repeat(2)),
+                    _ => None,
+                };
+                if let Some(txt) = txt {
+                    edges.push((pos, txt));
+                }
+                for (j, &mark) in unit.marks.iter().enumerate() {
+                    if let Some(tanwin @ ("an" | "un" | "in")) = vowel(&[mark]) {
+                        edges.push((pos + 1 + j, tanwin.to_string()));
+                    }
+                }
+            }
+            for (pos, txt) in edges {
+                let edge = Edge::new_regular(pos, pos + 1, txt, "rom diacritics".to_string());
+                self.replace_edges_in_span(pos, pos + 1, vec![edge]);
+            }
+        }
+    }
+}
+
+/// Returns the `(start, end)` spans of the runs of Arabic letters and their marks in `chars`.
+pub(crate) fn arabic_words(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        if !is_arabic_letter(chars[start]) {
+            start += 1;
+            continue;
+        }
+        let end = (start..chars.len())
+            .find(|&i| !is_arabic_letter(chars[i]) && !is_arabic_mark(chars[i]))
+            .unwrap_or(chars.len());
+        words.push((start, end));
+        start = end;
+    }
+    words
+}
+
+/// Splits an Arabic word into its letters, each with the marks that follow it.
+pub(crate) fn split_units(word: &[char]) -> Vec<Unit> {
+    let mut units: Vec<Unit> = Vec::new();
+    for (i, &c) in word.iter().enumerate() {
+        match units.last_mut() {
+            Some(unit) if is_arabic_mark(c) => {
+                unit.marks.push(c);
+                unit.end = i + 1;
+            }
+            _ => units.push(Unit {
+                start: i,
+                end: i + 1,
+                letter: c,
+                marks: Vec::new(),
+            }),
+        }
+    }
+    units
+}
+
+fn is_arabic_letter(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0621}'..='\u{064A}' | '\u{0671}' | 'پ' | 'چ' | 'ژ' | 'گ'
+    )
+}
+
+fn is_arabic_mark(c: char) -> bool {
+    matches!(c, '\u{064B}'..='\u{0652}' | SUPERSCRIPT_ALEF)
+}
+
+/// Romanizes an Arabic word into `(start, end, txt)` spans relative to the word.
+fn romanize_ala_lc(word: &[char]) -> Vec<(usize, usize, String)> {
+    let units = split_units(word);
+    let mut spans: Vec<(usize, usize, String)> = Vec::new();
+    let mut rest = &units[..];
+
+    // The definite article, assimilated to a following sun letter.
+    let mut assimilated = false;
+    if let [alif, lam, next, ..] = rest
+        && matches!(alif.letter, 'ا' | 'ٱ')
+        && lam.letter == 'ل'
+    {
+        let txt = if SUN_LETTERS.contains(next.letter) {
+            assimilated = true;
+            format!("a{}-", consonant(next.letter))
+        } else {
+            "al-".to_string()
+        };
+        spans.push((alif.start, lam.end, txt));
+        rest = &rest[2..];
+    }
+
+    let word_start = spans.is_empty();
+    for (i, unit) in rest.iter().enumerate() {
+        let initial = word_start && i == 0;
+        let prev = spans.last_mut().map(|(_, _, txt)| txt);
+        let doubled = unit.marks.contains(&SHADDA) && !(assimilated && i == 0);
+
+        let txt = match unit.letter {
+            'ا' | 'ٱ' if initial => vowel(&unit.marks).unwrap_or("a").to_string(),
+            'ا' => match prev {
+                // An alif after tanwīn fatḥ is silent.
+                Some(prev) if prev.ends_with("an") => String::new(),
+                Some(prev) if prev.ends_with('a') => {
+                    prev.pop();
+                    "ā".to_string()
+                }
+                _ => "ā".to_string(),
+            },
+            'ى' => match prev {
+                Some(prev) if prev.ends_with('a') => {
+                    prev.pop();
+                    "á".to_string()
+                }
+                _ => "á".to_string(),
+            },
+            'و' | 'ي' if unit.marks.is_empty() => {
+                let (short, long, glide) = if unit.letter == 'و' {
+                    ('u', "ū", "w")
+                } else {
+                    ('i', "ī", "y")
+                };
+                match prev {
+                    Some(prev) if prev.ends_with(short) => {
+                        prev.pop();
+                        long.to_string()
+                    }
+                    _ => glide.to_string(),
+                }
+            }
+            'ة' => match vowel(&unit.marks) {
+                Some(v) => format!("t{v}"),
+                None => "h".to_string(),
+            },
+            'آ' => if initial { "ā" } else { "ʼā" }.to_string(),
+            'ء' | 'أ' | 'إ' | 'ؤ' | 'ئ' => {
+                let v = vowel(&unit.marks).unwrap_or(match unit.letter {
+                    'أ' if initial => "a",
+                    'إ' if initial => "i",
+                    _ => "",
+                });
+                if initial {
+                    v.to_string()
+                } else {
+                    format!("ʼ{v}")
+                }
+            }
+            'ـ' => String::new(),
+            letter => {
+                let c = consonant(letter);
+                let mut txt = if doubled { c.repeat(2) } else { c.to_string() };
+                txt.push_str(vowel(&unit.marks).unwrap_or(""));
+                txt
+            }
+        };
+        spans.push((unit.start, unit.end, txt));
+    }
+    spans
+}
+
+/// Returns the vowel written by the harakat in `marks`, if any.
+fn vowel(marks: &[char]) -> Option<&'static str> {
+    marks.iter().find_map(|&mark| match mark {
+        '\u{064B}' => Some("an"),
+        '\u{064C}' => Some("un"),
+        '\u{064D}' => Some("in"),
+        FATHA => Some("a"),
+        DAMMA => Some("u"),
+        KASRA => Some("i"),
+        SUPERSCRIPT_ALEF => Some("ā"),
+        _ => None,
+    })
+}
+
+fn consonant(letter: char) -> &'static str {
+    match letter {
+        'ب' => "b",
+        'ت' => "t",
+        'ث' => "th",
+        'ج' => "j",
+        'ح' => "ḥ",
+        'خ' => "kh",
+        'د' => "d",
+        'ذ' => "dh",
+        'ر' => "r",
+        'ز' => "z",
+        'س' => "s",
+        'ش' => "sh",
+        'ص' => "ṣ",
+        'ض' => "ḍ",
+        'ط' => "ṭ",
+        'ظ' => "ẓ",
+        'ع' => "ʻ",
+        'غ' => "gh",
+        'ف' => "f",
+        'ق' => "q",
+        'ك' => "k",
+        'ل' => "l",
+        'م' => "m",
+        'ن' => "n",
+        'ه' => "h",
+        'و' => "w",
+        'ي' => "y",
+        'پ' => "p",
+        'چ' => "ch",
+        'ژ' => "zh",
+        'گ' => "g",
+        _ => "",
+    }
+}
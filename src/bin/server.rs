@@ -0,0 +1,123 @@
+//! HTTP server for uroman-rs.
+//!
+//! Serves `POST /romanize` with a JSON body `{"text": ..., "lcode": ..., "format": ...}`,
+//! where `lcode` and `format` are optional. The response is the romanized string, the
+//! array of edges for the `edges`, `alts` and `lattice` formats, or the array of lattice
+//! nodes for the `lattice_nodes` format, as JSON.
+//!
+//! The server listens on the address given as the first argument, `127.0.0.1:8080` by
+//! default, and handles requests on one thread per CPU core with a shared `Uroman`.
+//! Request bodies larger than `MAX_BODY` are rejected with status 413.
+
+use serde::Deserialize;
+use std::io::Read;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+use uroman::{RomFormat, Uroman};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// The largest request body that is read, in bytes.
+const MAX_BODY: usize = 1 << 20;
+
+#[derive(Deserialize)]
+struct RomanizeRequest {
+    text: String,
+    lcode: Option<String>,
+    format: Option<String>,
+}
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("Error: Failed to listen on {address}: {err}");
+            std::process::exit(1);
+        }
+    };
+    let uroman = Uroman::new();
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    eprintln!("Listening on http://{address}");
+
+    thread::scope(|scope| {
+        for _ in 0..n_threads {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    handle(&uroman, request);
+                }
+            });
+        }
+    });
+}
+
+fn handle(uroman: &Uroman, mut request: Request) {
+    let (status, body) = if request.url() != "/romanize" {
+        (404, error_body("Not found"))
+    } else if *request.method() != Method::Post {
+        (405, error_body("Method not allowed; use POST"))
+    } else if request.body_length().is_some_and(|len| len > MAX_BODY) {
+        (413, error_body("Request body too large"))
+    } else {
+        // A body without a `Content-Length` is read up to one byte past the limit.
+        let mut body = String::new();
+        match request
+            .as_reader()
+            .take(MAX_BODY as u64 + 1)
+            .read_to_string(&mut body)
+        {
+            Ok(len) if len > MAX_BODY => (413, error_body("Request body too large")),
+            Ok(_) => romanize(uroman, &body),
+            Err(err) => (
+                400,
+                error_body(&format!("Failed to read the request: {err}")),
+            ),
+        }
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(err) = request.respond(response) {
+        eprintln!("Error: Failed to send a response: {err}");
+    }
+}
+
+fn romanize(uroman: &Uroman, body: &str) -> (u16, String) {
+    let request: RomanizeRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => return (400, error_body(&format!("Invalid request: {err}"))),
+    };
+    let rom_format = match request.format.as_deref().unwrap_or("str") {
+        "str" => RomFormat::Str,
+        "edges" => RomFormat::Edges,
+        "alts" => RomFormat::Alts,
+        "lattice" => RomFormat::Lattice,
+        "lattice_nodes" => RomFormat::LatticeNodes,
+        _ => {
+            return (
+                400,
+                error_body(
+                    "Invalid format. Must be 'str', 'edges', 'alts', 'lattice' or 'lattice_nodes'.",
+                ),
+            );
+        }
+    };
+
+    let result =
+        uroman.romanize_with_format(&request.text, request.lcode.as_deref(), Some(rom_format));
+    match serde_json::to_string(&result) {
+        Ok(body) => (200, body),
+        Err(err) => (
+            500,
+            error_body(&format!("Failed to serialize the result: {err}")),
+        ),
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
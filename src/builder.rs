@@ -0,0 +1,599 @@
+//! Defines the `UromanBuilder` used to configure a `Uroman` instance before construction.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::arabic::ArabicScheme;
+use crate::cache::TokenCache;
+use crate::chinese::{ChineseScheme, ChineseTones, Jyutping};
+use crate::core::UromanInner;
+use crate::cyrillic::CyrillicScheme;
+use crate::fallback::Fallback;
+use crate::georgian::GeorgianScheme;
+use crate::greek::GreekScheme;
+use crate::indic::IndicScheme;
+use crate::ipa::OutputTarget;
+use crate::japanese::{JapaneseScheme, LongVowelStyle};
+use crate::korean::KoreanScheme;
+use crate::options::{InvalidUtf8, Normalization, NumericMode, RomanizationOptions};
+use crate::scheme::Scheme;
+use crate::symbol::EmojiPolicy;
+use crate::tibetan::TibetanScheme;
+use crate::{GLOBAL_UROMAN_INNER, Uroman};
+
+/// Errors that can occur when building a `Uroman` instance.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("Unknown language code: '{0}'")]
+    UnknownLanguage(String),
+
+    #[error("Invalid compiled data: {0}")]
+    InvalidCompiledData(String),
+
+    #[error("Compiled data version mismatch: expected {expected}, found {found}")]
+    CompiledVersionMismatch { expected: String, found: String },
+
+    #[error("Unknown scheme '{scheme}' for language code '{lcode}'")]
+    UnknownScheme { lcode: String, scheme: String },
+
+    /// A numeric option is out of its range (e.g., a `max_expansion_ratio` of `0.0`).
+    #[error("Invalid value {value} for the option '{option}'")]
+    InvalidOption { option: &'static str, value: String },
+}
+
+/// A builder for configuring a `Uroman` instance.
+///
+/// # Example
+/// ```
+/// # use uroman::{Uroman, rom_format};
+/// let uroman = Uroman::builder()
+///     .with_languages(&["ukr", "rus"])
+///     .case_sensitive(false)
+///     .build()
+///     .unwrap();
+///
+/// let result = uroman.romanize_string::<rom_format::Str>("Київ", Some("ukr"));
+/// assert_eq!(result.to_string(), "kyiv");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UromanBuilder {
+    languages: Option<Vec<String>>,
+    options: RomanizationOptions,
+    cache_capacity: Option<usize>,
+    schemes: HashMap<String, String>,
+}
+
+impl UromanBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the language-specific rules to the given ISO 639-3 codes.
+    ///
+    /// Rules that apply to all languages are always kept. Rules restricted to other
+    /// languages are dropped. The instance loads its own tables from the embedded data
+    /// rather than sharing those of `Uroman::new()`, so that the rules it drops are never
+    /// held in memory unless another instance without this restriction is created, or a
+    /// dropped language is restored by `Uroman::enable_language`.
+    pub fn with_languages(mut self, lcodes: &[&str]) -> Self {
+        self.languages = Some(lcodes.iter().map(|lcode| lcode.to_string()).collect());
+        self
+    }
+
+    /// If set to `false`, the romanized output is folded to lowercase (default: `true`).
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.options.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Sets the character emitted for syllable-boundary apostrophes (default: `'`).
+    ///
+    /// Apostrophes that are already present in the input are left untouched. This is
+    /// shorthand for `syllable_separator(Some(apostrophe))`.
+    pub fn apostrophe(mut self, apostrophe: char) -> Self {
+        self.options.syllable_separator = Some(apostrophe);
+        self
+    }
+
+    /// Sets the character emitted for the apostrophes that the rules of every language
+    /// produce, such as at ambiguous syllable boundaries (`kin'en` in Hepburn), or
+    /// `None` to omit them (default: `Some('\'')`).
+    ///
+    /// Apostrophes that are already present in the input are left untouched.
+    pub fn syllable_separator(mut self, separator: Option<char>) -> Self {
+        self.options.syllable_separator = separator;
+        self
+    }
+
+    /// If set to `true`, diacritics are stripped from the romanized output (default: `false`).
+    ///
+    /// The output is NFD-decomposed and its combining marks are removed, so `ā` becomes
+    /// `a` and `ñ` becomes `n`. Characters that do not fold to ASCII (e.g., `ø`) are kept.
+    pub fn ascii_fold(mut self, ascii_fold: bool) -> Self {
+        self.options.ascii_fold = ascii_fold;
+        self
+    }
+
+    /// If set to `true`, capitalization is carried over from the source (default: `false`).
+    ///
+    /// When the first letter of an edge's source is uppercase, the first letter of its
+    /// output is uppercased and the remaining letters are lowercased, so `Θ` becomes `Th`
+    /// rather than `TH`. Edges of uncased scripts are unaffected. This is applied after
+    /// `case_sensitive(false)` and therefore takes precedence over it.
+    pub fn preserve_case(mut self, preserve_case: bool) -> Self {
+        self.options.preserve_case = preserve_case;
+        self
+    }
+
+    /// Sets the Unicode normalization applied to the input before rule matching
+    /// (default: `Normalization::None`).
+    ///
+    /// Use `Normalization::Nfc` to romanize precomposed and decomposed input alike.
+    /// Edge offsets then refer to the normalized text rather than the input.
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.options.normalization = normalization;
+        self
+    }
+
+    /// If set to `true`, the Halfwidth and Fullwidth Forms block (U+FF00..U+FFEF) of the
+    /// input is mapped to its standard forms before rule matching (default: `false`).
+    ///
+    /// Fullwidth letters, digits and punctuation become ASCII, so fullwidth digits form
+    /// regular numeric edges. Halfwidth katakana become fullwidth katakana, so the Japanese
+    /// rules apply to them. Edge offsets then refer to the mapped text.
+    pub fn normalize_fullwidth(mut self, normalize_fullwidth: bool) -> Self {
+        self.options.normalize_fullwidth = normalize_fullwidth;
+        self
+    }
+
+    /// If set to `true`, compatibility ligatures of the input are expanded to their letters
+    /// before rule matching (default: `false`).
+    ///
+    /// This covers the Latin ligatures (e.g., `ﬁ` to `fi`, `ﬆ` to `st`), the Latin digraphs
+    /// (e.g., `ĳ`, `ǆ`) and the Armenian ligatures (e.g., `ﬓ` to `մն`), without the other
+    /// effects of NFKC. The Armenian `և` is a letter of the alphabet, and is romanized
+    /// `ev` either way. Edge offsets then refer to the expanded text.
+    pub fn expand_ligatures(mut self, expand: bool) -> Self {
+        self.options.expand_ligatures = expand;
+        self
+    }
+
+    /// If set to `true`, zero width joiners (U+200D) that only affect rendering are removed
+    /// from Indic and Arabic text before rule matching (default: `false`).
+    ///
+    /// A joiner after a virama selects the half form of a consonant rather than a conjunct
+    /// (e.g., `"क्\u{200D}ष"`), and a joiner after an Arabic letter selects a joining form. Neither
+    /// changes the sound, so the rules for the cluster apply across the removed joiner.
+    /// Zero width non-joiners (U+200C) are always kept, so that one between the letters
+    /// of a conjunct prevents the rules for the conjunct from applying, as it prevents the
+    /// conjunct from being rendered; they are then romanized as nothing. Joiners in emoji
+    /// sequences are kept too. Edge offsets then refer to the text without the joiners.
+    pub fn normalize_joiners(mut self, normalize: bool) -> Self {
+        self.options.normalize_joiners = normalize;
+        self
+    }
+
+    /// If set to `true`, spaces are inserted between the words of Thai, Lao and Khmer text
+    /// when `lcode` is `tha`, `lao` or `khm` (default: `false`).
+    ///
+    /// These scripts are written without spaces between words. Words are found by maximal
+    /// matching against a bundled list of common words, and characters outside known words
+    /// are kept together, so the segmentation of rare words and names is approximate. The
+    /// input is not changed: each space is output by an edge that covers no characters,
+    /// so edge offsets still refer to the input.
+    pub fn segment_words(mut self, segment: bool) -> Self {
+        self.options.segment_words = segment;
+        self
+    }
+
+    /// Romanizes kana under `scheme` when `lcode` is `jpn` (default: the table rules).
+    ///
+    /// Other languages, and kana romanized without `lcode` `jpn`, are unaffected.
+    pub fn japanese_scheme(mut self, scheme: JapaneseScheme) -> Self {
+        self.options.japanese_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets how long vowels of Japanese are written when `lcode` is `jpn`, overriding the
+    /// convention of the `japanese_scheme`.
+    ///
+    /// Without a `japanese_scheme`, kana are romanized with `JapaneseScheme::Hepburn`.
+    pub fn long_vowels(mut self, style: LongVowelStyle) -> Self {
+        self.options.long_vowels = Some(style);
+        self
+    }
+
+    /// Romanizes Hangul under `scheme` when `lcode` is `kor` (default: jamo by jamo).
+    ///
+    /// Unlike the default, both schemes transcribe the sound changes between syllables
+    /// (e.g., 독립문 becomes `dongnimmun` rather than `dogribmun`).
+    pub fn korean_scheme(mut self, scheme: KoreanScheme) -> Self {
+        self.options.korean_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets how pinyin tones are written when `lcode` is `zho` (default: `ChineseTones::None`).
+    ///
+    /// Each Han character gets the citation tone of its reading; tone sandhi and neutral
+    /// tones in context are not applied (e.g., 你好 becomes `nǐhǎo`).
+    pub fn chinese_tones(mut self, tones: ChineseTones) -> Self {
+        self.options.chinese_tones = tones;
+        self
+    }
+
+    /// Sets the romanization of Mandarin when `lcode` is `zho` (default: `ChineseScheme::Pinyin`).
+    ///
+    /// Under `ChineseScheme::WadeGiles`, the syllables of a run of Han characters are
+    /// joined with hyphens and `chinese_tones` is ignored (e.g., 北京 becomes `pei-ching`).
+    pub fn chinese_scheme(mut self, scheme: ChineseScheme) -> Self {
+        self.options.chinese_scheme = scheme;
+        self
+    }
+
+    /// Sets the romanization of Georgian when `lcode` is `kat` (default: the table rules).
+    ///
+    /// The schemes differ in the ejective consonants: `GeorgianScheme::National` writes
+    /// ჭ as `ch'`, while `GeorgianScheme::Iso` writes it as `č̣`.
+    pub fn georgian_scheme(mut self, scheme: GeorgianScheme) -> Self {
+        self.options.georgian_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets the romanization of Greek when `lcode` is `ell` or `grc` (default: the table rules).
+    ///
+    /// `GreekScheme::Classical` writes the breathings and iota subscripts of polytonic
+    /// Greek (e.g., ᾅδης becomes `haidēs`), while `GreekScheme::ModernBgnPcgn` follows
+    /// Modern Greek pronunciation (e.g., `adis`).
+    pub fn greek_scheme(mut self, scheme: GreekScheme) -> Self {
+        self.options.greek_scheme = Some(scheme);
+        self
+    }
+
+    /// Romanizes Han characters in Jyutping when `lcode` is `yue` (default: pinyin).
+    ///
+    /// The Cantonese readings cover about 270 common characters only (e.g., 香港 becomes
+    /// `hoeng1gong2` with `Jyutping::ToneNumbers`). Other characters keep their pinyin, on
+    /// edges of the type `rom mandarin-fallback` that tell them apart.
+    pub fn jyutping(mut self, jyutping: Jyutping) -> Self {
+        self.options.jyutping = Some(jyutping);
+        self
+    }
+
+    /// Romanizes Arabic under `scheme` when `lcode` is `ara` (default: `ArabicScheme::Default`).
+    ///
+    /// Under `ArabicScheme::AlaLc`, the definite article is assimilated to sun letters
+    /// (e.g., الشَّمْس becomes `ash-shams`). Short vowels are only written where the input
+    /// carries harakat.
+    pub fn arabic_scheme(mut self, scheme: ArabicScheme) -> Self {
+        self.options.arabic_scheme = scheme;
+        self
+    }
+
+    /// If set to `true`, the harakat of vocalized Arabic are fully written when `lcode` is
+    /// `ara` (default: `false`).
+    ///
+    /// The romanization tables already write fatḥah, ḍammah and kasrah (e.g., كَتَبَ becomes
+    /// `kataba`), but drop shaddah and tanwīn and spell out long vowel letters after their
+    /// short vowel. With this option, مُحَمَّد becomes `muhammad` rather than `muhamad`, and
+    /// كِتَابٌ becomes `kitabun` rather than `kitaab`. Words without harakat keep their
+    /// consonant skeleton. `ArabicScheme::AlaLc` always writes harakat and ignores this option.
+    pub fn arabic_use_diacritics(mut self, arabic_use_diacritics: bool) -> Self {
+        self.options.arabic_use_diacritics = arabic_use_diacritics;
+        self
+    }
+
+    /// If set to `true`, the definite article is assimilated to sun letters when `lcode` is
+    /// `ara` (default: `false`).
+    ///
+    /// The article is written `al-` before moon letters and takes the sun letter's
+    /// consonant before sun letters (e.g., الشمس becomes `ash-shms` and القمر `al-qmr`).
+    /// With `arabic_use_diacritics`, the shaddah of the sun letter is written by the
+    /// article (e.g., الشَّمْس becomes `ash-shams`). `ArabicScheme::AlaLc` always assimilates
+    /// the article and ignores this option.
+    pub fn arabic_assimilate_article(mut self, arabic_assimilate_article: bool) -> Self {
+        self.options.arabic_assimilate_article = arabic_assimilate_article;
+        self
+    }
+
+    /// If set to `true`, Hebrew is romanized from its niqqud when `lcode` is `heb`
+    /// (default: `false`).
+    ///
+    /// Vocalized words are written with their vowels, and a dagesh distinguishes `b`, `k` and
+    /// `p` from `v`, `kh` and `f` (e.g., בָּרוּךְ becomes `barukh` rather than `baruke`). Words
+    /// without vowel points keep their consonant skeleton (e.g., ברוך becomes `brvkh`).
+    pub fn hebrew_niqqud(mut self, hebrew_niqqud: bool) -> Self {
+        self.options.hebrew_niqqud = hebrew_niqqud;
+        self
+    }
+
+    /// If set to `true`, the traditional Mongolian script is transliterated as in scholarly
+    /// works on Classical Mongolian when `lcode` is `mon` (default: `false`).
+    ///
+    /// ᠬ and ᠭ follow the vowel harmony of the word, as `q` and `γ` in words with back
+    /// vowels and `k` and `g` otherwise (e.g., ᠮᠣᠩᠭᠣᠯ becomes `mongγol` and ᠭᠡᠷ `ger`), and
+    /// ᠥ, ᠦ, ᠴ, ᠵ and ᠱ are written `ö`, `ü`, `č`, `ǰ` and `š`. The positional forms of the
+    /// letters are chosen by the font, so free variation selectors and the vowel separator
+    /// are dropped, and a suffix joined by a narrow no-break space is written after a `-`.
+    /// Mongolian in Cyrillic is unaffected.
+    pub fn traditional_mongolian(mut self, traditional_mongolian: bool) -> Self {
+        self.options.traditional_mongolian = traditional_mongolian;
+        self
+    }
+
+    /// Romanizes Cyrillic under `scheme` (default: `CyrillicScheme::Default`).
+    ///
+    /// The scheme applies to Cyrillic letters whatever the `lcode`. `CyrillicScheme::Iso9`
+    /// maps each letter to a single Latin letter, so that the romanization can be reversed
+    /// (e.g., щука becomes `ŝuka`).
+    pub fn cyrillic_scheme(mut self, scheme: CyrillicScheme) -> Self {
+        self.options.cyrillic_scheme = scheme;
+        self
+    }
+
+    /// Transliterates Devanagari under `scheme` (default: the table rules).
+    ///
+    /// The scheme applies to Devanagari whatever the `lcode`, and writes the inherent vowel
+    /// of every consonant, as in Sanskrit (e.g., संस्कृत becomes `saṃskṛta` under
+    /// `IndicScheme::Iast` and `saṁskr̥ta` under `IndicScheme::Iso15919`).
+    pub fn indic_scheme(mut self, scheme: IndicScheme) -> Self {
+        self.options.indic_scheme = Some(scheme);
+        self
+    }
+
+    /// Romanizes Tibetan under `scheme` when `lcode` is `bod` (default: letter by letter).
+    ///
+    /// Unlike the default, the scheme finds the root letter of each syllable to place its
+    /// inherent vowel (e.g., བཀྲ་ཤིས་ becomes `bkra shis` rather than `bakr·shis·`).
+    pub fn tibetan_scheme(mut self, scheme: TibetanScheme) -> Self {
+        self.options.tibetan_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets the scheme of each language code in `schemes`, by name, in place of the scheme
+    /// options for its script when a string is romanized under that code.
+    ///
+    /// | `lcode`                  | Scheme names                                   |
+    /// |--------------------------|------------------------------------------------|
+    /// | `jpn`                    | `hepburn`, `kunrei`, `wapuro`                  |
+    /// | `kor`                    | `revised`, `mccune`                            |
+    /// | `zho`                    | `pinyin`, `wade-giles`                         |
+    /// | `kat`                    | `national`, `iso`                              |
+    /// | `ell`, `grc`             | `classical`, `bgn-pcgn`                        |
+    /// | `ara`                    | `ala-lc`                                       |
+    /// | `bod`                    | `wylie`                                        |
+    /// | `rus`, `ukr`, `bul`, ... | `iso9`, `bgn-pcgn`                             |
+    /// | `hin`, `mar`, `san`, ... | `iast`, `iso15919`                             |
+    ///
+    /// The name `default` selects the table rules of the language. Since the schemes are
+    /// chosen by language code, they also apply to the runs that `romanize_segments`
+    /// romanizes under a guessed code. `build` fails with `BuildError::UnknownScheme` for
+    /// a name that is not a scheme of its language code.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use uroman::{Uroman, rom_format};
+    /// let schemes = HashMap::from([
+    ///     ("kor".to_string(), "mccune".to_string()),
+    ///     ("zho".to_string(), "wade-giles".to_string()),
+    /// ]);
+    /// let uroman = Uroman::builder().schemes(schemes).build().unwrap();
+    ///
+    /// let result = uroman.romanize_string::<rom_format::Str>("北京", Some("zho"));
+    /// assert_eq!(result.to_string(), "pei-ching");
+    /// ```
+    pub fn schemes(mut self, schemes: HashMap<String, String>) -> Self {
+        self.schemes.extend(schemes);
+        self
+    }
+
+    /// Sets how numbers are romanized (default: `NumericMode::Spell`).
+    ///
+    /// `NumericMode::AsciiDigits` maps digits one by one to ASCII (e.g., ١٬٠٠٠ becomes
+    /// `1,000`), and `NumericMode::Passthrough` leaves them and their separators as they
+    /// are. Both leave numerals written with letters or ideographs to the text rules
+    /// (e.g., 三百 becomes `sanbai`), and disable `recognize_roman_numerals`.
+    pub fn numeric_mode(mut self, mode: NumericMode) -> Self {
+        self.options.numeric_mode = mode;
+        self
+    }
+
+    /// If set to `true`, runs of whitespace in the string output are collapsed to single
+    /// ASCII spaces, and leading and trailing whitespace is trimmed from each line
+    /// (default: `false`).
+    ///
+    /// Line breaks are kept. This applies to the methods that return a string:
+    /// `rom_format::Str` and the methods built on it, such as `Uroman::romanize_into` and
+    /// `Uroman::romanize_file`, and `Uroman::romanize_checked`, `Uroman::try_romanize`,
+    /// `Uroman::romanize_cow`, `Uroman::romanize_with_diagnostics`,
+    /// `Uroman::romanize_with_alignment`, `Uroman::romanize_edits` and
+    /// `Uroman::romanize_annotated`. The `Edges`, `Alts`, `Lattice` and `Json` formats and
+    /// `Uroman::romanize_nbest` keep the whitespace as is.
+    pub fn collapse_whitespace(mut self, collapse: bool) -> Self {
+        self.options.collapse_whitespace = collapse;
+        self
+    }
+
+    /// If set to `true`, URLs (e.g., `https://example.org/путь`, `www.example.org`), email
+    /// addresses and @mentions are passed through verbatim (default: `false`).
+    ///
+    /// Each such token is covered by one edge of the type `verbatim`, which the other
+    /// output options, such as `case_sensitive` and `ascii_fold`, leave unchanged.
+    /// Punctuation at the end of a URL (e.g., a sentence-final `.`) is not part of it.
+    pub fn preserve_tokens(mut self, preserve: bool) -> Self {
+        self.options.preserve_tokens = preserve;
+        self
+    }
+
+    /// Sets how emoji and other symbols are romanized (default: `EmojiPolicy::Keep`).
+    ///
+    /// With `EmojiPolicy::Drop` or `EmojiPolicy::Name`, each emoji sequence or symbol is
+    /// covered by one edge of the type `symbol` (e.g., 😀 becomes `:grinning_face:` under
+    /// `EmojiPolicy::Name`). URLs and other tokens kept by `preserve_tokens` are unaffected.
+    pub fn emoji_policy(mut self, policy: EmojiPolicy) -> Self {
+        self.options.emoji_policy = policy;
+        self
+    }
+
+    /// Sets how `Uroman::romanize_bytes` handles bytes that are not valid UTF-8
+    /// (default: `InvalidUtf8::Replace`).
+    pub fn invalid_utf8(mut self, policy: InvalidUtf8) -> Self {
+        self.options.invalid_utf8 = policy;
+        self
+    }
+
+    /// Sets the alphabet of the output (default: `OutputTarget::Latin`).
+    ///
+    /// With `OutputTarget::Ipa`, kana, Hangul and, when `lcode` is `ara`, Arabic are
+    /// transcribed in approximate IPA, in edges of the type `ipa` (e.g., すし becomes
+    /// `sɯɕi`). Other letters are romanized as before, and the type of their edges is
+    /// prefixed with `latin` (e.g., `latin rom`) to mark them as a fallback.
+    pub fn output(mut self, output: OutputTarget) -> Self {
+        self.options.output = output;
+        self
+    }
+
+    /// Sets what is written for a character that no rule romanizes (default: `Fallback::Keep`).
+    ///
+    /// With `Fallback::Replace` or `Fallback::Drop`, each such character is covered by an
+    /// edge of the type `fallback`, so that e.g. `Fallback::Replace("?".to_string())`
+    /// yields ASCII output. ASCII characters are never replaced, and symbols are replaced
+    /// only if the `EmojiPolicy` keeps them.
+    pub fn fallback(mut self, fallback: Fallback) -> Self {
+        self.options.fallback = fallback;
+        self
+    }
+
+    /// If set to `true`, text is romanized only where `Uroman::deromanize` can map it back
+    /// (default: `false`).
+    ///
+    /// Tibetan is romanized in Extended Wylie under `bod`, and Cyrillic in ISO 9 under any
+    /// other `lcode`. Every other character is passed through unchanged, since its
+    /// romanization could not be undone, and the runs of characters that
+    /// `Uroman::deromanize` would read as a romanization, such as Latin words, are enclosed
+    /// in backslashes (e.g., `\Kyiv\`), as is each backslash (`\\`). The lossy
+    /// output options, such as `ascii_fold`, `case_sensitive` and `collapse_whitespace`,
+    /// and the other schemes are ignored in this mode.
+    pub fn reversible(mut self, reversible: bool) -> Self {
+        self.options.reversible = reversible;
+        self
+    }
+
+    /// If set to `true`, Roman numerals are romanized as numbers (default: `false`).
+    ///
+    /// Only words written entirely in uppercase that form a numeral in standard notation
+    /// are recognized (e.g., `MCMLXXXIV` becomes `1984`), which leaves ordinary words such
+    /// as `Mix` and invalid forms such as `IIII` untouched. A lone `I` is read as the pronoun,
+    /// and common uppercase words and abbreviations that happen to be numerals, such as
+    /// `MIX`, `DC` and `CV`, are read as numbers only after a cue word such as `Chapter`,
+    /// `Part` or `Vol.` (e.g., `Chapter DC` becomes `Chapter 600`).
+    /// The numeral edges have the type `roman`, and keep the letters as `Edge::orig_txt`.
+    pub fn recognize_roman_numerals(mut self, recognize_roman_numerals: bool) -> Self {
+        self.options.recognize_roman_numerals = recognize_roman_numerals;
+        self
+    }
+
+    /// Drops edges whose `Edge::score` is below `min_edge_score` before decoding (default: `0.0`).
+    ///
+    /// This speeds up decoding and limits the `Alts`, `Lattice` and `Json` formats to
+    /// plausible alternatives (e.g., `0.6` drops the `rom-alt` alternatives). Where every
+    /// edge starting at a position scores below the threshold, the highest-scoring ones
+    /// are kept, so that a full romanization always exists. `UromanBuilder::build` returns
+    /// `BuildError::InvalidOption` for a negative or non-finite score.
+    pub fn min_edge_score(mut self, min_edge_score: f32) -> Self {
+        self.options.min_edge_score = min_edge_score;
+        self
+    }
+
+    /// Caps the romanization at `ratio` times the length of the input, in bytes
+    /// (default: unbounded).
+    ///
+    /// This guards services that romanize untrusted input against output that is much
+    /// larger than the input. `Uroman::romanize_checked`, `Uroman::try_romanize` and
+    /// `Uroman::romanize_bytes` return `RomanizationError::ExpansionLimit` when the limit is
+    /// exceeded. The other methods that return a string, such as `Uroman::romanize_into`,
+    /// `Uroman::romanize_cow`, `Uroman::romanize_with_alignment` and `Uroman::romanize_file`
+    /// for each line, truncate the romanization at the last character that fits instead,
+    /// and their offsets and edits cover the truncated romanization. The `Edges`, `Alts`,
+    /// `Lattice` and `Json` formats are not limited. Use `Uroman::romanize_checked` to tell
+    /// whether a romanization was truncated. `UromanBuilder::build` returns
+    /// `BuildError::InvalidOption` for a ratio that is not positive and finite.
+    pub fn max_expansion_ratio(mut self, ratio: f32) -> Self {
+        self.options.max_expansion_ratio = Some(ratio);
+        self
+    }
+
+    /// Enables an LRU cache of the romanization of up to `capacity` tokens.
+    ///
+    /// With a cache, `romanize_string::<rom_format::Str>` (and every method built on it)
+    /// romanizes each run of whitespace and non-whitespace characters separately and
+    /// memoizes the result by `(token, lcode)`. Rules that span whitespace (e.g., some
+    /// number patterns) therefore no longer apply. The cache is shared by clones of the
+    /// instance. Use `Uroman::cache_stats` to check its effectiveness.
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Builds the `Uroman` instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BuildError::UnknownLanguage` if a code passed to `with_languages`
+    /// is not a known ISO 639-3 language code, or is the code of a language whose script
+    /// is left out by the `lang-*` features.
+    pub fn build(self) -> Result<Uroman, BuildError> {
+        if let Some(ratio) = self.options.max_expansion_ratio
+            && !(ratio.is_finite() && ratio > 0.0)
+        {
+            return Err(BuildError::InvalidOption {
+                option: "max_expansion_ratio",
+                value: ratio.to_string(),
+            });
+        }
+        let min_edge_score = self.options.min_edge_score;
+        if !(min_edge_score.is_finite() && min_edge_score >= 0.0) {
+            return Err(BuildError::InvalidOption {
+                option: "min_edge_score",
+                value: min_edge_score.to_string(),
+            });
+        }
+
+        let inner = match self.languages {
+            None => Arc::clone(&GLOBAL_UROMAN_INNER),
+            Some(languages) => {
+                // The tables are loaded anew, not copied from `GLOBAL_UROMAN_INNER`, which
+                // would keep all the rules in memory for the other instances.
+                let mut inner = UromanInner::new();
+                if let Some(unknown) =
+                    languages.iter().find(|lcode| !inner.is_known_language(lcode))
+                {
+                    return Err(BuildError::UnknownLanguage(unknown.clone()));
+                }
+
+                let languages: HashSet<String> = languages.into_iter().collect();
+                inner.retain_languages(&languages);
+                Arc::new(inner)
+            }
+        };
+
+        let mut options = self.options;
+        let mut schemes: Vec<(String, String)> = self.schemes.into_iter().collect();
+        schemes.sort();
+        for (lcode, name) in schemes {
+            let Some(scheme) = Scheme::parse(&lcode, &name) else {
+                return Err(BuildError::UnknownScheme { lcode, scheme: name });
+            };
+            options.schemes.insert(lcode, scheme);
+        }
+
+        Ok(Uroman {
+            inner,
+            options,
+            cache: self
+                .cache_capacity
+                .map(|capacity| Arc::new(TokenCache::new(capacity))),
+        })
+    }
+}
@@ -0,0 +1,105 @@
+//! Defines the opt-in LRU cache for romanized tokens.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type CacheKey = (String, Option<String>);
+
+/// Hit and miss counts of a `Uroman` token cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A thread-safe least-recently-used cache of romanized tokens, keyed by `(token, lcode)`.
+#[derive(Debug)]
+pub(crate) struct TokenCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// Maps a key to its romanization and the tick of its last use.
+    entries: HashMap<CacheKey, (String, u64)>,
+    /// Maps the tick of the last use to its key, oldest first.
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+impl TokenCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lru: Mutex::new(Lru::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached romanization of `token`, computing and inserting it on a miss.
+    ///
+    /// The lock is not held while `romanize` runs, so concurrent misses of the same
+    /// token may both compute it.
+    pub(crate) fn get_or_insert_with(
+        &self,
+        token: &str,
+        lcode: Option<&str>,
+        romanize: impl FnOnce() -> String,
+    ) -> String {
+        let key = (token.to_string(), lcode.map(str::to_string));
+
+        if let Some(rom) = self.lru.lock().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return rom;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let rom = romanize();
+        if self.capacity > 0 {
+            self.lru.lock().unwrap().insert(key, rom.clone(), self.capacity);
+        }
+        rom
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Lru {
+    fn get(&mut self, key: &CacheKey) -> Option<String> {
+        self.tick += 1;
+        let (rom, last_used) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(last_used).expect("LRU recency out of sync");
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(rom.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, rom: String, capacity: usize) {
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (rom, self.tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.tick, key);
+
+        while self.entries.len() > capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
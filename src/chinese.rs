@@ -0,0 +1,366 @@
+//! Adds the tones of Mandarin readings to the pinyin of Han characters, romanizes
+//! Mandarin in Wade-Giles, and romanizes Cantonese with Jyutping.
+//!
+//! The readings are the citation forms of `Chinese_to_Pinyin.txt` and
+//! `Chinese_to_Jyutping.txt`, one per character. Tone sandhi and neutral tones in
+//! context are not applied.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// How the tones of pinyin are written when `lcode` is `zho`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChineseTones {
+    /// Tones are dropped (`zhongguo` for 中国).
+    #[default]
+    None,
+    /// Tone marks over the vowels (`zhōngguó`).
+    Marks,
+    /// A tone number after each syllable (`zhong1guo2`), with `v` for `ü`.
+    /// Syllables in the neutral tone have no number.
+    Numbers,
+}
+
+/// The romanization of Mandarin used when `lcode` is `zho`.
+///
+/// | Hanzi | `Pinyin`  | `WadeGiles` |
+/// |-------|-----------|-------------|
+/// | 北京  | beijing   | pei-ching   |
+/// | 台北  | taibei    | t'ai-pei    |
+/// | 广州  | guangzhou | kuang-chou  |
+/// | 学习  | xuexi     | hsüeh-hsi   |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChineseScheme {
+    /// Hanyu Pinyin, the official romanization of the People's Republic of China.
+    #[default]
+    Pinyin,
+    /// Wade-Giles, with `'` for aspirated initials (`t'ai`), `ê` and `ü` where written,
+    /// and hyphens between the syllables of a run of Han characters. Tones are not written.
+    WadeGiles,
+}
+
+/// How Han characters are romanized in Jyutping when `lcode` is `yue`.
+///
+/// The Cantonese readings of `Chinese_to_Jyutping.txt` cover only about 270 common
+/// characters. The other Han characters keep their Mandarin pinyin, on edges of the type
+/// `rom mandarin-fallback`, so that text with them comes out as a mix of Cantonese and
+/// Mandarin readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Jyutping {
+    /// Tones are dropped (`hoenggong` for 香港).
+    Toneless,
+    /// A tone number after each syllable (`hoeng1gong2`).
+    ToneNumbers,
+}
+
+impl Lattice<'_> {
+    /// Replaces the romanization of Han characters with their toned readings.
+    ///
+    /// Edges spanning several characters (e.g., for 北京) get the concatenated readings
+    /// of their characters. Numeric edges (e.g., for 三百) are kept.
+    pub fn apply_chinese_tones(&mut self, tones: ChineseTones) {
+        if tones == ChineseTones::None {
+            return;
+        }
+
+        let inner = self.uroman;
+        let readings: Vec<Option<&str>> = self
+            .s_chars
+            .iter()
+            .map(|c| inner.toned_pinyin.get(c).map(String::as_str))
+            .collect();
+        if readings.iter().all(Option::is_none) {
+            return;
+        }
+
+        let mut toned_edges = Vec::new();
+        self.retain_edges(|edge| {
+            if edge.is_numeric() || !edge.r#type().starts_with("rom") {
+                return true;
+            }
+            let mut toned = String::new();
+            for i in edge.start()..edge.end() {
+                let Some(reading) = readings[i] else {
+                    return true;
+                };
+                // As in pinyin words, `'` separates a syllable starting with a, o or e.
+                if i > 0
+                    && readings[i - 1].is_some()
+                    && reading.nfd().next().is_some_and(|c| "aoe".contains(c))
+                {
+                    toned.push('\'');
+                }
+                match tones {
+                    ChineseTones::Numbers => toned.push_str(&marks_to_numbers(reading)),
+                    _ => toned.push_str(reading),
+                }
+            }
+            toned_edges.push(Edge::new_regular(
+                edge.start(),
+                edge.end(),
+                toned,
+                edge.r#type().to_string(),
+            ));
+            false
+        });
+        for edge in toned_edges {
+            self.add_edge(edge);
+        }
+    }
+}
+
+impl Lattice<'_> {
+    /// Replaces the pinyin of Han characters with their Wade-Giles romanization.
+    ///
+    /// A hyphen is written before each syllable that follows another converted syllable,
+    /// so that a run of characters reads as one hyphenated word. Numeric edges, such as
+    /// the `4` of 四, are kept. Within the edge of a word, such as 百度 (`pai-tu`), every
+    /// syllable after the first is hyphenated. An edge that follows a character that is
+    /// also read as a number (e.g., the 四 of 四川) is not hyphenated to it, since the
+    /// number may be chosen instead.
+    pub fn apply_wade_giles(&mut self) {
+        let inner = self.uroman;
+        let readings: Vec<Option<String>> = self
+            .s_chars
+            .iter()
+            .map(|c| {
+                inner
+                    .toned_pinyin
+                    .get(c)
+                    .map(|pinyin| pinyin_to_wade_giles(pinyin))
+            })
+            .collect();
+        if readings.iter().all(Option::is_none) {
+            return;
+        }
+
+        let mut spans = Vec::new();
+        let mut numeric = HashSet::new();
+        self.retain_edges(|edge| {
+            if edge.is_numeric() {
+                numeric.extend(edge.start()..edge.end());
+                return true;
+            }
+            if !edge.r#type().starts_with("rom")
+                || readings[edge.start()..edge.end()]
+                    .iter()
+                    .any(Option::is_none)
+            {
+                return true;
+            }
+            spans.push((edge.start(), edge.end()));
+            false
+        });
+
+        // Characters that are also read as numbers, such as 四, may be written as digits
+        // where their own edge is chosen, but are syllables within the edge of a word.
+        let converted: HashSet<usize> = spans
+            .iter()
+            .flat_map(|&(start, end)| start..end)
+            .filter(|i| !numeric.contains(i))
+            .collect();
+        for (start, end) in spans {
+            let mut rom = String::new();
+            for (i, reading) in readings.iter().enumerate().take(end).skip(start) {
+                if i > start || (i > 0 && converted.contains(&(i - 1))) {
+                    rom.push('-');
+                }
+                rom.extend(reading.as_deref());
+            }
+            self.add_edge(Edge::new_regular(
+                start,
+                end,
+                rom,
+                "rom wade-giles".to_string(),
+            ));
+        }
+    }
+}
+
+/// Converts a pinyin syllable, with or without tone marks, to Wade-Giles.
+fn pinyin_to_wade_giles(pinyin: &str) -> String {
+    // Drops the tone marks, keeping the diaeresis of `ü`.
+    let syllable: String = pinyin
+        .nfd()
+        .filter(|c| !matches!(c, '\u{0304}' | '\u{0301}' | '\u{030C}' | '\u{0300}'))
+        .nfc()
+        .collect::<String>()
+        .to_lowercase();
+
+    match syllable.as_str() {
+        "zhi" => return "chih".to_string(),
+        "chi" => return "ch'ih".to_string(),
+        "shi" => return "shih".to_string(),
+        "ri" => return "jih".to_string(),
+        "zi" => return "tzu".to_string(),
+        "ci" => return "tz'u".to_string(),
+        "si" => return "ssu".to_string(),
+        "e" => return "o".to_string(),
+        // 儿 is read `r` as a suffix, and `er` on its own.
+        "er" | "r" => return "erh".to_string(),
+        "yi" => return "i".to_string(),
+        "you" => return "yu".to_string(),
+        _ => {}
+    }
+
+    let (initial, final_) = ["zh", "ch", "sh"]
+        .iter()
+        .find(|initial| syllable.starts_with(*initial))
+        .map(|initial| syllable.split_at(initial.len()))
+        .or_else(|| {
+            syllable
+                .chars()
+                .next()
+                .filter(|c| "bpmfdtnlgkhjqxrzcsyw".contains(*c))
+                .map(|c| syllable.split_at(c.len_utf8()))
+        })
+        .unwrap_or(("", &syllable));
+
+    // After `j`, `q`, `x` and `y`, pinyin writes `ü` as `u`.
+    let final_ = match initial {
+        "j" | "q" | "x" | "y" if final_.starts_with('u') => format!("ü{}", &final_[1..]),
+        _ => final_.to_string(),
+    };
+    let final_ = match final_.as_str() {
+        // `y` + `an` and `e` become `yen` and `yeh`.
+        "an" if initial == "y" => "en".to_string(),
+        "e" if initial == "y" => "eh".to_string(),
+        "e" if matches!(initial, "g" | "k" | "h") => "o".to_string(),
+        "e" => "ê".to_string(),
+        "en" => "ên".to_string(),
+        "eng" => "êng".to_string(),
+        "ong" => "ung".to_string(),
+        "iong" => "iung".to_string(),
+        "ian" => "ien".to_string(),
+        "ie" => "ieh".to_string(),
+        "üe" | "ue" => "üeh".to_string(),
+        "uo" if !matches!(initial, "g" | "k" | "h" | "sh" | "w") => "o".to_string(),
+        "ui" if matches!(initial, "g" | "k") => "uei".to_string(),
+        _ => final_,
+    };
+
+    let initial = match initial {
+        "b" => "p",
+        "p" => "p'",
+        "d" => "t",
+        "t" => "t'",
+        "g" => "k",
+        "k" => "k'",
+        "j" | "zh" => "ch",
+        "q" | "ch" => "ch'",
+        "x" => "hs",
+        "r" => "j",
+        "z" => "ts",
+        "c" => "ts'",
+        initial => initial,
+    };
+    format!("{initial}{final_}")
+}
+
+impl Lattice<'_> {
+    /// Replaces the pinyin of Han characters with their Cantonese readings in Jyutping.
+    ///
+    /// Like `apply_chinese_tones`, edges spanning several characters get the concatenated
+    /// readings of their characters. Edges with a Han character that has no Cantonese
+    /// reading keep their pinyin and get the type `rom mandarin-fallback`, and numeric
+    /// edges are kept.
+    pub fn apply_jyutping(&mut self, jyutping: Jyutping) {
+        let inner = self.uroman;
+        let readings: Vec<Option<&str>> = self
+            .s_chars
+            .iter()
+            .map(|c| inner.jyutping.get(c).map(String::as_str))
+            .collect();
+        let has_pinyin: Vec<bool> = self
+            .s_chars
+            .iter()
+            .map(|c| inner.toned_pinyin.contains_key(c))
+            .collect();
+
+        let mut cantonese_edges = Vec::new();
+        self.retain_edges(|edge| {
+            if edge.is_numeric() || !edge.r#type().starts_with("rom") {
+                return true;
+            }
+            let span = edge.start()..edge.end();
+            let (rom, r#type) = if span.clone().any(|i| readings[i].is_none() && has_pinyin[i]) {
+                (edge.txt().to_string(), "rom mandarin-fallback")
+            } else if let Some(readings) =
+                readings[span].iter().copied().collect::<Option<Vec<_>>>()
+            {
+                let rom = readings
+                    .into_iter()
+                    .map(|reading| match jyutping {
+                        Jyutping::ToneNumbers => reading,
+                        Jyutping::Toneless => {
+                            reading.trim_end_matches(|c: char| c.is_ascii_digit())
+                        }
+                    })
+                    .collect();
+                (rom, "rom jyutping")
+            } else {
+                return true;
+            };
+            cantonese_edges.push(Edge::new_regular(
+                edge.start(),
+                edge.end(),
+                rom,
+                r#type.to_string(),
+            ));
+            false
+        });
+        for edge in cantonese_edges {
+            self.add_edge(edge);
+        }
+    }
+}
+
+/// Converts pinyin with tone marks to pinyin with a tone number after each syllable.
+fn marks_to_numbers(pinyin: &str) -> String {
+    let mut letters: Vec<(char, Option<char>)> = Vec::new();
+    for c in pinyin.nfd() {
+        match (c, letters.last_mut()) {
+            ('\u{0304}', Some((_, tone))) => *tone = Some('1'),
+            ('\u{0301}', Some((_, tone))) => *tone = Some('2'),
+            ('\u{030C}', Some((_, tone))) => *tone = Some('3'),
+            ('\u{0300}', Some((_, tone))) => *tone = Some('4'),
+            ('\u{0308}', Some((base, _))) if *base == 'u' => *base = 'v',
+            _ => letters.push((c, None)),
+        }
+    }
+
+    let is_vowel = |i: usize| {
+        letters
+            .get(i)
+            .is_some_and(|&(c, _)| "aeiouv".contains(c.to_ascii_lowercase()))
+    };
+    // Returns `true` if the letter at `i` starts a new syllable.
+    let starts_syllable = |i: usize| {
+        let c = letters[i].0.to_ascii_lowercase();
+        let is_coda = match c {
+            'n' => !is_vowel(i + 1),
+            'g' => letters[i - 1].0 == 'n' && !is_vowel(i + 1),
+            'r' => is_vowel(i - 1) && !is_vowel(i + 1),
+            _ => false,
+        };
+        !is_vowel(i) && !is_coda
+    };
+
+    let mut numbered = String::with_capacity(pinyin.len() + 2);
+    let mut pending = None;
+    for (i, &(c, tone)) in letters.iter().enumerate() {
+        if pending.is_some() && starts_syllable(i) {
+            numbered.extend(pending.take());
+        }
+        numbered.push(c);
+        if tone.is_some() {
+            pending = tone;
+        }
+    }
+    numbered.extend(pending);
+    numbered
+}
@@ -0,0 +1,127 @@
+//! Serializes the compiled tables of a `Uroman` instance for fast startup.
+//!
+//! A compiled blob starts with `MAGIC` and the little-endian `FORMAT_VERSION`,
+//! followed by the bincode-encoded tables and the crate version that produced them.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::{fs, io, path::Path};
+
+use crate::core::UromanInner;
+use crate::options::RomanizationOptions;
+use crate::{BuildError, Uroman};
+
+const MAGIC: &[u8; 8] = b"UROMANRS";
+
+/// Incremented whenever the layout of the compiled tables changes in a release.
+const FORMAT_VERSION: u32 = 1;
+
+const HEADER_LEN: usize = MAGIC.len() + size_of::<u32>();
+
+#[derive(Serialize, Deserialize)]
+struct CompiledUroman<'a> {
+    crate_version: Cow<'a, str>,
+    options: Cow<'a, RomanizationOptions>,
+    inner: Cow<'a, UromanInner>,
+}
+
+impl Uroman {
+    /// Serializes the compiled romanization tables and options of this instance.
+    ///
+    /// The token cache, if any, is not included.
+    ///
+    /// The result can be loaded with `Uroman::from_compiled_bytes`, which skips the
+    /// parsing of the data files done by `Uroman::new`.
+    pub fn to_compiled_bytes(&self) -> Vec<u8> {
+        let compiled = CompiledUroman {
+            crate_version: Cow::Borrowed(env!("CARGO_PKG_VERSION")),
+            options: Cow::Borrowed(&self.options),
+            inner: Cow::Borrowed(&self.inner),
+        };
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, &compiled)
+            .expect("Serializing the compiled tables into memory cannot fail");
+        bytes
+    }
+
+    /// Loads an instance from bytes produced by `Uroman::to_compiled_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// * `BuildError::CompiledVersionMismatch` if the bytes were produced by a different
+    ///   version of this crate or of the compiled format.
+    /// * `BuildError::InvalidCompiledData` if the bytes are not a compiled `Uroman` instance.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// let bytes = Uroman::new().to_compiled_bytes();
+    /// let uroman = Uroman::from_compiled_bytes(&bytes).unwrap();
+    ///
+    /// let result = uroman.romanize_string::<rom_format::Str>("ᚺᚨᛚᛚᛟ", None);
+    /// assert_eq!(result.to_string(), "hallo");
+    /// ```
+    pub fn from_compiled_bytes(bytes: &[u8]) -> Result<Uroman, BuildError> {
+        let Some((magic, rest)) = bytes.split_first_chunk::<8>() else {
+            return Err(BuildError::InvalidCompiledData("missing header".to_string()));
+        };
+        if magic != MAGIC {
+            return Err(BuildError::InvalidCompiledData("missing header".to_string()));
+        }
+
+        let Some((version, payload)) = rest.split_first_chunk::<4>() else {
+            return Err(BuildError::InvalidCompiledData("missing format version".to_string()));
+        };
+        let version = u32::from_le_bytes(*version);
+        if version != FORMAT_VERSION {
+            return Err(BuildError::CompiledVersionMismatch {
+                expected: format!("format {FORMAT_VERSION}"),
+                found: format!("format {version}"),
+            });
+        }
+
+        let compiled: CompiledUroman = bincode::deserialize(payload)
+            .map_err(|e| BuildError::InvalidCompiledData(e.to_string()))?;
+        if compiled.crate_version != env!("CARGO_PKG_VERSION") {
+            return Err(BuildError::CompiledVersionMismatch {
+                expected: env!("CARGO_PKG_VERSION").to_string(),
+                found: compiled.crate_version.into_owned(),
+            });
+        }
+
+        Ok(Uroman {
+            inner: Arc::new(compiled.inner.into_owned()),
+            options: compiled.options.into_owned(),
+            cache: None,
+        })
+    }
+
+    /// Loads an instance from a file written with the bytes of `Uroman::to_compiled_bytes`.
+    ///
+    /// This reads the file and decodes it with `Uroman::from_compiled_bytes`, which skips
+    /// the parsing of the data files.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of reading the file, or an error of the kind
+    /// `io::ErrorKind::InvalidData` wrapping the `BuildError` of `Uroman::from_compiled_bytes`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use uroman::Uroman;
+    /// std::fs::write("uroman.bin", Uroman::new().to_compiled_bytes())?;
+    ///
+    /// // In each process:
+    /// let uroman = Uroman::from_compiled_file("uroman.bin")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_compiled_file(path: impl AsRef<Path>) -> io::Result<Uroman> {
+        let bytes = fs::read(path)?;
+        Uroman::from_compiled_bytes(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
@@ -1,13 +1,16 @@
 use regex::Regex;
+#[cfg(feature = "lang-zho")]
 use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "lang-zho")]
 use unicode_properties::UnicodeGeneralCategory;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::LazyLock;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::{RomRule, utils};
 use crate::rom_rule::RomRules;
-use crate::utils::slot_value_in_double_colon_del_list;
+use crate::utils::{reorder_combining_marks, slot_value_in_double_colon_del_list};
 
 static KAYAH_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"kayah\s+(\S+)\s*$").unwrap());
 static MENDE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"m\d+\s+(\S+)\s*$").unwrap());
@@ -25,7 +28,7 @@ static HANGUL_TAILS: &[&str] = &[
 ];
 
 /// Represents a value that can be an integer, float, or string.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum Value {
     Int(i64),
     Float(f64),
@@ -35,7 +38,7 @@ pub(crate) enum Value {
 
 /// Represents a script with its properties.
 #[allow(unused)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Script {
     pub script_name: String,
     pub direction: Option<String>,
@@ -52,13 +55,13 @@ pub(crate) struct Script {
 //     pub is_large_power: Option<bool>,
 // }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub(crate) enum AbugidaRuleType {
     A,
     AO,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct UromanInner {
     pub(crate) rom_rules: RomRules,
     pub(crate) scripts: HashMap<String, Script>,
@@ -69,6 +72,19 @@ pub(crate) struct UromanInner {
     pub(crate) fraction_connectors: HashSet<String>,
     pub(crate) plus_signs: HashSet<String>,
     pub(crate) minus_signs: HashSet<String>,
+    /// Primary English name of each language in `ISO-639-3-list.txt`, keyed by its code.
+    pub(crate) language_names: HashMap<String, String>,
+    /// The languages whose language-specific rules are retained (`None` means all).
+    pub(crate) enabled_languages: Option<HashSet<String>>,
+    /// The rules dropped by `disable_language`, with their source, keyed by language code.
+    pub(crate) disabled_rules: HashMap<String, Vec<(String, RomRule)>>,
+    /// Pinyin with tone marks of each Han character in `Chinese_to_Pinyin.txt`.
+    pub(crate) toned_pinyin: HashMap<char, String>,
+    /// Jyutping with tone numbers of each Han character in `Chinese_to_Jyutping.txt`.
+    pub(crate) jyutping: HashMap<char, String>,
+    /// Whether a user rule has a source starting with an ASCII character, so that ASCII
+    /// input can no longer be assumed to romanize to itself.
+    pub(crate) has_ascii_user_rules: bool,
 }
 
 impl UromanInner {
@@ -83,6 +99,12 @@ impl UromanInner {
             fraction_connectors: HashSet::with_capacity(1),
             minus_signs: HashSet::with_capacity(2),
             plus_signs: HashSet::new(),
+            language_names: HashMap::with_capacity(375),
+            enabled_languages: None,
+            disabled_rules: HashMap::new(),
+            toned_pinyin: HashMap::with_capacity(26688),
+            jyutping: HashMap::with_capacity(272),
+            has_ascii_user_rules: false,
         };
         uroman.load_resource_files();
         uroman
@@ -99,8 +121,10 @@ impl UromanInner {
     }
 
     fn load_resource_files(&mut self) {
+        // The rule files are copied by the build script without the rules of the
+        // characters of the disabled `lang-*` features.
         self.load_rom_file(
-            include_str!("../data/romanization-auto-table.txt"),
+            include_str!(concat!(env!("OUT_DIR"), "/romanization-auto-table.txt")),
             "ud",
             "rom",
         );
@@ -110,19 +134,154 @@ impl UromanInner {
             "u2r",
         );
         self.load_rom_file(
-            include_str!("../data/romanization-table.txt"),
+            include_str!(concat!(env!("OUT_DIR"), "/romanization-table.txt")),
             "man",
             "rom",
         );
-        self.load_chinese_pinyin_file(include_str!("../data/Chinese_to_Pinyin.txt"));
+        #[cfg(feature = "lang-zho")]
+        {
+            self.load_chinese_pinyin_file(include_str!("../data/Chinese_to_Pinyin.txt"));
+            self.load_jyutping_file(include_str!("../data/Chinese_to_Jyutping.txt"));
+        }
         self.load_script_file(include_str!("../data/Scripts.txt"));
         self.load_unicode_data_props(include_str!("../data/UnicodeDataProps.txt"));
+        #[cfg(feature = "lang-zho")]
         self.load_unicode_data_props(include_str!("../data/UnicodeDataPropsCJK.txt"));
+        #[cfg(feature = "lang-kor")]
         self.load_unicode_data_props(include_str!("../data/UnicodeDataPropsHangul.txt"));
         self.load_num_props(include_str!("../data/NumProps.jsonl"));
+        self.load_language_list(include_str!("../data/ISO-639-3-list.txt"));
+        // The languages whose rules are all left out by the `lang-*` features.
+        for lcode in include_str!(concat!(env!("OUT_DIR"), "/excluded-languages.txt")).lines() {
+            self.language_names.remove(lcode);
+        }
         self.add_thai_cancellation_rules();
     }
 
+    /// Loads the language codes and names from `ISO-639-3-list.txt`.
+    fn load_language_list(&mut self, file: &'static str) {
+        for line in file.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(lcode) = slot_value_in_double_colon_del_list(line, "lang-code") {
+                let name = slot_value_in_double_colon_del_list(line, "lang-names")
+                    .and_then(|names| names.split(';').next())
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_default();
+                self.language_names.insert(lcode.to_string(), name);
+            }
+        }
+    }
+
+    /// Returns `true` if `lcode` is a language code listed in `ISO-639-3-list.txt`.
+    pub(crate) fn is_known_language(&self, lcode: &str) -> bool {
+        self.language_names.contains_key(lcode)
+    }
+
+    /// Returns the characters of the romanizations of the rules that apply under `lcode`:
+    /// the rules without an `::lcode` restriction and, if `lcode` is given, its own rules.
+    pub(crate) fn output_alphabet(&self, lcode: Option<&str>) -> BTreeSet<char> {
+        self.rom_rules
+            .values()
+            .flatten()
+            .filter(|rule| {
+                rule.lcodes.is_empty()
+                    || lcode.is_some_and(|lcode| rule.lcodes.iter().any(|lc| lc == lcode))
+            })
+            .flat_map(|rule| rule.t.iter().chain(&rule.t_at_end_of_syllable))
+            .flat_map(|t| t.chars())
+            .collect()
+    }
+
+    /// Drops all language-specific rules except those for the given `languages`.
+    ///
+    /// Rules without an `::lcode` restriction are shared by all languages and are kept.
+    pub(crate) fn retain_languages(&mut self, languages: &HashSet<String>) {
+        for rules in self.rom_rules.values_mut() {
+            rules.retain_mut(|rule| {
+                if rule.lcodes.is_empty() {
+                    return true;
+                }
+                rule.lcodes.retain(|lcode| languages.contains(lcode));
+                !rule.lcodes.is_empty()
+            });
+        }
+        self.rom_rules.retain(|_, rules| !rules.is_empty());
+        self.rom_rules.shrink_to_fit();
+        self.enabled_languages = Some(languages.clone());
+    }
+
+    /// Returns `true` if a rule is restricted to `lcode`, including the rules dropped by
+    /// `disable_language`. Some of these codes, such as `srp2`, are not in
+    /// `ISO-639-3-list.txt`.
+    pub(crate) fn has_language_rules(&self, lcode: &str) -> bool {
+        self.disabled_rules.contains_key(lcode)
+            || self
+                .rom_rules
+                .values()
+                .flatten()
+                .any(|rule| rule.lcodes.iter().any(|l| l == lcode))
+    }
+
+    /// Returns `true` if the rules restricted to `lcode` are retained.
+    pub(crate) fn is_language_retained(&self, lcode: &str) -> bool {
+        self.enabled_languages
+            .as_ref()
+            .is_none_or(|languages| languages.contains(lcode))
+    }
+
+    /// Returns the rules restricted to `lcode` with their source, each restricted to
+    /// `lcode` alone.
+    fn language_rules(&self, lcode: &str) -> Vec<(String, RomRule)> {
+        let mut language_rules = Vec::new();
+        for (s, rules) in &self.rom_rules {
+            for rule in rules.iter().filter(|rule| rule.lcodes.iter().any(|l| l == lcode)) {
+                let mut rule = rule.clone();
+                rule.lcodes = vec![lcode.to_string()];
+                language_rules.push((s.clone(), rule));
+            }
+        }
+        language_rules
+    }
+
+    /// Drops the rules restricted to `lcode`, as if it had been left out of
+    /// `retain_languages`, and keeps them for `enable_language`.
+    pub(crate) fn disable_language(&mut self, lcode: &str) {
+        let mut languages = self.enabled_languages.clone().unwrap_or_else(|| {
+            let rule_lcodes = self.rom_rules.values().flatten().flat_map(|rule| &rule.lcodes);
+            self.language_names.keys().chain(rule_lcodes).cloned().collect()
+        });
+        languages.remove(lcode);
+        let rules = self.language_rules(lcode);
+        self.disabled_rules.insert(lcode.to_string(), rules);
+        self.retain_languages(&languages);
+    }
+
+    /// Restores the rules restricted to `lcode`, if it was disabled: those dropped by
+    /// `disable_language`, or else those of the tables returned by `builtin`.
+    pub(crate) fn enable_language<'b>(
+        &mut self,
+        lcode: &str,
+        builtin: impl FnOnce() -> &'b UromanInner,
+    ) {
+        let Some(languages) = &mut self.enabled_languages else {
+            return;
+        };
+        if !languages.insert(lcode.to_string()) {
+            return;
+        }
+        let rules = match self.disabled_rules.remove(lcode) {
+            Some(rules) => rules,
+            None => builtin().language_rules(lcode),
+        };
+        for (s, rule) in rules {
+            self.register_s_prefix(&s);
+            self.rom_rules.entry(s).or_default().push(rule);
+        }
+    }
+
     /// Loads numerical properties from a JSONL file (e.g., NumProps.jsonl).
     fn load_num_props(&mut self, file_content: &'static str) {
         for line in file_content.lines() {
@@ -372,6 +531,77 @@ impl UromanInner {
         }
     }
 
+    /// Parses user rules in the format of `romanization-table.txt`, with the number of the
+    /// line of each rule, or returns the number of the first line that is not a rule.
+    pub(crate) fn parse_user_rules(&self, rules: &str) -> Result<Vec<(usize, RomRule)>, usize> {
+        let mut parsed = Vec::new();
+        for (i, line) in rules.lines().enumerate() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            parsed.push((i + 1, RomRule::from_line(line, "user", "rom", self).ok_or(i + 1)?));
+        }
+        Ok(parsed)
+    }
+
+    /// Adds user rules in the format of `romanization-table.txt`.
+    ///
+    /// The rules for a source string replace all rules loaded before for it. Returns the
+    /// 1-based number of the first line that is not a rule, leaving `self` unchanged.
+    pub(crate) fn add_user_rules(&mut self, rules: &str) -> Result<(), usize> {
+        let parsed = self.parse_user_rules(rules)?;
+
+        let mut replaced = HashSet::new();
+        for (_, rule) in parsed {
+            if replaced.insert(rule.s.clone()) {
+                self.rom_rules.remove(&rule.s);
+            }
+            self.has_ascii_user_rules |= rule.s.starts_with(|c: char| c.is_ascii());
+            self.add_rom_rule(rule);
+        }
+        Ok(())
+    }
+
+    /// Writes all rules in the format of `romanization-table.txt`, sorted by source string.
+    pub(crate) fn export_rules(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let mut sources: Vec<&String> = self.rom_rules.keys().collect();
+        sources.sort();
+        for s in sources {
+            for rule in &self.rom_rules[s] {
+                writeln!(writer, "{}", rule.to_line())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a user rule that maps `s` to `t`, restricted to `lcode` if given.
+    ///
+    /// The rule replaces the rules for `s` with the same scope: those not restricted to any
+    /// language if `lcode` is `None`, and otherwise those restricted to `lcode`.
+    pub(crate) fn add_user_rule(&mut self, s: &str, t: &str, lcode: Option<&str>) {
+        let s = &reorder_combining_marks(s);
+        if let Some(rules) = self.rom_rules.get_mut(s) {
+            rules.retain_mut(|rule| match lcode {
+                None => !rule.lcodes.is_empty(),
+                Some(lcode) => {
+                    if rule.lcodes.is_empty() {
+                        return true;
+                    }
+                    rule.lcodes.retain(|rule_lcode| rule_lcode != lcode);
+                    !rule.lcodes.is_empty()
+                }
+            });
+        }
+
+        self.has_ascii_user_rules |= s.starts_with(|c: char| c.is_ascii());
+        let mut rule = RomRule::new_simple(s.to_string(), t, "user");
+        if let Some(lcode) = lcode {
+            rule.lcodes = vec![lcode.to_string()];
+            rule.n_restr = 1;
+        }
+        self.add_rom_rule(rule);
+    }
+
     fn add_rom_rule(&mut self, rule: RomRule) {
         if rule.is_minus_sign {
             self.minus_signs.insert(rule.s.clone());
@@ -415,7 +645,24 @@ impl UromanInner {
         }
     }
 
+    /// Loads the Cantonese readings of Han characters.
+    #[cfg(feature = "lang-zho")]
+    fn load_jyutping_file(&mut self, file: &'static str) {
+        for line in file.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some((chinese, jyutping)) = line.split_once('\t')
+                && let Some(c) = chinese.chars().next()
+            {
+                self.jyutping.insert(c, jyutping.trim().to_string());
+            }
+        }
+    }
+
     /// Loads and processes the Chinese to Pinyin mapping file.
+    #[cfg(feature = "lang-zho")]
     fn load_chinese_pinyin_file(&mut self, file: &'static str) {
         for line in file.lines() {
             if line.starts_with('#') || line.trim().is_empty() {
@@ -423,6 +670,11 @@ impl UromanInner {
             }
 
             if let Some((chinese, pinyin_with_accent)) = line.split_once(char::is_whitespace) {
+                let mut chars = chinese.chars();
+                if let (Some(c), None) = (chars.next(), chars.next()) {
+                    self.toned_pinyin.insert(c, pinyin_with_accent.trim().to_string());
+                }
+
                 // `de_accent_pinyin` logic: NFD decomposition to separate base chars and accents.
                 let rom: String = pinyin_with_accent
                     .nfd()
@@ -0,0 +1,187 @@
+//! Romanizes Cyrillic according to a selectable `CyrillicScheme`.
+//!
+//! Unlike the other schemes, the Cyrillic schemes apply to Cyrillic letters under any
+//! `lcode`, since ISO 9 is defined for the script rather than for a language.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Cyrillic.
+///
+/// | Cyrillic | `Default` | `Iso9`  | `BgnPcgn` |
+/// |----------|-----------|---------|-----------|
+/// | жёлтый   | zhyoltyy  | žëltyj  | zhëltyy   |
+/// | щука     | shchuka   | ŝuka    | shchuka   |
+/// | хлеб     | khleb     | hleb    | khleb     |
+/// | ель      | yel       | elʹ     | yel’      |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CyrillicScheme {
+    /// The rules of the romanization tables.
+    #[default]
+    Default,
+    /// ISO 9:1995, which maps each Cyrillic letter to a single Latin letter, with
+    /// diacritics where needed, so that the romanization can be reversed.
+    Iso9,
+    /// BGN/PCGN (1947) for Russian, which uses digraphs instead of diacritics except for
+    /// `ë`. `е` and `ё` are written `ye` and `yë` at the start of a word and after a vowel,
+    /// `й`, `ъ` or `ь`. Letters not used in Russian keep the rules of the romanization tables.
+    BgnPcgn,
+}
+
+impl CyrillicScheme {
+    fn edge_type(self) -> &'static str {
+        match self {
+            CyrillicScheme::Default => "rom",
+            CyrillicScheme::Iso9 => "rom iso9",
+            CyrillicScheme::BgnPcgn => "rom bgn-pcgn",
+        }
+    }
+}
+
+pub(crate) const ISO_9: [(char, &str); 49] = [
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('ґ', "g\u{0300}"),
+    ('д', "d"),
+    ('ѓ', "ǵ"),
+    ('ђ', "đ"),
+    ('е', "e"),
+    ('ё', "ë"),
+    ('є', "ê"),
+    ('ж', "ž"),
+    ('з', "z"),
+    ('ѕ', "ẑ"),
+    ('и', "i"),
+    ('і', "ì"),
+    ('ї', "ï"),
+    ('й', "j"),
+    ('ј', "ǰ"),
+    ('к', "k"),
+    ('л', "l"),
+    ('љ', "l\u{0302}"),
+    ('м', "m"),
+    ('н', "n"),
+    ('њ', "n\u{0302}"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('ќ', "ḱ"),
+    ('ћ', "ć"),
+    ('у', "u"),
+    ('ў', "ǔ"),
+    ('ф', "f"),
+    ('х', "h"),
+    ('ц', "c"),
+    ('ч', "č"),
+    ('џ', "d\u{0302}"),
+    ('ш', "š"),
+    ('щ', "ŝ"),
+    ('ъ', "ʺ"),
+    ('ы', "y"),
+    ('ь', "ʹ"),
+    ('э', "è"),
+    ('ю', "û"),
+    ('я', "â"),
+    ('ѣ', "ě"),
+    ('ѫ', "ǎ"),
+];
+
+const BGN_PCGN: [(char, &str); 33] = [
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ё', "ë"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('й', "y"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', "”"),
+    ('ы', "y"),
+    ('ь', "’"),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+];
+
+impl Lattice<'_> {
+    /// Replaces the edges of every Cyrillic letter covered by `scheme` with its romanization.
+    pub fn apply_cyrillic_scheme(&mut self, scheme: CyrillicScheme) {
+        let table: &[(char, &str)] = match scheme {
+            CyrillicScheme::Default => return,
+            CyrillicScheme::Iso9 => &ISO_9,
+            CyrillicScheme::BgnPcgn => &BGN_PCGN,
+        };
+
+        let mut edges = Vec::new();
+        for (i, &c) in self.s_chars.iter().enumerate() {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let Some(&(_, rom)) = table.iter().find(|&&(letter, _)| letter == lower) else {
+                continue;
+            };
+
+            let mut txt = rom.to_string();
+            if scheme == CyrillicScheme::BgnPcgn
+                && matches!(lower, 'е' | 'ё')
+                && i.checked_sub(1).is_none_or(|prev| {
+                    let prev = self.s_chars[prev].to_lowercase().next().unwrap_or(' ');
+                    !is_cyrillic_letter(prev) || "аеёиоуыэюяйъь".contains(prev)
+                })
+            {
+                txt.insert(0, 'y');
+            }
+            if c != lower {
+                txt = match scheme {
+                    CyrillicScheme::Iso9 => txt.to_uppercase(),
+                    _ => capitalize(&txt),
+                };
+            }
+            edges.push(Edge::new_regular(
+                i,
+                i + 1,
+                txt,
+                scheme.edge_type().to_string(),
+            ));
+        }
+        for edge in edges {
+            let (start, end) = (edge.start(), edge.end());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
+
+fn is_cyrillic_letter(c: char) -> bool {
+    matches!(c, '\u{0400}'..='\u{04FF}') && c.is_alphabetic()
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
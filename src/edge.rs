@@ -1,7 +1,13 @@
 use crate::core::{UromanInner, Value};
+use crate::script::Script;
 use num_rational::Ratio;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
 #[derive(Debug, Clone, Serialize, PartialEq, PartialOrd)]
 pub struct EdgeData {
@@ -9,6 +15,10 @@ pub struct EdgeData {
     pub end: usize,
     pub txt: String,
     pub r#type: String,
+    /// The dominant script of the source characters of the edge, which is set on the
+    /// edges returned by `romanize_string`.
+    #[serde(skip)]
+    pub source_script: Option<Script>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd, Serialize)]
@@ -23,6 +33,40 @@ pub struct NumData {
     pub active: bool,
     pub value_s: Option<String>,
     pub n_decimals: Option<usize>,
+    /// The exact value of an integer, if known, as `value` loses precision beyond 2^53.
+    #[serde(skip)]
+    pub exact: Option<ExactInt>,
+}
+
+/// The type of the exact integer values of numeric edges: `BigInt` with the `bigint`
+/// feature, and `i128` otherwise, for which values that overflow are unknown.
+#[cfg(feature = "bigint")]
+pub type ExactInt = num_bigint::BigInt;
+#[cfg(not(feature = "bigint"))]
+pub type ExactInt = i128;
+
+/// Multiplies two exact values, if both are known.
+pub(crate) fn exact_product(a: Option<&ExactInt>, b: Option<&ExactInt>) -> Option<ExactInt> {
+    #[cfg(feature = "bigint")]
+    return Some(a? * b?);
+    #[cfg(not(feature = "bigint"))]
+    return a?.checked_mul(*b?);
+}
+
+/// Adds exact values, if all of them are known.
+pub(crate) fn exact_sum<'a>(values: impl IntoIterator<Item = Option<&'a ExactInt>>) -> Option<ExactInt> {
+    let mut sum = ExactInt::from(0);
+    for value in values {
+        #[cfg(feature = "bigint")]
+        {
+            sum += value?;
+        }
+        #[cfg(not(feature = "bigint"))]
+        {
+            sum = sum.checked_add(*value?)?;
+        }
+    }
+    Some(sum)
 }
 
 /// A dedicated struct for flexibly updating fields of a `NumData`.
@@ -40,15 +84,77 @@ pub struct NumDataUpdates {
     pub n_decimals: Option<usize>,
     pub orig_txt: Option<String>,
     pub value_s: Option<String>,
+    pub exact: Option<ExactInt>,
 }
 
 /// A unified Edge type.
-#[derive(Debug, Clone, Serialize, PartialOrd)]
+///
+/// An edge serializes to a flat object with the stable fields `start`, `end`, `txt`,
+/// `type`, `is_numeric`, `value` and `orig_txt`. With the `serde` feature, it can also
+/// be deserialized from that form. Numeric edges then only keep `value` and `orig_txt`
+/// of their numeric properties.
+// Numeric edges are rare, and boxing `NumData` would complicate every pattern match on them.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialOrd)]
 pub enum Edge {
     Regular(EdgeData),
     Numeric { data: EdgeData, num_data: NumData },
 }
 
+/// The flat serialized form of an `Edge`.
+#[derive(Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+struct SerializedEdge<'a> {
+    start: usize,
+    end: usize,
+    txt: Cow<'a, str>,
+    r#type: Cow<'a, str>,
+    is_numeric: bool,
+    value: Option<f64>,
+    orig_txt: Option<Cow<'a, str>>,
+}
+
+impl Serialize for Edge {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedEdge {
+            start: self.start(),
+            end: self.end(),
+            txt: Cow::Borrowed(self.txt()),
+            r#type: Cow::Borrowed(self.r#type()),
+            is_numeric: self.is_numeric(),
+            value: self.value(),
+            orig_txt: self.is_numeric().then(|| Cow::Borrowed(self.orig_txt())),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Edge {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let edge = SerializedEdge::deserialize(deserializer)?;
+        let data = EdgeData {
+            start: edge.start,
+            end: edge.end,
+            txt: edge.txt.into_owned(),
+            r#type: edge.r#type.into_owned(),
+            source_script: None,
+        };
+
+        if edge.is_numeric {
+            let num_data = NumData {
+                orig_txt: edge.orig_txt.map(Cow::into_owned).unwrap_or_default(),
+                value: edge.value,
+                active: true,
+                ..Default::default()
+            };
+            Ok(Edge::Numeric { data, num_data })
+        } else {
+            Ok(Edge::Regular(data))
+        }
+    }
+}
+
 impl Hash for Edge {
     fn hash<H: Hasher>(&self, state: &mut H) {
         let d = self.get_data();
@@ -77,6 +183,7 @@ impl Edge {
             end,
             txt,
             r#type,
+            source_script: None,
         })
     }
 
@@ -97,6 +204,10 @@ impl Edge {
             Value::Float(f) => Some(*f),
             _ => None,
         });
+        let exact = props_map.get("value").and_then(|v| match v {
+            Value::Int(i) => Some(ExactInt::from(*i)),
+            _ => None,
+        });
 
         let fraction = props_map.get("fraction").and_then(|v| match v {
             Value::String(s) => s
@@ -150,6 +261,7 @@ impl Edge {
                 end,
                 txt: rom_text,
                 r#type,
+                source_script: None,
             },
             num_data: NumData {
                 orig_txt: char.to_string(),
@@ -160,6 +272,7 @@ impl Edge {
                 script,
                 is_large_power,
                 active: true,
+                exact,
                 ..Default::default()
             },
         };
@@ -204,6 +317,7 @@ impl Edge {
                 end,
                 txt: "".to_string(),
                 r#type: e_type,
+                source_script: None,
             },
             num_data,
         };
@@ -275,6 +389,9 @@ impl Edge {
             if let Some(v) = updates.value_s {
                 num_data.value_s = Some(v);
             }
+            if let Some(v) = updates.exact {
+                num_data.exact = Some(v);
+            }
 
             // --- Recalculate the display text (`txt`) after all updates ---
             self.recalculate_numeric_txt();
@@ -288,6 +405,10 @@ impl Edge {
             // Determine the primary string for the value, prioritizing `value_s`.
             let value_s = if let Some(vs) = &num_data.value_s {
                 vs.clone()
+            } else if let Some(exact) = &num_data.exact
+                && num_data.n_decimals.is_none()
+            {
+                exact.to_string()
             } else if let Some(v) = num_data.value {
                 if let Some(nd) = num_data.n_decimals {
                     format!("{v:.nd$}")
@@ -364,6 +485,73 @@ impl Edge {
         &self.get_data().r#type
     }
 
+    /// Returns the span of the source text covered by this edge, in character offsets.
+    ///
+    /// If the `Uroman` instance normalizes its input, the offsets refer to the normalized text.
+    pub fn source_range(&self) -> Range<usize> {
+        self.start()..self.end()
+    }
+
+    /// Returns the script of the source characters of this edge.
+    ///
+    /// Digits, punctuation and symbols only count if the span has no other characters.
+    /// For a span of several scripts, the script of most of its characters is returned,
+    /// or `None` if no script has most of them. Edges that the `Uroman` instance did not
+    /// return from `romanize_string` (e.g., edges built with `Edge::new_regular`) have no
+    /// script.
+    pub fn source_script(&self) -> Option<Script> {
+        self.get_data().source_script.clone()
+    }
+
+    /// Returns the preference of the decoder for this edge over the other edges of its span,
+    /// from `1.0` (most preferred) down to `0.1`.
+    ///
+    /// Active numeric edges are preferred over romanizations, which are preferred over
+    /// romanizations by decomposition, other edges and inactive numeric edges. Alternative
+    /// romanizations (`rom-alt*`), which the decoder never picks, score `0.5`.
+    pub fn score(&self) -> f32 {
+        let r#type = self.r#type();
+        match self {
+            Edge::Numeric { .. } if self.is_active() => 1.0,
+            Edge::Numeric { .. } => 0.1,
+            Edge::Regular(_) if r#type.starts_with("rom-alt") => 0.5,
+            Edge::Regular(_) if r#type.starts_with("rom decomp") => 0.7,
+            Edge::Regular(_) if r#type.starts_with("rom") || r#type.starts_with("num") => 0.9,
+            Edge::Regular(_) => 0.3,
+        }
+    }
+
+    /// Returns `true` if the edge is a number marked as an ordinal, with the type `ordinal`.
+    ///
+    /// Ordinal edges are numeric, and keep the value of the number. The recognized markers are:
+    ///
+    /// - under any `lcode`, the indicators `º`, `ª` and `ᵉ` after the number (e.g., `1º`,
+    ///   `2.ª`, `3ᵉ`), and `第` before it (e.g., 第三)
+    /// - English (`eng`, or no `lcode`): `st`, `nd`, `rd` and `th` after ASCII digits,
+    ///   matching the number (e.g., `21st` but not `21th`)
+    /// - French (`fra`): `er`, `re`, `e` and `ème` (e.g., `1er`, `2e`)
+    /// - Japanese (`jpn`): `番目` (e.g., `3番目`)
+    /// - Korean (`kor`): `제` before the number (e.g., `제1`)
+    pub fn is_ordinal(&self) -> bool {
+        self.is_numeric() && self.r#type() == "ordinal"
+    }
+
+    /// Returns `true` if the edge is an alternative romanization (`rom-alt`, `rom-alt2` or
+    /// `rom-alt3`) of another edge, as added to the output of the `Alts`, `Lattice` and
+    /// `Json` formats.
+    pub fn is_alternative(&self) -> bool {
+        self.r#type().starts_with("rom-alt")
+    }
+
+    /// Returns the romanizations of the alternative edges in `edges` with the same span as this edge.
+    pub fn alternatives<'a>(&self, edges: &'a [Edge]) -> Vec<&'a str> {
+        edges
+            .iter()
+            .filter(|edge| edge.is_alternative() && edge.source_range() == self.source_range())
+            .map(Edge::txt)
+            .collect()
+    }
+
     pub fn is_active(&self) -> bool {
         self.get_num_data().is_none_or(|d| d.active)
     }
@@ -374,7 +562,157 @@ impl Edge {
         }
     }
 
+    /// Returns the value of a numeric edge, including its fraction (e.g., `0.5` for ½ and
+    /// `12.5` for ١٢٫٥).
     pub fn value(&self) -> Option<f64> {
+        let num_data = self.get_num_data()?;
+        match num_data.fraction {
+            Some(fraction) => {
+                let fraction = *fraction.numer() as f64 / *fraction.denom() as f64;
+                Some(num_data.value.unwrap_or(0.0) + fraction)
+            }
+            None => num_data.value,
+        }
+    }
+
+    /// Returns the integer part of the value of a numeric edge, which the numeral passes combine.
+    pub(crate) fn whole_value(&self) -> Option<f64> {
         self.get_num_data().and_then(|d| d.value)
     }
+
+    /// Returns the exact value of a numeric edge holding an integer.
+    ///
+    /// Unlike `value`, this is exact beyond 2^53 (e.g., for long digit strings or
+    /// compositional numerals such as 九千九百九十九京). Returns `None` for edges without
+    /// an integer value, and for values that do not fit in an `i128`.
+    pub fn value_i128(&self) -> Option<i128> {
+        #[cfg(feature = "bigint")]
+        return self.value_bigint().and_then(|value| i128::try_from(value).ok());
+        #[cfg(not(feature = "bigint"))]
+        return self.exact_value();
+    }
+
+    /// Returns the exact value of a numeric edge holding an integer, of any size.
+    ///
+    /// Requires the `bigint` feature.
+    #[cfg(feature = "bigint")]
+    pub fn value_bigint(&self) -> Option<num_bigint::BigInt> {
+        self.exact_value()
+    }
+
+    /// Returns the exact integer value, falling back to `value` where it is exactly an
+    /// integer (e.g., for deserialized edges).
+    fn exact_value(&self) -> Option<ExactInt> {
+        const MAX_EXACT_F64: f64 = (1u64 << 53) as f64;
+
+        let num_data = self.get_num_data()?;
+        if num_data.n_decimals.is_some() || num_data.fraction.is_some() {
+            return None;
+        }
+        if num_data.exact.is_some() {
+            return num_data.exact.as_ref().cloned();
+        }
+        num_data
+            .value
+            .filter(|v| v.fract() == 0.0 && v.abs() <= MAX_EXACT_F64)
+            .map(|v| ExactInt::from(v as i64))
+    }
+
+    pub(crate) fn exact(&self) -> Option<&ExactInt> {
+        self.get_num_data().and_then(|d| d.exact.as_ref())
+    }
+
+    /// Returns `true` if no rule covers the edge and it passes a letter or digit, or a
+    /// private-use or unassigned character (`Co` and `Cn`), through unchanged.
+    ///
+    /// Punctuation and symbols (e.g., `€`) are passed through intentionally and are not reported.
+    pub fn is_unromanized(&self) -> bool {
+        match self.r#type() {
+            "orig" => self.txt().chars().any(char::is_alphanumeric),
+            "Co" | "Cn" => true,
+            _ => false,
+        }
+    }
+}
+
+/// A position between two characters of the input in a romanization lattice, with the
+/// edges that start there.
+///
+/// The edges of a node compete with each other; an edge from `position` to `end` is
+/// followed by the edges of the node at `end`. Each edge serializes with its `score`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LatticeNode {
+    /// The offset of the position, in characters.
+    pub position: usize,
+    /// The edges starting at `position`, longest first and then by decreasing score.
+    #[serde(serialize_with = "serialize_scored_edges")]
+    pub edges: Vec<Edge>,
+}
+
+impl LatticeNode {
+    /// Groups `edges` into one node for each position from `0` to the end of the last edge.
+    pub fn from_edges(edges: Vec<Edge>) -> Vec<LatticeNode> {
+        let len = edges.iter().map(Edge::end).max().unwrap_or(0);
+        let mut nodes: Vec<LatticeNode> = (0..=len)
+            .map(|position| LatticeNode {
+                position,
+                edges: Vec::new(),
+            })
+            .collect();
+        for edge in edges {
+            nodes[edge.start()].edges.push(edge);
+        }
+        for node in &mut nodes {
+            node.edges.sort_by(|a, b| {
+                b.end()
+                    .cmp(&a.end())
+                    .then(b.score().total_cmp(&a.score()))
+                    .then_with(|| (a.r#type(), a.txt()).cmp(&(b.r#type(), b.txt())))
+            });
+        }
+        nodes
+    }
+}
+
+fn serialize_scored_edges<S: Serializer>(edges: &[Edge], serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct ScoredEdge<'a> {
+        #[serde(flatten)]
+        edge: &'a Edge,
+        score: f32,
+    }
+
+    serializer.collect_seq(edges.iter().map(|edge| ScoredEdge {
+        edge,
+        score: edge.score(),
+    }))
+}
+
+/// Writes `edges` as a Graphviz DOT digraph, with one node for each position at
+/// which an edge starts or ends and one edge labeled with the text, type and score
+/// of each `Edge`.
+pub(crate) fn edges_to_dot(edges: &[Edge]) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    let positions: BTreeSet<usize> = edges.iter().flat_map(|edge| [edge.start(), edge.end()]).collect();
+
+    let mut dot = String::from("digraph lattice {\n    rankdir=LR;\n    node [shape=circle];\n");
+    for position in positions {
+        dot.push_str(&format!("    {position};\n"));
+    }
+    for edge in edges {
+        dot.push_str(&format!(
+            "    {} -> {} [label=\"{}\\n{}\\n{:.2}\"];\n",
+            edge.start(),
+            edge.end(),
+            escape(edge.txt()),
+            escape(edge.r#type()),
+            edge.score()
+        ));
+    }
+    dot.push_str("}\n");
+    dot
 }
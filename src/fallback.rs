@@ -0,0 +1,68 @@
+//! Applies a `Fallback` to the characters that no rule romanizes.
+//!
+//! A character that neither a rule nor a Unicode category covers is passed through
+//! by an edge of the type `orig`, and a private-use or unassigned character by an edge
+//! of the type `Co` or `Cn`. ASCII characters, such as punctuation, are passed through
+//! the same way but need no romanization, so only non-ASCII edges are affected.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// What is written for a character that cannot be romanized.
+///
+/// | Input | `Keep` | `Replace("?")` | `Drop` |
+/// |-------|--------|----------------|--------|
+/// | a€b   | a€b    | a?b            | ab     |
+/// | 𓀀     | 𓀀      | ?              |        |
+/// | `a\u{E000}b` | `a\u{E000}b` | a?b | ab  |
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Fallback {
+    /// The character is passed through unchanged, as by the original uroman, which
+    /// however drops private-use characters.
+    #[default]
+    Keep,
+    /// The character is replaced with the given string.
+    Replace(String),
+    /// The character is removed.
+    Drop,
+}
+
+impl Lattice<'_> {
+    /// Replaces the text of the non-ASCII `orig` edges and of the `Co` and `Cn` edges as
+    /// selected by `fallback`, and tags them with the type `fallback`.
+    pub fn apply_fallback(&mut self, fallback: &Fallback) {
+        let txt = match fallback {
+            Fallback::Keep => return,
+            Fallback::Replace(replacement) => replacement.as_str(),
+            Fallback::Drop => "",
+        };
+
+        for edges in self.edge_lattice.values_mut() {
+            let is_fallback = |edge: &Edge| match edge.r#type() {
+                "orig" => !edge.txt().is_ascii(),
+                "Co" | "Cn" => true,
+                _ => false,
+            };
+            if !edges.iter().any(is_fallback) {
+                continue;
+            }
+            *edges = edges
+                .drain()
+                .map(|edge| {
+                    if is_fallback(&edge) {
+                        Edge::new_regular(
+                            edge.start(),
+                            edge.end(),
+                            txt.to_string(),
+                            "fallback".to_string(),
+                        )
+                    } else {
+                        edge
+                    }
+                })
+                .collect();
+        }
+    }
+}
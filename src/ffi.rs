@@ -0,0 +1,103 @@
+//! C bindings for uroman-rs.
+//!
+//! The functions exchange null-terminated UTF-8 strings and never unwind across the FFI
+//! boundary: a panic, like invalid input, is reported by returning a null pointer. Every
+//! `Uroman` returned by `uroman_new` must be released with `uroman_free`, and every string
+//! returned by `uroman_romanize` with `uroman_string_free`. The declarations are in
+//! `include/uroman.h`.
+//!
+//! # Example
+//! ```c
+//! Uroman *uroman = uroman_new();
+//! char *romanized = uroman_romanize(uroman, "Київ", "ukr");
+//! printf("%s\n", romanized); // Kyiv
+//! uroman_string_free(romanized);
+//! uroman_free(uroman);
+//! ```
+
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{Uroman, rom_format};
+
+/// Creates a `Uroman` instance with the default options.
+///
+/// Returns a null pointer if the instance cannot be created.
+#[unsafe(no_mangle)]
+pub extern "C" fn uroman_new() -> *mut Uroman {
+    panic::catch_unwind(|| Box::into_raw(Box::new(Uroman::new()))).unwrap_or(ptr::null_mut())
+}
+
+/// Romanizes the null-terminated UTF-8 string `text`.
+///
+/// `lcode` is an ISO 639-3 language code, or a null pointer for none. Returns a newly
+/// allocated null-terminated UTF-8 string, to be released with `uroman_string_free`, or a
+/// null pointer if an argument is null or not valid UTF-8, or if romanization fails.
+///
+/// # Safety
+///
+/// `uroman` must be a pointer returned by `uroman_new` that has not been freed. `text`
+/// and `lcode`, if not null, must point to null-terminated strings that stay valid for
+/// the duration of the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uroman_romanize(
+    uroman: *const Uroman,
+    text: *const c_char,
+    lcode: *const c_char,
+) -> *mut c_char {
+    if uroman.is_null() || text.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: The pointers are non-null and valid as required by the caller.
+    let (uroman, text) = unsafe { (&*uroman, CStr::from_ptr(text)) };
+    let lcode = if lcode.is_null() {
+        None
+    } else {
+        // SAFETY: `lcode` is non-null and null-terminated as required by the caller.
+        match unsafe { CStr::from_ptr(lcode) }.to_str() {
+            Ok(lcode) => Some(lcode),
+            Err(_) => return ptr::null_mut(),
+        }
+    };
+    let Ok(text) = text.to_str() else {
+        return ptr::null_mut();
+    };
+
+    let romanized = panic::catch_unwind(AssertUnwindSafe(|| {
+        uroman
+            .romanize_string::<rom_format::Str>(text, lcode)
+            .to_string()
+    }));
+    match romanized.map(CString::new) {
+        Ok(Ok(romanized)) => romanized.into_raw(),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Releases a string returned by `uroman_romanize`. Does nothing if `s` is null.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by `uroman_romanize` that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uroman_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: `s` was allocated by `CString::into_raw` in `uroman_romanize`.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Releases a `Uroman` instance returned by `uroman_new`. Does nothing if `uroman` is null.
+///
+/// # Safety
+///
+/// `uroman` must be null or a pointer returned by `uroman_new` that has not been freed,
+/// and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uroman_free(uroman: *mut Uroman) {
+    if !uroman.is_null() {
+        // SAFETY: `uroman` was allocated by `Box::into_raw` in `uroman_new`.
+        drop(unsafe { Box::from_raw(uroman) });
+    }
+}
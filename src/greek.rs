@@ -0,0 +1,286 @@
+//! Romanizes Greek according to a selectable `GreekScheme` when `lcode` is `ell` or `grc`.
+//!
+//! Polytonic letters are read from their canonical decomposition: the rough breathing
+//! (U+0314) and the iota subscript (U+0345) are written by `GreekScheme::Classical`,
+//! while accents and the smooth breathing are dropped by both schemes.
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Greek.
+///
+/// | Greek      | `Classical`  | `ModernBgnPcgn` |
+/// |------------|--------------|-----------------|
+/// | ᾅδης       | haidēs       | adis            |
+/// | Ὅμηρος     | Homēros      | Omiros          |
+/// | εὐαγγέλιον | euangelion   | evangelion      |
+/// | μπίρα      | mpira        | bira            |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GreekScheme {
+    /// The conventional transliteration of Ancient Greek: `ē` and `ō` for eta and omega,
+    /// `h` for the rough breathing (`rh` for rho), `i` after the vowel for the iota
+    /// subscript, `ch` and `ph` for chi and phi, and `n` for gamma before a velar.
+    Classical,
+    /// BGN/PCGN (1996) for Modern Greek, which is ELOT 743: eta, iota and upsilon are
+    /// all `i` or `y` as pronounced, `αυ` and `ευ` are written `av`/`af` and `ev`/`ef`, and
+    /// `μπ`, `ντ` and `γκ` are `b`, `d` and `g` at the start of a word. Breathings and
+    /// iota subscripts are dropped.
+    ModernBgnPcgn,
+}
+
+impl GreekScheme {
+    fn edge_type(self) -> &'static str {
+        match self {
+            GreekScheme::Classical => "rom greek-classical",
+            GreekScheme::ModernBgnPcgn => "rom bgn-pcgn",
+        }
+    }
+}
+
+const ROUGH_BREATHING: char = '\u{0314}';
+const IOTA_SUBSCRIPT: char = '\u{0345}';
+const DIAERESIS: char = '\u{0308}';
+
+/// A Greek letter with the marks of its decomposition.
+struct Letter {
+    /// The span of the letter and of any combining marks after it.
+    start: usize,
+    end: usize,
+    /// The lowercase base letter, with `ς` as `σ`.
+    base: char,
+    upper: bool,
+    rough: bool,
+    iota_subscript: bool,
+    diaeresis: bool,
+}
+
+fn is_greek_letter(c: char) -> bool {
+    matches!(c, '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}') && c.is_alphabetic()
+}
+
+fn is_vowel(c: char) -> bool {
+    "αεηιουω".contains(c)
+}
+
+/// Returns the letters of each word of Greek in `chars`.
+fn greek_words(chars: &[char]) -> Vec<Vec<Letter>> {
+    let mut words = Vec::new();
+    let mut word: Vec<Letter> = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if let Some(letter) = word.last_mut()
+            && matches!(c, '\u{0300}'..='\u{036F}')
+        {
+            // Combining marks of decomposed input belong to the letter before them.
+            letter.end = i + 1;
+            letter.rough |= c == ROUGH_BREATHING;
+            letter.iota_subscript |= c == IOTA_SUBSCRIPT;
+            letter.diaeresis |= c == DIAERESIS;
+            continue;
+        }
+        if !is_greek_letter(c) {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        let decomposed: Vec<char> = c.nfd().collect();
+        let base = decomposed[0].to_lowercase().next().unwrap_or(decomposed[0]);
+        word.push(Letter {
+            start: i,
+            end: i + 1,
+            base: if base == 'ς' { 'σ' } else { base },
+            upper: decomposed[0].is_uppercase(),
+            rough: decomposed.contains(&ROUGH_BREATHING),
+            iota_subscript: decomposed.contains(&IOTA_SUBSCRIPT),
+            diaeresis: decomposed.contains(&DIAERESIS),
+        });
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn classical(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "a",
+        'β' => "b",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' => "e",
+        'ζ' => "z",
+        'η' => "ē",
+        'θ' => "th",
+        'ι' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' => "s",
+        'τ' => "t",
+        'υ' => "y",
+        'φ' => "ph",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' => "ō",
+        _ => return None,
+    })
+}
+
+fn modern(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' => "e",
+        'ζ' => "z",
+        'η' => "i",
+        'θ' => "th",
+        'ι' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' => "s",
+        'τ' => "t",
+        'υ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' => "o",
+        _ => return None,
+    })
+}
+
+/// Romanizes one word, returning the romanization of each span of its letters.
+fn romanize_word(word: &[Letter], scheme: GreekScheme) -> Vec<(usize, usize, String)> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < word.len() {
+        let letter = &word[i];
+        let next = word.get(i + 1);
+        let Some(mut txt) = (match scheme {
+            GreekScheme::Classical => classical(letter.base),
+            GreekScheme::ModernBgnPcgn => modern(letter.base),
+        })
+        .map(str::to_string) else {
+            i += 1;
+            continue;
+        };
+        let mut len = 1;
+
+        // `υ` is `u` as the second vowel of a diphthong, unless a diaeresis separates it.
+        let diphthong_upsilon = next.is_some_and(|next| {
+            next.base == 'υ' && !next.diaeresis && "αεηο".contains(letter.base)
+        });
+        match scheme {
+            GreekScheme::Classical => {
+                if diphthong_upsilon {
+                    txt.push('u');
+                    len = 2;
+                } else if letter.base == 'γ' && next.is_some_and(|next| "γκξχ".contains(next.base))
+                {
+                    txt = "n".to_string();
+                } else if letter.base == 'ρ'
+                    && (letter.rough || i == 0 || i > 0 && word[i - 1].base == 'ρ')
+                {
+                    txt = "rh".to_string();
+                }
+                if letter.iota_subscript {
+                    txt.push('i');
+                }
+            }
+            GreekScheme::ModernBgnPcgn => {
+                let initial = i == 0;
+                if diphthong_upsilon && letter.base == 'ο' {
+                    txt = "ou".to_string();
+                    len = 2;
+                } else if diphthong_upsilon {
+                    // `αυ`, `ευ` and `ηυ` are voiceless before a voiceless consonant and at
+                    // the end of a word.
+                    let voiceless = word
+                        .get(i + 2)
+                        .is_none_or(|after| "θκξπστφχψ".contains(after.base));
+                    txt.push(if voiceless { 'f' } else { 'v' });
+                    len = 2;
+                } else if let Some(next) = next
+                    && let Some(cluster) = match (letter.base, next.base) {
+                        ('μ', 'π') => Some(if initial { "b" } else { "mb" }),
+                        ('ν', 'τ') => Some(if initial { "d" } else { "nd" }),
+                        ('γ', 'κ') => Some(if initial { "g" } else { "ng" }),
+                        _ => None,
+                    }
+                {
+                    txt = cluster.to_string();
+                    len = 2;
+                } else if letter.base == 'γ' && next.is_some_and(|next| "γξχ".contains(next.base))
+                {
+                    txt = "n".to_string();
+                }
+            }
+        }
+
+        spans.push((letter.start, word[i + len - 1].end, txt));
+        i += len;
+    }
+
+    if scheme == GreekScheme::Classical
+        && word
+            .iter()
+            .take_while(|letter| is_vowel(letter.base))
+            .any(|letter| letter.rough)
+        && let Some((_, _, txt)) = spans.first_mut()
+    {
+        txt.insert(0, 'h');
+    }
+
+    // A word in capitals is written in capitals, and a capitalized word is capitalized.
+    let all_upper = word.len() > 1 && word.iter().all(|letter| letter.upper);
+    let mut letters = word.iter();
+    for (start, _, txt) in &mut spans {
+        let Some(letter) = letters.find(|letter| letter.start == *start) else {
+            continue;
+        };
+        if all_upper {
+            *txt = txt.to_uppercase();
+        } else if letter.upper {
+            *txt = capitalize(txt);
+        }
+    }
+    spans
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every Greek letter with its romanization under `scheme`.
+    pub fn apply_greek_scheme(&mut self, scheme: GreekScheme) {
+        let spans: Vec<(usize, usize, String)> = greek_words(&self.s_chars)
+            .iter()
+            .flat_map(|word| romanize_word(word, scheme))
+            .collect();
+        for (start, end, txt) in spans {
+            let edge = Edge::new_regular(start, end, txt, scheme.edge_type().to_string());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,54 @@
+//! Romanizes gzip-compressed files, behind the `gzip` feature.
+
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::{RomFormat, RomanizationError, Uroman};
+
+impl Uroman {
+    /// Romanizes a gzip-compressed file line by line, like `Uroman::romanize_file`, and
+    /// writes the output to `output_path`, compressed with gzip if `compress_output` is
+    /// `true`.
+    ///
+    /// Files of several concatenated gzip members, as written by `cat a.gz b.gz`, are read
+    /// as a whole. Other gzip streams, such as stdin, can be wrapped in the same way with
+    /// `flate2::read::MultiGzDecoder` and passed to `Uroman::romanize_file` directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RomanizationError::Io` if a file cannot be opened or created, or if the
+    /// input is not valid gzip.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use uroman::{RomFormat, Uroman};
+    /// let uroman = Uroman::new();
+    /// uroman.romanize_gzip_file("corpus.txt.gz", "corpus.rom.txt.gz", None, RomFormat::Str, true)?;
+    /// # Ok::<(), uroman::RomanizationError>(())
+    /// ```
+    pub fn romanize_gzip_file(
+        &self,
+        input_path: impl AsRef<Path>,
+        output_path: impl AsRef<Path>,
+        lcode: Option<&str>,
+        rom_format: RomFormat,
+        compress_output: bool,
+    ) -> Result<(), RomanizationError> {
+        let reader = BufReader::new(MultiGzDecoder::new(File::open(input_path)?));
+        let mut writer = BufWriter::new(File::create(output_path)?);
+
+        if compress_output {
+            let mut encoder = GzEncoder::new(writer, Compression::default());
+            self.romanize_file(reader, &mut encoder, lcode, rom_format, None, false, true)?;
+            encoder.finish()?.flush()?;
+        } else {
+            self.romanize_file(reader, &mut writer, lcode, rom_format, None, false, true)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Romanizes Hebrew from its niqqud, the vowel points and dagesh of vocalized text.
+//!
+//! Every run of Hebrew letters is romanized as a word here and replaces the edges of the
+//! romanization tables in the lattice. Words with vowel points are written with their
+//! vowels; words without them keep their consonant skeleton.
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+const SHEVA: char = '\u{05B0}';
+const PATAH: char = '\u{05B7}';
+const HOLAM: char = '\u{05B9}';
+const DAGESH: char = '\u{05BC}';
+const SIN_DOT: char = '\u{05C2}';
+
+/// A letter together with the marks that follow it.
+struct Unit {
+    start: usize,
+    end: usize,
+    letter: char,
+    marks: Vec<char>,
+}
+
+impl Unit {
+    fn has(&self, mark: char) -> bool {
+        self.marks.contains(&mark)
+    }
+
+    /// Returns the vowel point of the letter, if any.
+    fn vowel(&self) -> Option<char> {
+        self.marks
+            .iter()
+            .copied()
+            .find(|&mark| ('\u{05B0}'..='\u{05BB}').contains(&mark) || mark == '\u{05C7}')
+    }
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every run of Hebrew letters with a romanization that follows
+    /// its niqqud: vowel points are written as vowels, `ו` and `י` that carry or follow a
+    /// vowel are read as vowel letters, and a dagesh distinguishes `b`, `k` and `p` from
+    /// `v`, `kh` and `f` (e.g., שָׁלוֹם becomes `shalom` and מֶלֶךְ becomes `melekh`).
+    ///
+    /// Words without vowel points are written as their consonant skeleton, with `b`, `k`
+    /// and `p` at the start of a word, where these letters always have a dagesh
+    /// (e.g., שלום becomes `shlvm`).
+    pub fn apply_hebrew_niqqud(&mut self) {
+        for (start, end) in hebrew_words(&self.s_chars) {
+            let edges = romanize_word(&self.s_chars[start..end])
+                .into_iter()
+                .map(|(s, e, txt)| {
+                    Edge::new_regular(start + s, start + e, txt, "rom niqqud".to_string())
+                })
+                .collect();
+            self.replace_edges_in_span(start, end, edges);
+        }
+    }
+}
+
+/// Returns the `(start, end)` spans of the runs of Hebrew letters and their marks in `chars`.
+fn hebrew_words(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        if !is_hebrew_letter(chars[start]) {
+            start += 1;
+            continue;
+        }
+        let end = (start..chars.len())
+            .find(|&i| !is_hebrew_letter(chars[i]) && !is_hebrew_mark(chars[i]))
+            .unwrap_or(chars.len());
+        words.push((start, end));
+        start = end;
+    }
+    words
+}
+
+fn is_hebrew_letter(c: char) -> bool {
+    ('\u{05D0}'..='\u{05EA}').contains(&c)
+}
+
+/// Returns `true` for the cantillation marks and points, but not for the punctuation
+/// (maqaf, paseq, sof pasuq and nun hafukha) between them.
+fn is_hebrew_mark(c: char) -> bool {
+    ('\u{0591}'..='\u{05C7}').contains(&c)
+        && !matches!(c, '\u{05BE}' | '\u{05C0}' | '\u{05C3}' | '\u{05C6}')
+}
+
+/// Romanizes one Hebrew word, returning the romanization of the span of each letter.
+fn romanize_word(word: &[char]) -> Vec<(usize, usize, String)> {
+    let mut units: Vec<Unit> = Vec::new();
+    for (i, &c) in word.iter().enumerate() {
+        match units.last_mut() {
+            Some(unit) if is_hebrew_mark(c) => {
+                unit.marks.push(c);
+                unit.end = i + 1;
+            }
+            _ => units.push(Unit {
+                start: i,
+                end: i + 1,
+                letter: c,
+                marks: Vec::new(),
+            }),
+        }
+    }
+    let pointed = units.iter().any(|unit| unit.vowel().is_some());
+
+    let mut spans = Vec::new();
+    for (i, unit) in units.iter().enumerate() {
+        let prev_vowel = i.checked_sub(1).and_then(|prev| units[prev].vowel());
+        let last = i + 1 == units.len();
+
+        let txt = if !pointed {
+            consonant(unit, i == 0, pointed).to_string()
+        } else {
+            match (unit.letter, unit.vowel()) {
+                // Holam male and shuruq, unless the vav follows a vowel and is a consonant.
+                ('ו', Some(HOLAM)) if prev_vowel.is_none() => "o".to_string(),
+                ('ו', None) if unit.has(DAGESH) && prev_vowel.is_none() => "u".to_string(),
+                // Yod as the vowel letter of hiriq and tsere.
+                ('י', None) if matches!(prev_vowel, Some('\u{05B4}' | '\u{05B5}' | '\u{05B6}')) => {
+                    String::new()
+                }
+                // A furtive patah is pronounced before its final guttural.
+                ('ח' | 'ע', Some(PATAH)) if last => {
+                    format!("a{}", consonant(unit, i == 0, pointed))
+                }
+                ('ה', Some(PATAH)) if last && unit.has(DAGESH) => "ah".to_string(),
+                (_, vowel) => {
+                    let mut txt = consonant(unit, i == 0, pointed).to_string();
+                    txt.push_str(match vowel {
+                        // A sheva is only pronounced at the start of a word.
+                        Some(SHEVA) if i == 0 => "e",
+                        Some(SHEVA) | None => "",
+                        Some('\u{05B2}' | '\u{05B7}' | '\u{05B8}') => "a",
+                        Some('\u{05B1}' | '\u{05B5}' | '\u{05B6}') => "e",
+                        Some('\u{05B4}') => "i",
+                        Some('\u{05BB}') => "u",
+                        Some(_) => "o",
+                    });
+                    txt
+                }
+            }
+        };
+        spans.push((unit.start, unit.end, txt));
+    }
+    spans
+}
+
+/// Returns the romanization of the consonant of `unit`. `initial` is `true` at the start
+/// of a word, where `ב`, `כ` and `פ` are read with a dagesh in a word that is not
+/// `pointed`.
+fn consonant(unit: &Unit, initial: bool, pointed: bool) -> &'static str {
+    let plosive = unit.has(DAGESH) || (initial && !pointed);
+    match unit.letter {
+        'א' => "",
+        'ב' if plosive => "b",
+        'ב' => "v",
+        'ג' => "g",
+        'ד' => "d",
+        'ה' => "h",
+        'ו' => "v",
+        'ז' => "z",
+        'ח' => "ch",
+        'ט' => "t",
+        'י' => "y",
+        'כ' | 'ך' if plosive => "k",
+        'כ' | 'ך' => "kh",
+        'ל' => "l",
+        'מ' | 'ם' => "m",
+        'נ' | 'ן' => "n",
+        'ס' => "s",
+        'ע' if initial => "",
+        'ע' => "'",
+        'פ' | 'ף' if plosive => "p",
+        'פ' | 'ף' => "f",
+        'צ' | 'ץ' => "ts",
+        'ק' => "k",
+        'ר' => "r",
+        'ש' if unit.has(SIN_DOT) => "s",
+        'ש' => "sh",
+        'ת' => "t",
+        _ => "",
+    }
+}
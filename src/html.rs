@@ -0,0 +1,131 @@
+//! Splits HTML into markup, which is kept as is, and text, which is romanized.
+//!
+//! This is a tokenizer rather than a parser: it distinguishes tags, comments and
+//! character references from text, without building a tree or fixing invalid markup.
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// A character reference, such as `&amp;`, `&#1050;` or `&#x41A;`.
+static ENTITY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(?:[a-zA-Z][a-zA-Z0-9]*|#[0-9]+|#[xX][0-9a-fA-F]+);").unwrap());
+
+/// The quoted value of an `alt` or `title` attribute.
+static ATTRIBUTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)(\s(?:alt|title)\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Elements whose contents are not text and are kept as is.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Returns `html` with its text nodes, and the values of `alt` and `title` attributes,
+/// passed through `romanize`.
+///
+/// Character references are kept as is, so `romanize` is called on the text between them.
+pub(crate) fn romanize_html(html: &str, mut romanize: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let len = comment
+                .find("-->")
+                .map_or(rest.len(), |end| end + "<!---->".len());
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+        } else if let Some(len) = tag_len(rest) {
+            let tag = &rest[..len];
+            out.push_str(&ATTRIBUTE_RE.replace_all(tag, |caps: &Captures| {
+                let (quote, value) = match caps.get(2) {
+                    Some(value) => ('"', value.as_str()),
+                    None => ('\'', &caps[3]),
+                };
+                format!(
+                    "{}{quote}{}{quote}",
+                    &caps[1],
+                    romanize_text(value, &mut romanize)
+                )
+            }));
+            rest = &rest[len..];
+
+            if let Some(element) = RAW_TEXT_ELEMENTS
+                .iter()
+                .find(|element| tag_name(tag).eq_ignore_ascii_case(element))
+            {
+                let len =
+                    find_ignore_ascii_case(rest, &format!("</{element}")).unwrap_or(rest.len());
+                out.push_str(&rest[..len]);
+                rest = &rest[len..];
+            }
+        } else {
+            // Text runs to the next tag or comment; a `<` that starts neither is text.
+            let len = rest
+                .char_indices()
+                .skip(1)
+                .find(|&(i, c)| c == '<' && starts_markup(&rest[i..]))
+                .map_or(rest.len(), |(i, _)| i);
+            out.push_str(&romanize_text(&rest[..len], &mut romanize));
+            rest = &rest[len..];
+        }
+    }
+    out
+}
+
+/// Romanizes the text between the character references of `text`.
+fn romanize_text(text: &str, romanize: &mut impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for entity in ENTITY_RE.find_iter(text) {
+        if entity.start() > last {
+            out.push_str(&romanize(&text[last..entity.start()]));
+        }
+        out.push_str(entity.as_str());
+        last = entity.end();
+    }
+    if last < text.len() {
+        out.push_str(&romanize(&text[last..]));
+    }
+    out
+}
+
+fn starts_markup(s: &str) -> bool {
+    s.starts_with("<!--") || tag_len(s).is_some()
+}
+
+/// Returns the length of the tag at the start of `s`, if any.
+///
+/// A tag starts with `<` followed by a letter, `/`, `!` or `?`, and ends at the first `>`
+/// outside a quoted attribute value.
+fn tag_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    chars.next().filter(|&(_, c)| c == '<')?;
+    chars
+        .next()
+        .filter(|&(_, c)| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'))?;
+    let mut quote = None;
+    for (i, c) in chars {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the name of an opening `tag`, or an empty string for other tags.
+fn tag_name(tag: &str) -> &str {
+    let name = &tag[1..];
+    let len = name
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(name.len());
+    &name[..len]
+}
+
+/// Returns the byte offset of the first occurrence of the ASCII `needle` in `haystack`,
+/// ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
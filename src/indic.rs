@@ -0,0 +1,215 @@
+//! Romanizes Devanagari according to a selectable `IndicScheme`.
+//!
+//! Both schemes are transliterations: the inherent vowel `a` is written after every
+//! consonant without a vowel sign or virama, including at the end of a word, as in
+//! Sanskrit (e.g., वेद becomes `veda`). Like the Cyrillic schemes, they apply under
+//! any `lcode`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A transliteration scheme for Devanagari.
+///
+/// | Devanagari | `Iast`    | `Iso15919` |
+/// |------------|-----------|------------|
+/// | ऋषि        | ṛṣi       | r̥ṣi        |
+/// | संस्कृत      | saṃskṛta  | saṁskr̥ta   |
+/// | एक         | eka       | ēka        |
+/// | ओम्         | om        | ōm         |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IndicScheme {
+    /// The International Alphabet of Sanskrit Transliteration, with `ṛ`, `ḷ` and `ṃ`.
+    Iast,
+    /// ISO 15919, with `r̥`, `l̥` and `ṁ`, and `ē` and `ō` for ए and ओ so that they are
+    /// distinguished from the short ऎ and ऒ (`e` and `o`).
+    Iso15919,
+}
+
+impl IndicScheme {
+    fn edge_type(self) -> &'static str {
+        match self {
+            IndicScheme::Iast => "rom iast",
+            IndicScheme::Iso15919 => "rom iso15919",
+        }
+    }
+}
+
+const VIRAMA: char = '\u{094D}';
+const NUKTA: char = '\u{093C}';
+
+impl Lattice<'_> {
+    /// Replaces the edges of every Devanagari letter and sign with its transliteration
+    /// under `scheme`.
+    pub fn apply_indic_scheme(&mut self, scheme: IndicScheme) {
+        let chars = &self.s_chars;
+        let mut edges = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if let Some(consonant) = consonant(c, chars.get(i + 1) == Some(&NUKTA), scheme) {
+                let end = if chars.get(i + 1) == Some(&NUKTA) {
+                    i + 2
+                } else {
+                    i + 1
+                };
+                let mut txt = consonant.to_string();
+                if chars
+                    .get(end)
+                    .is_none_or(|&next| next != VIRAMA && vowel_sign(next, scheme).is_none())
+                {
+                    txt.push('a');
+                }
+                edges.push((i, end, txt));
+                i = end;
+                continue;
+            }
+            let txt = match c {
+                VIRAMA => Some(""),
+                _ => vowel_sign(c, scheme).or_else(|| letter(c, scheme)),
+            };
+            if let Some(txt) = txt {
+                edges.push((i, i + 1, txt.to_string()));
+            }
+            i += 1;
+        }
+
+        for (start, end, txt) in edges {
+            let edge = Edge::new_regular(start, end, txt, scheme.edge_type().to_string());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
+
+/// Returns the transliteration of a consonant without its inherent vowel, or `None`
+/// if `c` is not a consonant. Precomposed nukta forms are treated as `nukta` forms.
+fn consonant(c: char, nukta: bool, scheme: IndicScheme) -> Option<&'static str> {
+    let (c, nukta) = match c {
+        '\u{0958}'..='\u{095F}' => (
+            ['क', 'ख', 'ग', 'ज', 'ड', 'ढ', 'फ', 'य'][c as usize - 0x0958],
+            true,
+        ),
+        _ => (c, nukta),
+    };
+    if nukta {
+        let txt = match c {
+            'क' => "q",
+            'ख' => "k͟h",
+            'ग' => "ġ",
+            'ज' => "z",
+            'ड' => "ṛ",
+            'ढ' => "ṛh",
+            'फ' => "f",
+            'य' => "ẏ",
+            _ => return consonant(c, false, scheme),
+        };
+        return Some(txt);
+    }
+    let txt = match c {
+        'क' => "k",
+        'ख' => "kh",
+        'ग' => "g",
+        'घ' => "gh",
+        'ङ' => "ṅ",
+        'च' => "c",
+        'छ' => "ch",
+        'ज' => "j",
+        'झ' => "jh",
+        'ञ' => "ñ",
+        'ट' => "ṭ",
+        'ठ' => "ṭh",
+        'ड' => "ḍ",
+        'ढ' => "ḍh",
+        'ण' => "ṇ",
+        'त' => "t",
+        'थ' => "th",
+        'द' => "d",
+        'ध' => "dh",
+        'न' => "n",
+        'प' => "p",
+        'फ' => "ph",
+        'ब' => "b",
+        'भ' => "bh",
+        'म' => "m",
+        'य' => "y",
+        'र' => "r",
+        'ल' => "l",
+        'ळ' => match scheme {
+            IndicScheme::Iast => "ḻ",
+            IndicScheme::Iso15919 => "ḷ",
+        },
+        'व' => "v",
+        'श' => "ś",
+        'ष' => "ṣ",
+        'स' => "s",
+        'ह' => "h",
+        _ => return None,
+    };
+    Some(txt)
+}
+
+/// Returns the transliteration of a dependent vowel sign.
+fn vowel_sign(c: char, scheme: IndicScheme) -> Option<&'static str> {
+    let independent = match c {
+        'ा' => 'आ',
+        'ि' => 'इ',
+        'ी' => 'ई',
+        'ु' => 'उ',
+        'ू' => 'ऊ',
+        'ृ' => 'ऋ',
+        'ॄ' => 'ॠ',
+        'ॢ' => 'ऌ',
+        'ॣ' => 'ॡ',
+        'ॅ' => 'ऍ',
+        'ॆ' => 'ऎ',
+        'े' => 'ए',
+        'ै' => 'ऐ',
+        'ॉ' => 'ऑ',
+        'ॊ' => 'ऒ',
+        'ो' => 'ओ',
+        'ौ' => 'औ',
+        _ => return None,
+    };
+    letter(independent, scheme)
+}
+
+/// Returns the transliteration of an independent vowel or another sign.
+fn letter(c: char, scheme: IndicScheme) -> Option<&'static str> {
+    let iso = scheme == IndicScheme::Iso15919;
+    let txt = match c {
+        'अ' => "a",
+        'आ' => "ā",
+        'इ' => "i",
+        'ई' => "ī",
+        'उ' => "u",
+        'ऊ' => "ū",
+        'ऋ' if iso => "r̥",
+        'ऋ' => "ṛ",
+        'ॠ' if iso => "r̥̄",
+        'ॠ' => "ṝ",
+        'ऌ' if iso => "l̥",
+        'ऌ' => "ḷ",
+        'ॡ' if iso => "l̥̄",
+        'ॡ' => "ḹ",
+        'ऍ' => "ê",
+        'ऎ' => "e",
+        'ए' if iso => "ē",
+        'ए' => "e",
+        'ऐ' => "ai",
+        'ऑ' => "ô",
+        'ऒ' => "o",
+        'ओ' if iso => "ō",
+        'ओ' => "o",
+        'औ' => "au",
+        'ं' if iso => "ṁ",
+        'ं' => "ṃ",
+        'ँ' => "m̐",
+        'ः' => "ḥ",
+        'ऽ' => "'",
+        'ॐ' if iso => "ōṁ",
+        'ॐ' => "oṃ",
+        _ => return None,
+    };
+    Some(txt)
+}
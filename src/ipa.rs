@@ -0,0 +1,334 @@
+//! Transcribes kana, Hangul and Arabic in approximate IPA under `OutputTarget::Ipa`.
+//!
+//! The transcription is broad and phonemic: each kana mora, Hangul syllable and Arabic
+//! letter is transcribed on its own, without the sound changes across them (e.g., the
+//! liaison and nasalization of Korean, or the assimilation of the Arabic article).
+//! Unvocalized Arabic is transcribed without its short vowels.
+
+use serde::{Deserialize, Serialize};
+
+use crate::arabic::{arabic_words, split_units};
+use crate::edge::Edge;
+use crate::japanese::{is_kana, to_hiragana};
+use crate::korean::is_hangul_syllable;
+use crate::lattice::Lattice;
+
+/// `(start, end, txt)` spans relative to a run of characters.
+type Spans = Vec<(usize, usize, String)>;
+
+/// The alphabet of the output.
+///
+/// | Input  | `Latin`      | `Ipa`       |
+/// |--------|--------------|-------------|
+/// | すし   | sushi        | sɯɕi        |
+/// | 한국   | hangug       | hankuk̚      |
+/// | كِتَاب | kitaab       | kitaːb      |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutputTarget {
+    /// Latin letters, as by the romanization tables and schemes.
+    #[default]
+    Latin,
+    /// An approximate IPA transcription of kana, of Hangul, and of Arabic when `lcode`
+    /// is `ara`, in edges of the type `ipa`. Other text is romanized in Latin letters,
+    /// in edges whose type is prefixed with `latin`.
+    Ipa,
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every run of kana, Hangul and, if `lcode` is `ara`, Arabic
+    /// with their IPA transcription.
+    pub fn apply_ipa(&mut self, lcode: Option<&str>) {
+        let mut runs: Vec<(usize, usize, Spans)> = Vec::new();
+        for (start, end) in runs_of(&self.s_chars, is_kana) {
+            runs.push((start, end, kana_ipa(&self.s_chars[start..end])));
+        }
+        for (start, end) in runs_of(&self.s_chars, is_hangul_syllable) {
+            let spans = self.s_chars[start..end]
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| (i, i + 1, hangul_ipa(c)))
+                .collect();
+            runs.push((start, end, spans));
+        }
+        if lcode == Some("ara") {
+            for (start, end) in arabic_words(&self.s_chars) {
+                runs.push((start, end, arabic_ipa(&self.s_chars[start..end])));
+            }
+        }
+
+        for (start, end, spans) in runs {
+            let edges = spans
+                .into_iter()
+                .map(|(s, e, txt)| Edge::new_regular(start + s, start + e, txt, "ipa".to_string()))
+                .collect();
+            self.replace_edges_in_span(start, end, edges);
+        }
+    }
+}
+
+fn runs_of(chars: &[char], is_member: fn(char) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        if !is_member(chars[start]) {
+            start += 1;
+            continue;
+        }
+        let end = (start..chars.len())
+            .find(|&i| !is_member(chars[i]))
+            .unwrap_or(chars.len());
+        runs.push((start, end));
+        start = end;
+    }
+    runs
+}
+
+/// Returns the onset and vowel of a kana, in hiragana.
+fn mora(c: char) -> Option<(&'static str, &'static str)> {
+    let (onset, column) = match c {
+        'あ' | 'ぁ' => ("", 0),
+        'い' | 'ぃ' => ("", 1),
+        'う' | 'ぅ' => ("", 2),
+        'え' | 'ぇ' => ("", 3),
+        'お' | 'ぉ' => ("", 4),
+        'か' | 'ゕ' => ("k", 0),
+        'き' => ("k", 1),
+        'く' => ("k", 2),
+        'け' | 'ゖ' => ("k", 3),
+        'こ' => ("k", 4),
+        'が' => ("ɡ", 0),
+        'ぎ' => ("ɡ", 1),
+        'ぐ' => ("ɡ", 2),
+        'げ' => ("ɡ", 3),
+        'ご' => ("ɡ", 4),
+        'さ' => ("s", 0),
+        'し' => ("ɕ", 1),
+        'す' => ("s", 2),
+        'せ' => ("s", 3),
+        'そ' => ("s", 4),
+        'ざ' => ("dz", 0),
+        'じ' | 'ぢ' => ("dʑ", 1),
+        'ず' | 'づ' => ("dz", 2),
+        'ぜ' => ("dz", 3),
+        'ぞ' => ("dz", 4),
+        'た' => ("t", 0),
+        'ち' => ("tɕ", 1),
+        'つ' => ("ts", 2),
+        'て' => ("t", 3),
+        'と' => ("t", 4),
+        'だ' => ("d", 0),
+        'で' => ("d", 3),
+        'ど' => ("d", 4),
+        'な' => ("n", 0),
+        'に' => ("ɲ", 1),
+        'ぬ' => ("n", 2),
+        'ね' => ("n", 3),
+        'の' => ("n", 4),
+        'は' => ("h", 0),
+        'ひ' => ("ç", 1),
+        'ふ' => ("ɸ", 2),
+        'へ' => ("h", 3),
+        'ほ' => ("h", 4),
+        'ば' => ("b", 0),
+        'び' => ("b", 1),
+        'ぶ' => ("b", 2),
+        'べ' => ("b", 3),
+        'ぼ' => ("b", 4),
+        'ぱ' => ("p", 0),
+        'ぴ' => ("p", 1),
+        'ぷ' => ("p", 2),
+        'ぺ' => ("p", 3),
+        'ぽ' => ("p", 4),
+        'ま' => ("m", 0),
+        'み' => ("m", 1),
+        'む' => ("m", 2),
+        'め' => ("m", 3),
+        'も' => ("m", 4),
+        'や' | 'ゃ' => ("j", 0),
+        'ゆ' | 'ゅ' => ("j", 2),
+        'よ' | 'ょ' => ("j", 4),
+        'ら' => ("ɾ", 0),
+        'り' => ("ɾ", 1),
+        'る' => ("ɾ", 2),
+        'れ' => ("ɾ", 3),
+        'ろ' => ("ɾ", 4),
+        'わ' | 'ゎ' => ("w", 0),
+        'ゐ' => ("", 1),
+        'ゑ' => ("", 3),
+        'を' => ("", 4),
+        'ゔ' => ("v", 2),
+        'ん' => return Some(("ɴ", "")),
+        _ => return None,
+    };
+    Some((onset, ["a", "i", "ɯ", "e", "o"][column]))
+}
+
+/// Returns the onset of a kana of the `i` column before a small `ゃ`, `ゅ` or `ょ`.
+fn palatalize(onset: &'static str) -> String {
+    match onset {
+        "ɕ" | "dʑ" | "tɕ" | "ɲ" | "ç" => onset.to_string(),
+        _ => format!("{onset}ʲ"),
+    }
+}
+
+/// Transcribes a run of kana into `(start, end, txt)` spans relative to the run.
+///
+/// A small `っ` is merged into the span of the following mora, and a long vowel
+/// mark `ー` into the span of the preceding one.
+fn kana_ipa(kana: &[char]) -> Spans {
+    let hira: Vec<char> = kana.iter().map(|&c| to_hiragana(c)).collect();
+
+    let mut spans: Spans = Vec::new();
+    let mut i = 0;
+    while i < hira.len() {
+        let start = i;
+        let geminate = hira[i] == 'っ';
+        if geminate {
+            i += 1;
+        }
+
+        let Some(&c) = hira.get(i) else {
+            spans.push((start, i, "ʔ".to_string()));
+            break;
+        };
+        if c == 'ー' {
+            match spans.last_mut() {
+                Some((_, end, txt)) => {
+                    *end = i + 1;
+                    txt.push('ː');
+                }
+                None => spans.push((start, i + 1, "ː".to_string())),
+            }
+            i += 1;
+            continue;
+        }
+
+        let (base_onset, mut vowel) = mora(c).unwrap_or(("", ""));
+        let mut onset = base_onset.to_string();
+        i += 1;
+        if let Some((small_onset, small_vowel)) = hira.get(i).and_then(|&next| {
+            matches!(next, 'ゃ' | 'ゅ' | 'ょ' | 'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ')
+                .then(|| mora(next))
+                .flatten()
+        }) {
+            if small_onset == "j" && vowel == "i" {
+                onset = palatalize(base_onset);
+            } else if onset.is_empty() && vowel == "ɯ" {
+                onset = "w".to_string();
+            }
+            vowel = small_vowel;
+            i += 1;
+        }
+
+        let mut txt = String::new();
+        if geminate {
+            txt.push(onset.chars().next().unwrap_or('ʔ'));
+        }
+        txt.push_str(&onset);
+        txt.push_str(vowel);
+        spans.push((start, i, txt));
+    }
+    spans
+}
+
+/// Transcribes a Hangul syllable, with the final consonant neutralized.
+fn hangul_ipa(c: char) -> String {
+    const LEADS: [&str; 19] = [
+        "k", "k͈", "n", "t", "t͈", "ɾ", "m", "p", "p͈", "s", "s͈", "", "tɕ", "t͈ɕ", "tɕʰ", "kʰ", "tʰ",
+        "pʰ", "h",
+    ];
+    const VOWELS: [&str; 21] = [
+        "a", "ɛ", "ja", "jɛ", "ʌ", "e", "jʌ", "je", "o", "wa", "wɛ", "ø", "jo", "u", "wʌ", "we",
+        "y", "ju", "ɯ", "ɰi", "i",
+    ];
+    const TAILS: [&str; 28] = [
+        "", "k̚", "k̚", "k̚", "n", "n", "n", "t̚", "l", "k̚", "m", "l", "l", "l", "p̚", "l", "m", "p̚",
+        "p̚", "t̚", "t̚", "ŋ", "t̚", "t̚", "k̚", "t̚", "p̚", "t̚",
+    ];
+
+    let code = c as u32 - 0xAC00;
+    let lead = LEADS[(code / (21 * 28)) as usize];
+    let vowel = VOWELS[((code / 28) % 21) as usize];
+    let tail = TAILS[(code % 28) as usize];
+    format!("{lead}{vowel}{tail}")
+}
+
+fn arabic_consonant(letter: char) -> &'static str {
+    match letter {
+        'ء' | 'أ' | 'إ' | 'ؤ' | 'ئ' => "ʔ",
+        'ب' => "b",
+        'ت' => "t",
+        'ث' => "θ",
+        'ج' => "d͡ʒ",
+        'ح' => "ħ",
+        'خ' => "x",
+        'د' => "d",
+        'ذ' => "ð",
+        'ر' => "r",
+        'ز' => "z",
+        'س' => "s",
+        'ش' => "ʃ",
+        'ص' => "sˤ",
+        'ض' => "dˤ",
+        'ط' => "tˤ",
+        'ظ' => "ðˤ",
+        'ع' => "ʕ",
+        'غ' => "ɣ",
+        'ف' => "f",
+        'ق' => "q",
+        'ك' => "k",
+        'ل' => "l",
+        'م' => "m",
+        'ن' => "n",
+        'ه' => "h",
+        'و' => "w",
+        'ي' => "j",
+        _ => "",
+    }
+}
+
+/// Transcribes an Arabic word into `(start, end, txt)` spans relative to the word, one
+/// for each letter and its harakat.
+fn arabic_ipa(word: &[char]) -> Spans {
+    let mut spans: Spans = Vec::new();
+    for unit in split_units(word) {
+        let prev = spans.last().map_or("", |(_, _, txt)| txt.as_str());
+        let vowel = unit.marks.iter().find_map(|&mark| match mark {
+            '\u{064B}' => Some("an"),
+            '\u{064C}' => Some("un"),
+            '\u{064D}' => Some("in"),
+            '\u{064E}' => Some("a"),
+            '\u{064F}' => Some("u"),
+            '\u{0650}' => Some("i"),
+            '\u{0670}' => Some("aː"),
+            _ => None,
+        });
+
+        let txt = match unit.letter {
+            // A long vowel letter after its short vowel lengthens it.
+            'آ' => "ʔaː".to_string(),
+            'ا' | 'ى' if prev.ends_with("an") => String::new(),
+            'ا' | 'ى' if prev.ends_with('a') => "ː".to_string(),
+            'ا' | 'ى' if vowel.is_none() => "aː".to_string(),
+            'و' if vowel.is_none() && prev.ends_with('u') => "ː".to_string(),
+            'ي' if vowel.is_none() && prev.ends_with('i') => "ː".to_string(),
+            'ة' => match vowel {
+                Some(vowel) => format!("t{vowel}"),
+                None => "a".to_string(),
+            },
+            letter => {
+                let mut txt = arabic_consonant(letter).to_string();
+                if unit.marks.contains(&'\u{0651}') {
+                    txt.push('ː');
+                }
+                if letter == 'إ' && vowel.is_none() {
+                    txt.push('i');
+                }
+                txt.push_str(vowel.unwrap_or(""));
+                txt
+            }
+        };
+        spans.push((unit.start, unit.end, txt));
+    }
+    spans
+}
@@ -0,0 +1,431 @@
+//! Romanizes kana according to a selectable `JapaneseScheme`.
+//!
+//! Without a scheme, kana are romanized by the rules of the romanization tables.
+//! With a scheme, every run of kana is romanized here instead and replaces the
+//! edges of the tables in the lattice.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Japanese kana, used when `lcode` is `jpn`.
+///
+/// | Kana       | `Hepburn` | `Kunrei` | `Wapuro` |
+/// |------------|-----------|----------|----------|
+/// | し         | shi       | si       | shi      |
+/// | つ         | tsu       | tu       | tsu      |
+/// | ぢ         | ji        | zi       | di       |
+/// | を         | o         | o        | wo       |
+/// | きんえん   | kin'en    | kin'en   | kinnenn  |
+/// | とうきょう | tōkyō     | tôkyô    | toukyou  |
+/// | コーヒー   | kōhī      | kôhî     | ko-hi-   |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JapaneseScheme {
+    /// Modified Hepburn, with `n'` before vowels and `y` and macrons for long vowels.
+    Hepburn,
+    /// Kunrei-shiki (ISO 3602), with `n'` before vowels and `y` and circumflexes for long vowels.
+    Kunrei,
+    /// Wāpuro rōmaji as typed on a keyboard, spelling out every kana and `ー` as `-`.
+    Wapuro,
+}
+
+/// How long vowels of Japanese are written: doubled vowels such as `おう` and `おお`,
+/// and vowels lengthened by the chōonpu `ー`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LongVowelStyle {
+    /// Every vowel is written out, and `ー` repeats the preceding vowel (`koohii`).
+    Literal,
+    /// A macron over the long vowel (`kōhī`).
+    Macron,
+    /// A circumflex over the long vowel (`kôhî`).
+    Circumflex,
+}
+
+/// How a long vowel is written, including the `-` of `JapaneseScheme::Wapuro`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LongVowels {
+    Literal,
+    Macron,
+    Circumflex,
+    Hyphen,
+}
+
+impl From<LongVowelStyle> for LongVowels {
+    fn from(style: LongVowelStyle) -> Self {
+        match style {
+            LongVowelStyle::Literal => LongVowels::Literal,
+            LongVowelStyle::Macron => LongVowels::Macron,
+            LongVowelStyle::Circumflex => LongVowels::Circumflex,
+        }
+    }
+}
+
+impl JapaneseScheme {
+    fn edge_type(self) -> &'static str {
+        match self {
+            JapaneseScheme::Hepburn => "rom hepburn",
+            JapaneseScheme::Kunrei => "rom kunrei",
+            JapaneseScheme::Wapuro => "rom wapuro",
+        }
+    }
+
+    fn long_vowels(self) -> LongVowels {
+        match self {
+            JapaneseScheme::Hepburn => LongVowels::Macron,
+            JapaneseScheme::Kunrei => LongVowels::Circumflex,
+            JapaneseScheme::Wapuro => LongVowels::Hyphen,
+        }
+    }
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every run of kana with their romanization under `scheme`.
+    ///
+    /// `long_vowels` overrides the long vowel convention of the scheme.
+    pub fn apply_japanese_scheme(
+        &mut self,
+        scheme: JapaneseScheme,
+        long_vowels: Option<LongVowelStyle>,
+    ) {
+        let long_vowels = long_vowels.map_or(scheme.long_vowels(), LongVowels::from);
+        let len = self.s_chars.len();
+        let mut start = 0;
+        while start < len {
+            if !is_kana(self.s_chars[start]) {
+                start += 1;
+                continue;
+            }
+            let end = (start..len)
+                .find(|&i| !is_kana(self.s_chars[i]))
+                .unwrap_or(len);
+
+            let edges = romanize_kana(&self.s_chars[start..end], scheme, long_vowels)
+                .into_iter()
+                .map(|(s, e, txt)| {
+                    Edge::new_regular(start + s, start + e, txt, scheme.edge_type().to_string())
+                })
+                .collect();
+            self.replace_edges_in_span(start, end, edges);
+            start = end;
+        }
+    }
+}
+
+pub(crate) fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | 'ゝ' | 'ゞ' | '\u{30A1}'..='\u{30FA}' | 'ー' | 'ヽ' | 'ヾ')
+}
+
+/// Maps katakana to the corresponding hiragana, keeping `ヷ`-`ヺ` and `ー`.
+pub(crate) fn to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        'ヽ' => 'ゝ',
+        'ヾ' => 'ゞ',
+        _ => c,
+    }
+}
+
+/// Romanizes a run of kana into `(start, end, txt)` spans relative to the run.
+///
+/// A small `っ` is merged into the span of the following mora, and a long vowel
+/// into the span of the preceding one.
+fn romanize_kana(
+    kana: &[char],
+    scheme: JapaneseScheme,
+    long_vowels: LongVowels,
+) -> Vec<(usize, usize, String)> {
+    let hira: Vec<char> = kana.iter().map(|&c| to_hiragana(c)).collect();
+
+    let mut spans = Vec::new();
+    let mut prev_base = None;
+    let mut i = 0;
+    while i < hira.len() {
+        let start = i;
+        let geminate = hira[i] == 'っ';
+        if geminate {
+            i += 1;
+        }
+
+        let Some((rom, len, base)) = mora_at(&hira, i, prev_base, scheme) else {
+            // A `っ` without a following mora, or a `ー` without a preceding vowel.
+            let mut txt = String::new();
+            if !geminate {
+                if hira[i] == 'ー' && long_vowels == LongVowels::Hyphen {
+                    txt.push('-');
+                }
+                i += 1;
+            }
+            spans.push((start, i, txt));
+            continue;
+        };
+        i += len;
+        prev_base = Some(base);
+
+        let mut txt = if geminate {
+            gemination_prefix(&rom, scheme) + &rom
+        } else {
+            rom
+        };
+        if base == 'ん' {
+            if scheme == JapaneseScheme::Wapuro {
+                txt.push('n');
+            } else if mora_at(&hira, i, prev_base, scheme)
+                .is_some_and(|(next, _, _)| next.starts_with(['a', 'i', 'u', 'e', 'o', 'y']))
+            {
+                txt.push('\'');
+            }
+        } else {
+            i += extend_long_vowel(&mut txt, &hira[i..], long_vowels);
+        }
+        spans.push((start, i, txt));
+    }
+    spans
+}
+
+/// Returns the romanization of the mora at `i`, the number of kana it spans, and its base kana.
+fn mora_at(
+    hira: &[char],
+    i: usize,
+    prev_base: Option<char>,
+    scheme: JapaneseScheme,
+) -> Option<(String, usize, char)> {
+    let base = match *hira.get(i)? {
+        'ゝ' => prev_base?,
+        'ゞ' => voiced(prev_base?),
+        c => c,
+    };
+    if let Some(&small) = hira.get(i + 1)
+        && let Some(rom) = combine(base, small, scheme)
+    {
+        return Some((rom, 2, base));
+    }
+    Some((single(base, scheme)?.to_string(), 1, base))
+}
+
+/// Returns the voiced counterpart of `c` (e.g., `か` to `が`), or `c` if it has none.
+fn voiced(c: char) -> char {
+    let next = char::from_u32(c as u32 + 1).unwrap_or(c);
+    if "がぎぐげござじずぜぞだぢづでどばびぶべぼ".contains(next) {
+        next
+    } else {
+        c
+    }
+}
+
+/// Romanizes a single hiragana (or one of `ヷ`-`ヺ`).
+fn single(c: char, scheme: JapaneseScheme) -> Option<&'static str> {
+    let overridden = match scheme {
+        JapaneseScheme::Hepburn => None,
+        JapaneseScheme::Kunrei => match c {
+            'し' => Some("si"),
+            'ち' => Some("ti"),
+            'つ' => Some("tu"),
+            'ふ' => Some("hu"),
+            'じ' | 'ぢ' => Some("zi"),
+            'づ' => Some("zu"),
+            _ => None,
+        },
+        JapaneseScheme::Wapuro => match c {
+            'ぢ' => Some("di"),
+            'づ' => Some("du"),
+            'を' => Some("wo"),
+            'ゐ' => Some("wi"),
+            'ゑ' => Some("we"),
+            _ => None,
+        },
+    };
+    if overridden.is_some() {
+        return overridden;
+    }
+
+    let rom = match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        'ヷ' => "va",
+        'ヸ' => "vi",
+        'ヹ' => "ve",
+        'ヺ' => "vo",
+        _ => return None,
+    };
+    Some(rom)
+}
+
+/// Romanizes a kana followed by a small kana that modifies it (e.g., `きょ`, `ファ`).
+fn combine(base: char, small: char, scheme: JapaneseScheme) -> Option<String> {
+    let base_rom = single(base, scheme)?;
+    let is_i_row = base_rom.len() > 1 && base_rom.ends_with('i');
+
+    let vowel = match small {
+        'ゃ' => 'a',
+        'ゅ' => 'u',
+        'ょ' => 'o',
+        'ゎ' if matches!(base, 'く' | 'ぐ') => return Some(format!("{}wa", &base_rom[..1])),
+        'ぁ' => 'a',
+        'ぃ' => 'i',
+        'ぅ' => 'u',
+        'ぇ' => 'e',
+        'ぉ' => 'o',
+        _ => return None,
+    };
+
+    if matches!(small, 'ゃ' | 'ゅ' | 'ょ') {
+        return is_i_row.then(|| format!("{}{vowel}", yoon_stem(base_rom)));
+    }
+    let rom = match (base, vowel) {
+        ('ふ', v) if v != 'u' => format!("f{v}"),
+        ('ゔ', v) if v != 'u' => format!("v{v}"),
+        ('う', v @ ('i' | 'e' | 'o')) => format!("w{v}"),
+        ('つ', v) if v != 'u' => format!("ts{v}"),
+        ('て', 'i') => "ti".to_string(),
+        ('で', 'i') => "di".to_string(),
+        ('と', 'u') => "tu".to_string(),
+        ('ど', 'u') => "du".to_string(),
+        ('く', 'a') => "kwa".to_string(),
+        ('ぐ', 'a') => "gwa".to_string(),
+        (_, 'e') if is_i_row => format!("{}e", yoon_stem(base_rom)),
+        _ => return None,
+    };
+    Some(rom)
+}
+
+/// Returns the consonant of a contracted sound (yōon) based on the i-row kana `rom`.
+fn yoon_stem(rom: &str) -> String {
+    let stem = &rom[..rom.len() - 1];
+    if matches!(rom, "shi" | "chi" | "ji") {
+        stem.to_string()
+    } else {
+        format!("{stem}y")
+    }
+}
+
+/// Returns the doubled consonant written for a small `っ` before `rom`.
+fn gemination_prefix(rom: &str, scheme: JapaneseScheme) -> String {
+    if scheme != JapaneseScheme::Kunrei && rom.starts_with("ch") {
+        return "t".to_string();
+    }
+    match rom.chars().next() {
+        Some(c) if !"aiueo".contains(c) => c.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Extends `txt` with a long vowel at the start of `rest`, returning the number of kana consumed.
+fn extend_long_vowel(txt: &mut String, rest: &[char], long_vowels: LongVowels) -> usize {
+    let Some(last) = txt.chars().last().filter(|c| "aiueo".contains(*c)) else {
+        return 0;
+    };
+
+    let marks = rest.iter().take_while(|&&c| c == 'ー').count();
+    match long_vowels {
+        LongVowels::Literal => {
+            txt.extend(std::iter::repeat_n(last, marks));
+            return marks;
+        }
+        LongVowels::Hyphen => {
+            txt.extend(std::iter::repeat_n('-', marks));
+            return marks;
+        }
+        LongVowels::Macron | LongVowels::Circumflex => {}
+    }
+
+    let doubles = match rest {
+        ['ー', ..] => true,
+        [next, small, ..] if combine(*next, *small, JapaneseScheme::Hepburn).is_some() => false,
+        [next, ..] => matches!(
+            (last, next),
+            ('a', 'あ') | ('u', 'う') | ('e', 'え') | ('o', 'お') | ('o', 'う')
+        ),
+        [] => false,
+    };
+    if !doubles {
+        return 0;
+    }
+
+    txt.pop();
+    txt.push(accented(last, long_vowels));
+    1 + rest[1..].iter().take_while(|&&c| c == 'ー').count()
+}
+
+fn accented(vowel: char, long_vowels: LongVowels) -> char {
+    match (long_vowels, vowel) {
+        (LongVowels::Macron, 'a') => 'ā',
+        (LongVowels::Macron, 'i') => 'ī',
+        (LongVowels::Macron, 'u') => 'ū',
+        (LongVowels::Macron, 'e') => 'ē',
+        (LongVowels::Macron, 'o') => 'ō',
+        (LongVowels::Circumflex, 'a') => 'â',
+        (LongVowels::Circumflex, 'i') => 'î',
+        (LongVowels::Circumflex, 'u') => 'û',
+        (LongVowels::Circumflex, 'e') => 'ê',
+        (LongVowels::Circumflex, 'o') => 'ô',
+        _ => vowel,
+    }
+}
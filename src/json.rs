@@ -0,0 +1,97 @@
+//! Serializes a romanization lattice for `RomFormat::Json` and `RomFormat::Jsonl`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::Edge;
+
+/// An edge of the lattice with offsets in character, byte and UTF-16 units.
+#[derive(Serialize)]
+struct JsonEdge<'a> {
+    start: usize,
+    end: usize,
+    byte_start: usize,
+    byte_end: usize,
+    utf16_start: usize,
+    utf16_end: usize,
+    txt: &'a str,
+    r#type: &'a str,
+    is_numeric: bool,
+    value: Option<f64>,
+    alternatives: Vec<&'a str>,
+}
+
+/// Serializes the edges of a lattice over `s_chars` to a compact JSON array.
+///
+/// Alternative (`rom-alt*`) edges are not emitted on their own but listed as the
+/// `alternatives` of the other edges with the same span.
+pub(crate) fn lattice_to_json(s_chars: &[char], edges: &[Edge]) -> String {
+    // byte_offsets[i] and utf16_offsets[i] are the offsets of the i-th character.
+    let mut byte_offsets = Vec::with_capacity(s_chars.len() + 1);
+    let mut utf16_offsets = Vec::with_capacity(s_chars.len() + 1);
+    let (mut byte_offset, mut utf16_offset) = (0, 0);
+    for c in s_chars {
+        byte_offsets.push(byte_offset);
+        utf16_offsets.push(utf16_offset);
+        byte_offset += c.len_utf8();
+        utf16_offset += c.len_utf16();
+    }
+    byte_offsets.push(byte_offset);
+    utf16_offsets.push(utf16_offset);
+
+    let mut alternatives: HashMap<(usize, usize), Vec<&str>> = HashMap::new();
+    for alt in edges.iter().filter(|edge| edge.is_alternative()) {
+        alternatives
+            .entry((alt.start(), alt.end()))
+            .or_default()
+            .push(alt.txt());
+    }
+
+    // Edges of the same span come from a `HashSet`, so sort them for a stable output.
+    let mut sorted_edges: Vec<&Edge> = edges
+        .iter()
+        .filter(|edge| !edge.is_alternative())
+        .collect();
+    sorted_edges.sort_by(|a, b| {
+        (a.start(), b.end(), a.r#type(), a.txt()).cmp(&(b.start(), a.end(), b.r#type(), b.txt()))
+    });
+
+    let json_edges: Vec<JsonEdge> = sorted_edges
+        .into_iter()
+        .map(|edge| {
+            let (start, end) = (edge.start(), edge.end());
+            JsonEdge {
+                start,
+                end,
+                byte_start: byte_offsets[start],
+                byte_end: byte_offsets[end],
+                utf16_start: utf16_offsets[start],
+                utf16_end: utf16_offsets[end],
+                txt: edge.txt(),
+                r#type: edge.r#type(),
+                is_numeric: edge.is_numeric(),
+                value: edge.value(),
+                alternatives: alternatives.get(&(start, end)).cloned().unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    serde_json::to_string(&json_edges).expect("Serializing edges to JSON cannot fail")
+}
+
+/// A line of `RomFormat::Jsonl` output.
+#[derive(Serialize)]
+struct JsonlRecord<'a> {
+    orig: &'a str,
+    rom: &'a str,
+    edges: &'a [Edge],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lcode: Option<&'a str>,
+}
+
+/// Serializes the original text, romanization and edges of one input to a JSON object
+/// on a single line, with the language code of its `::lcode` directive, if any.
+pub(crate) fn jsonl_record(orig: &str, rom: &str, edges: &[Edge], lcode: Option<&str>) -> String {
+    serde_json::to_string(&JsonlRecord { orig, rom, edges, lcode })
+        .expect("Serializing edges to JSON cannot fail")
+}
@@ -0,0 +1,326 @@
+//! Romanizes Hangul according to a selectable `KoreanScheme`.
+//!
+//! Without a scheme, each Hangul syllable is romanized jamo by jamo. With a scheme,
+//! every run of Hangul syllables is romanized as a word, applying the sound changes
+//! between syllables (liaison, nasalization, lateralization, palatalization and
+//! aspiration) that both schemes transcribe.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Korean Hangul, used when `lcode` is `kor`.
+///
+/// | Hangul | `RevisedRomanization` | `McCuneReischauer` |
+/// |--------|-----------------------|--------------------|
+/// | 부산   | busan                 | pusan              |
+/// | 한국   | hanguk                | han'guk            |
+/// | 서울   | seoul                 | sŏul               |
+/// | 김치   | gimchi                | kimch'i            |
+/// | 독립문 | dongnimmun            | tongnimmun         |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KoreanScheme {
+    /// The Revised Romanization of Korean (2000), the official system of South Korea.
+    RevisedRomanization,
+    /// McCune-Reischauer, with breves (`ŏ`, `ŭ`), apostrophes for aspirates and voicing
+    /// of plain stops between voiced sounds.
+    McCuneReischauer,
+}
+
+impl KoreanScheme {
+    fn edge_type(self) -> &'static str {
+        match self {
+            KoreanScheme::RevisedRomanization => "rom rr",
+            KoreanScheme::McCuneReischauer => "rom mr",
+        }
+    }
+}
+
+/// The sound of a syllable-final consonant after neutralization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coda {
+    K,
+    N,
+    T,
+    L,
+    M,
+    P,
+    Ng,
+}
+
+// Indices of the initial consonants (leads) in the Hangul syllable block.
+const G: u32 = 0;
+const N: u32 = 2;
+const D: u32 = 3;
+const R: u32 = 5;
+const M: u32 = 6;
+const B: u32 = 7;
+const S: u32 = 9;
+const NULL: u32 = 11;
+const J: u32 = 12;
+const CH: u32 = 14;
+const K: u32 = 15;
+const T: u32 = 16;
+const P: u32 = 17;
+const H: u32 = 18;
+
+// Index of the vowel `ㅣ`.
+const I: u32 = 20;
+
+// Indices of the final consonants (tails) ending in `ㅎ` and of `ㅇ`.
+const TAIL_NH: u32 = 6;
+const TAIL_LH: u32 = 15;
+const TAIL_NG: u32 = 21;
+const TAIL_H: u32 = 27;
+
+struct Syllable {
+    lead: u32,
+    vowel: u32,
+    tail: u32,
+    coda: Option<Coda>,
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every run of Hangul syllables with their romanization under `scheme`.
+    pub fn apply_korean_scheme(&mut self, scheme: KoreanScheme) {
+        let len = self.s_chars.len();
+        let mut start = 0;
+        while start < len {
+            if !is_hangul_syllable(self.s_chars[start]) {
+                start += 1;
+                continue;
+            }
+            let end = (start..len)
+                .find(|&i| !is_hangul_syllable(self.s_chars[i]))
+                .unwrap_or(len);
+
+            let edges = romanize_word(&self.s_chars[start..end], scheme)
+                .into_iter()
+                .enumerate()
+                .map(|(i, txt)| {
+                    Edge::new_regular(start + i, start + i + 1, txt, scheme.edge_type().to_string())
+                })
+                .collect();
+            self.replace_edges_in_span(start, end, edges);
+            start = end;
+        }
+    }
+}
+
+pub(crate) fn is_hangul_syllable(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7A3}').contains(&c)
+}
+
+/// Romanizes a run of Hangul syllables, returning the romanization of each syllable.
+fn romanize_word(word: &[char], scheme: KoreanScheme) -> Vec<String> {
+    let mut syllables: Vec<Syllable> = word
+        .iter()
+        .map(|&c| {
+            let code = c as u32 - 0xAC00;
+            Syllable {
+                lead: code / (21 * 28),
+                vowel: (code / 28) % 21,
+                tail: code % 28,
+                coda: None,
+            }
+        })
+        .collect();
+
+    for i in 0..syllables.len() {
+        let tail = syllables[i].tail;
+        let Some(next) = syllables.get(i + 1) else {
+            syllables[i].coda = neutralize(tail);
+            break;
+        };
+        let (next_lead, next_vowel) = (next.lead, next.vowel);
+
+        // Liaison: the final consonant moves to a following syllable without an initial.
+        if next_lead == NULL && tail != 0 && tail != TAIL_NG {
+            let (remaining, mut moved) = liaison(tail);
+            if next_vowel == I {
+                // Palatalization (e.g., 같이 to `gachi`).
+                moved = match moved {
+                    D => J,
+                    T => CH,
+                    other => other,
+                };
+            }
+            syllables[i].coda = neutralize(remaining);
+            syllables[i + 1].lead = moved;
+            continue;
+        }
+
+        let mut coda = neutralize(tail);
+        let mut lead = next_lead;
+        if matches!(tail, TAIL_H | TAIL_NH | TAIL_LH) && matches!(next_lead, G | D | J) {
+            // Aspiration (e.g., 좋다 to `jota`).
+            lead = match next_lead {
+                G => K,
+                D => T,
+                _ => CH,
+            };
+            coda = match tail {
+                TAIL_NH => Some(Coda::N),
+                TAIL_LH => Some(Coda::L),
+                _ => None,
+            };
+        } else {
+            match (coda, next_lead) {
+                // Lateralization (e.g., 신라 to `silla`).
+                (Some(Coda::N | Coda::L), R) => coda = Some(Coda::L),
+                (Some(Coda::L), N) => lead = R,
+                // Nasalization of `ㄹ` (e.g., 종로 to `jongno`, 독립 to `dongnip`).
+                (Some(Coda::M | Coda::Ng), R) => lead = N,
+                (Some(Coda::K | Coda::T | Coda::P), R) => {
+                    lead = N;
+                    coda = coda.map(nasalize);
+                }
+                // Nasalization of stops (e.g., 국민 to `gungmin`).
+                (Some(Coda::K | Coda::T | Coda::P), N | M) => coda = coda.map(nasalize),
+                _ => {}
+            }
+        }
+        syllables[i].coda = coda;
+        syllables[i + 1].lead = lead;
+    }
+
+    let mut prev_coda = None;
+    syllables
+        .iter()
+        .enumerate()
+        .map(|(i, syllable)| {
+            let mut rom = String::new();
+            match scheme {
+                KoreanScheme::RevisedRomanization => {
+                    rom.push_str(rr_lead(syllable.lead, prev_coda));
+                    rom.push_str(RR_VOWELS[syllable.vowel as usize]);
+                }
+                KoreanScheme::McCuneReischauer => {
+                    if syllable.lead == G && prev_coda == Some(Coda::N) {
+                        // Distinguishes `n'g` (ㄴㄱ) from `ng` (ㅇ).
+                        rom.push('\'');
+                    }
+                    rom.push_str(mr_lead(syllable.lead, i == 0, prev_coda));
+                    rom.push_str(MR_VOWELS[syllable.vowel as usize]);
+                }
+            }
+            rom.push_str(match syllable.coda {
+                None => "",
+                Some(Coda::K) => "k",
+                Some(Coda::N) => "n",
+                Some(Coda::T) => "t",
+                Some(Coda::L) => "l",
+                Some(Coda::M) => "m",
+                Some(Coda::P) => "p",
+                Some(Coda::Ng) => "ng",
+            });
+            prev_coda = syllable.coda;
+            rom
+        })
+        .collect()
+}
+
+/// Returns the sound of the final consonant `tail` before a consonant or at the end of a word.
+fn neutralize(tail: u32) -> Option<Coda> {
+    match tail {
+        1 | 2 | 3 | 9 | 24 => Some(Coda::K),
+        4..=6 => Some(Coda::N),
+        7 | 19 | 20 | 22 | 23 | 25 | 27 => Some(Coda::T),
+        8 | 11 | 12 | 13 | 15 => Some(Coda::L),
+        10 | 16 => Some(Coda::M),
+        14 | 17 | 18 | 26 => Some(Coda::P),
+        21 => Some(Coda::Ng),
+        _ => None,
+    }
+}
+
+fn nasalize(coda: Coda) -> Coda {
+    match coda {
+        Coda::K => Coda::Ng,
+        Coda::T => Coda::N,
+        Coda::P => Coda::M,
+        other => other,
+    }
+}
+
+/// Splits the final consonant `tail` before a vowel into the tail that remains and the
+/// lead that moves to the next syllable.
+fn liaison(tail: u32) -> (u32, u32) {
+    match tail {
+        1 => (0, G),
+        2 => (0, 1),
+        3 => (1, S),
+        4 => (0, N),
+        5 => (4, J),
+        6 => (0, N),
+        7 => (0, D),
+        8 => (0, R),
+        9 => (8, G),
+        10 => (8, M),
+        11 => (8, B),
+        12 => (8, S),
+        13 => (8, T),
+        14 => (8, P),
+        15 => (0, R),
+        16 => (0, M),
+        17 => (0, B),
+        18 => (17, S),
+        19 => (0, S),
+        20 => (0, 10),
+        22 => (0, J),
+        23 => (0, CH),
+        24 => (0, K),
+        25 => (0, T),
+        26 => (0, P),
+        // `ㅎ` is silent before a vowel.
+        _ => (0, NULL),
+    }
+}
+
+const RR_LEADS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+
+const RR_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+
+const MR_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "ŏ", "e", "yŏ", "ye", "o", "wa", "wae", "oe", "yo", "u", "wŏ", "we",
+    "wi", "yu", "ŭ", "ŭi", "i",
+];
+
+fn rr_lead(lead: u32, prev_coda: Option<Coda>) -> &'static str {
+    if lead == R && prev_coda == Some(Coda::L) {
+        return "l";
+    }
+    RR_LEADS[lead as usize]
+}
+
+fn mr_lead(lead: u32, word_start: bool, prev_coda: Option<Coda>) -> &'static str {
+    if lead == R && prev_coda == Some(Coda::L) {
+        return "l";
+    }
+    // Plain stops are voiced between voiced sounds.
+    let voiced = !word_start && !matches!(prev_coda, Some(Coda::K | Coda::T | Coda::P));
+    match lead {
+        G if voiced => "g",
+        G => "k",
+        D if voiced => "d",
+        D => "t",
+        B if voiced => "b",
+        B => "p",
+        J if voiced => "j",
+        J => "ch",
+        13 => "tch",
+        CH => "ch'",
+        K => "k'",
+        T => "t'",
+        P => "p'",
+        H => "h",
+        _ => RR_LEADS[lead as usize],
+    }
+}
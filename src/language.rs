@@ -0,0 +1,83 @@
+//! Defines `LangInfo`, which describes a language supported by a `Uroman` instance.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::core::UromanInner;
+use crate::script::Script;
+
+/// A language supported by a `Uroman` instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LangInfo {
+    /// The ISO 639-3 language code (e.g., `ukr`).
+    pub code: String,
+    /// The primary English name of the language (e.g., `Ukrainian`).
+    pub name: String,
+    /// The scripts with rules or script data specific to the language.
+    ///
+    /// This is empty for languages that are only romanized by the universal rules.
+    pub scripts: Vec<Script>,
+}
+
+impl UromanInner {
+    /// Returns `true` if `lcode` is a known language code that has not been trimmed by
+    /// `UromanBuilder::with_languages`.
+    pub(crate) fn is_language_enabled(&self, lcode: &str) -> bool {
+        self.is_known_language(lcode)
+            && self
+                .enabled_languages
+                .as_ref()
+                .is_none_or(|languages| languages.contains(lcode))
+    }
+
+    /// Returns the enabled languages, sorted by code.
+    pub(crate) fn supported_languages(&self) -> Vec<LangInfo> {
+        let mut scripts: HashMap<&str, BTreeSet<Script>> = HashMap::new();
+
+        // Scripts of the source text of language-specific rules.
+        for rule in self.rom_rules.values().flatten() {
+            let Some(c) = rule.s.chars().next() else {
+                continue;
+            };
+            let script = Script::from_name(&self.chr_script_name(c));
+            if script == Script::Common {
+                continue;
+            }
+            for lcode in &rule.lcodes {
+                scripts.entry(lcode).or_default().insert(script.clone());
+            }
+        }
+
+        // Scripts that list the language by its English name in `Scripts.txt`.
+        let codes_by_name: HashMap<&str, &str> = self
+            .language_names
+            .iter()
+            .map(|(code, name)| (name.as_str(), code.as_str()))
+            .collect();
+        for script in self.scripts.values() {
+            for language in &script.languages {
+                if let Some(code) = codes_by_name.get(language.as_str()) {
+                    scripts
+                        .entry(code)
+                        .or_default()
+                        .insert(Script::from_name(&script.script_name));
+                }
+            }
+        }
+
+        let mut languages: Vec<LangInfo> = self
+            .language_names
+            .iter()
+            .filter(|(code, _)| self.is_language_enabled(code))
+            .map(|(code, name)| LangInfo {
+                code: code.clone(),
+                name: name.clone(),
+                scripts: scripts
+                    .remove(code.as_str())
+                    .map(|scripts| scripts.into_iter().collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        languages.sort_by(|a, b| a.code.cmp(&b.code));
+        languages
+    }
+}
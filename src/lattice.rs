@@ -1,5 +1,5 @@
 use crate::decompositions::DECOMPOSITIONS;
-use crate::edge::{Edge, EdgeData, NumData, NumDataUpdates};
+use crate::edge::{Edge, EdgeData, ExactInt, NumData, NumDataUpdates, exact_product, exact_sum};
 use crate::rom_rule::RomRule;
 use crate::{Uroman, rom_format};
 use crate::core::{AbugidaRuleType, UromanInner};
@@ -91,6 +91,8 @@ pub(super) struct Lattice<'a> {
     pub simple_top_rom_cache: HashMap<(usize, usize), Option<String>>,
     // self.contains_script: defaultdict(bool)
     pub contains_script: HashMap<String, bool>,
+    /// The character offsets of the spaces inserted between words by `segment_words`.
+    pub inserted_spaces: Vec<usize>,
 }
 
 #[allow(unused)]
@@ -101,7 +103,7 @@ pub enum BackwardsPathResult {
 }
 
 impl<'a> Lattice<'a> {
-    pub fn new(s: &'a str, uroman: &'a UromanInner, lcode: Option<&str>) -> Self {
+    pub fn new(s: &str, uroman: &'a UromanInner, lcode: Option<&str>) -> Self {
         let s_chars: Vec<char> = s.chars().collect();
         let max_vertex = s_chars.len();
 
@@ -117,6 +119,7 @@ impl<'a> Lattice<'a> {
             props: HashMap::new(),
             simple_top_rom_cache: HashMap::new(),
             contains_script: HashMap::new(),
+            inserted_spaces: Vec::new(),
         };
 
         lattice.check_for_scripts();
@@ -152,6 +155,46 @@ impl<'a> Lattice<'a> {
         self.left_links.entry(end).or_default().insert(start);
     }
 
+    /// Replaces every edge that overlaps `start..end` with `edges`.
+    ///
+    /// Used by romanization schemes that romanize a whole span themselves.
+    pub fn replace_edges_in_span(&mut self, start: usize, end: usize, edges: Vec<Edge>) {
+        self.retain_edges(|edge| edge.end() <= start || edge.start() >= end);
+        for edge in edges {
+            self.add_edge(edge);
+        }
+    }
+
+    /// Removes every edge for which `keep` returns `false`.
+    pub fn retain_edges(&mut self, mut keep: impl FnMut(&Edge) -> bool) {
+        for edges in self.edge_lattice.values_mut() {
+            edges.retain(|edge| keep(edge));
+        }
+        self.edge_lattice.retain(|_, edges| !edges.is_empty());
+
+        self.right_links.clear();
+        self.left_links.clear();
+        let spans: Vec<(usize, usize)> = self.edge_lattice.keys().copied().collect();
+        for (start, end) in spans {
+            self.right_links.entry(start).or_default().insert(end);
+            self.left_links.entry(end).or_default().insert(start);
+        }
+    }
+
+    /// Removes every edge with an `Edge::score` below `min_score`.
+    ///
+    /// A position whose edges all score below `min_score` keeps its highest-scoring
+    /// edges, so that every position that had an edge still has one and a full path
+    /// through the lattice always exists.
+    pub fn prune_edges(&mut self, min_score: f32) {
+        let mut best_scores: HashMap<usize, f32> = HashMap::new();
+        for edge in self.edge_lattice.values().flatten() {
+            let best = best_scores.entry(edge.start()).or_insert(f32::MIN);
+            *best = best.max(edge.score());
+        }
+        self.retain_edges(|edge| edge.score() >= min_score.min(best_scores[&edge.start()]));
+    }
+
     fn cand_is_valid(&mut self, rule: &RomRule, start: usize, end: usize) -> bool {
         let lcode_ok = rule.lcodes.is_empty()
             || self
@@ -357,6 +400,30 @@ impl<'a> Lattice<'a> {
         matches!(edge, Edge::Numeric { num_data, .. } if num_data.orig_txt == "零" || num_data.orig_txt == "〇")
     }
 
+    /// Returns `true` if `c` separates the integer and fractional digits of the number
+    /// that starts with `digit_edge`. The Arabic decimal separator `٫` is only used
+    /// with Arabic-Indic digits.
+    fn is_decimal_separator(&self, c: char, digit_edge: &Edge) -> bool {
+        match c {
+            '.' => true,
+            '\u{066B}' => digit_edge
+                .get_script()
+                .is_some_and(|script| script.ends_with("Arabic-Indic")),
+            _ => false,
+        }
+    }
+
+    /// Returns `false` if the digits `int_digits` at `start` are a group of a larger number,
+    /// which a fraction must not be attached to: they follow a digit and a thousands
+    /// separator, or they have a leading zero (e.g., `000` in `1,000.5`).
+    fn may_take_fraction(&self, start: usize, int_digits: &str) -> bool {
+        let follows_group = start >= 2
+            && matches!(self.s_chars[start - 1], ',' | '\u{066C}' | '\'' | '\u{2019}')
+            && self.s_chars[start - 2].is_numeric();
+        let zero_led = int_digits.len() > 1 && int_digits.starts_with('0');
+        !(follows_group || zero_led)
+    }
+
     fn edge_is_digit(edge: &Edge) -> bool {
         if let Edge::Numeric { data, num_data } = edge
             && data.r#type == "digit"
@@ -505,8 +572,9 @@ impl<'a> Lattice<'a> {
             GeneralCategory::NonspacingMark => ("".to_string(), "Mn".to_string()),
             // Cf (Format) -> empty string
             GeneralCategory::Format => ("".to_string(), "Cf".to_string()),
-            // Co (PrivateUse) -> empty string
-            GeneralCategory::PrivateUse => ("".to_string(), "Co".to_string()),
+            // Co (PrivateUse) and Cn (Unassigned) -> passed through for `Fallback`
+            GeneralCategory::PrivateUse => (orig_char.to_string(), "Co".to_string()),
+            GeneralCategory::Unassigned => (orig_char.to_string(), "Cn".to_string()),
             // Zs (SpaceSeparator) -> half-width space
             GeneralCategory::SpaceSeparator => (" ".to_string(), "Zs".to_string()),
             _ => {
@@ -543,7 +611,9 @@ impl<'a> Lattice<'a> {
                     if end2 <= end
                         && let Some(edges_for_span) = self.edge_lattice.get(&(start2, end2))
                     {
-                        result.extend(edges_for_span.iter().cloned());
+                        let mut edges: Vec<Edge> = edges_for_span.iter().cloned().collect();
+                        edges.sort_by(tie_break);
+                        result.extend(edges);
                     } else {
                         break;
                     }
@@ -554,6 +624,10 @@ impl<'a> Lattice<'a> {
         result
     }
 
+    /// Returns the edge of the span that the best path takes.
+    ///
+    /// Several edges of the same priority in a span are decided by `tie_break`, so that the
+    /// choice does not depend on the iteration order of the edge set.
     pub fn best_edge_in_span(&self, start: usize, end: usize, skip_num_edge: bool) -> Option<Edge> {
         let edges = self.edge_lattice.get(&(start, end))?;
 
@@ -563,28 +637,28 @@ impl<'a> Lattice<'a> {
         let mut decomp_edge = None;
         let mut other_edge = None;
 
+        // Keeps the edge that sorts first under `tie_break`.
+        fn keep_first<'e>(best: &mut Option<&'e Edge>, edge: &'e Edge) {
+            if best.is_none_or(|best| tie_break(edge, best).is_lt()) {
+                *best = Some(edge);
+            }
+        }
         for edge in edges {
             match edge {
                 Edge::Numeric { .. } if !skip_num_edge => {
                     if edge.is_active() {
-                        if active_num_edge.is_none() {
-                            active_num_edge = Some(edge.clone());
-                        }
-                    } else if inactive_num_edge.is_none() {
-                        inactive_num_edge = Some(edge.clone());
+                        keep_first(&mut active_num_edge, edge);
+                    } else {
+                        keep_first(&mut inactive_num_edge, edge);
                     }
                 }
                 Edge::Regular(data) => {
                     if data.r#type.starts_with("rom decomp") {
-                        if decomp_edge.is_none() {
-                            decomp_edge = Some(edge.clone());
-                        }
+                        keep_first(&mut decomp_edge, edge);
                     } else if data.r#type.starts_with("rom") || data.r#type.starts_with("num") {
-                        if rom_edge.is_none() {
-                            rom_edge = Some(edge.clone());
-                        }
-                    } else if other_edge.is_none() {
-                        other_edge = Some(edge.clone());
+                        keep_first(&mut rom_edge, edge);
+                    } else {
+                        keep_first(&mut other_edge, edge);
                     }
                 }
                 _ => {}
@@ -597,6 +671,7 @@ impl<'a> Lattice<'a> {
             .or(decomp_edge)
             .or(other_edge)
             .or(inactive_num_edge)
+            .cloned()
     }
 
     // fn find_first_rom_candidate_with_target(&self, start: usize, end: usize) -> Option<String> {
@@ -1042,7 +1117,7 @@ impl<'a> Lattice<'a> {
             // Remove commas as they cause issues in numerical parsers
             let clean_txt = txt.replace(',', "");
             if let Ok(value) = clean_txt.parse::<f64>() {
-                let new_edge = Edge::new_combined_numeric(
+                let mut new_edge = Edge::new_combined_numeric(
                     start_pos,
                     end_pos,
                     value,
@@ -1052,6 +1127,10 @@ impl<'a> Lattice<'a> {
                     None,                        // n_decimals
                     txt,
                 );
+                new_edge.update(NumDataUpdates {
+                    exact: clean_txt.parse().ok(),
+                    ..Default::default()
+                });
                 self.add_edge(new_edge);
             }
         }
@@ -1109,6 +1188,7 @@ impl<'a> Lattice<'a> {
         self.apply_f1_final_adjustments();
         self.deactivate_exceptional_singles(&mut active_edges);
         self.add_fallback_unicode_numbers();
+        self.tag_ordinals();
     }
 
     #[inline]
@@ -1133,11 +1213,11 @@ impl<'a> Lattice<'a> {
 
             'seq: while j < prev_pass_edges.len() {
                 let next_edge = &prev_pass_edges[j];
-                if next_edge.start() != last_edge_end {
+                if !Self::edge_is_digit(next_edge) {
                     break 'seq;
                 }
 
-                if Self::edge_is_digit(next_edge) {
+                if next_edge.start() == last_edge_end {
                     sub_edges.push(next_edge.clone());
                     combined_orig_txt.push_str(next_edge.orig_txt());
                     current_val_s.push_str(next_edge.txt());
@@ -1146,23 +1226,17 @@ impl<'a> Lattice<'a> {
                     }
                     last_edge_end = next_edge.end();
                 } else if n_decimals.is_none()
-                    && self.s_chars.get(last_edge_end..last_edge_end + 1) == Some(&['.'])
+                    && next_edge.start() == last_edge_end + 1
+                    && self.is_decimal_separator(self.s_chars[last_edge_end], start_edge)
+                    && self.may_take_fraction(start_edge.start(), &current_val_s)
                 {
-                    if let Some(digit_after_dot) = prev_pass_edges
-                        .get(j)
-                        .filter(|e| e.start() == last_edge_end + 1 && Self::edge_is_digit(e))
-                    {
-                        sub_edges.push(digit_after_dot.clone());
-                        combined_orig_txt.push('.');
-                        combined_orig_txt.push_str(digit_after_dot.orig_txt());
-                        current_val_s.push('.');
-                        current_val_s.push_str(digit_after_dot.txt());
-                        n_decimals = Some(1);
-                        last_edge_end = digit_after_dot.end();
-                        j += 1;
-                    } else {
-                        break 'seq;
-                    }
+                    sub_edges.push(next_edge.clone());
+                    combined_orig_txt.push(self.s_chars[last_edge_end]);
+                    combined_orig_txt.push_str(next_edge.orig_txt());
+                    current_val_s.push('.');
+                    current_val_s.push_str(next_edge.txt());
+                    n_decimals = Some(1);
+                    last_edge_end = next_edge.end();
                 } else {
                     break 'seq;
                 }
@@ -1186,6 +1260,7 @@ impl<'a> Lattice<'a> {
 
                 let updates = NumDataUpdates {
                     value: Some(new_value),
+                    exact: n_decimals.is_none().then(|| current_val_s.parse().ok()).flatten(),
                     value_s: Some(current_val_s),
                     ..Default::default()
                 };
@@ -1228,7 +1303,7 @@ impl<'a> Lattice<'a> {
 
                 if left.end() == right.start() && is_single_digit && is_base_number {
                     let new_value = left_val * right_val;
-                    let new_edge = Edge::new_combined_numeric(
+                    let mut new_edge = Edge::new_combined_numeric(
                         left.start(),
                         right.end(),
                         new_value,
@@ -1238,6 +1313,10 @@ impl<'a> Lattice<'a> {
                         None,
                         format!("{}{}", left.orig_txt(), right.orig_txt()),
                     );
+                    new_edge.update(NumDataUpdates {
+                        exact: exact_product(left.exact(), right.exact()),
+                        ..Default::default()
+                    });
                     self.add_edge(new_edge.clone());
                     next_pass_edges.push(new_edge);
                     i += 2;
@@ -1256,7 +1335,7 @@ impl<'a> Lattice<'a> {
         let mut i = 0;
         while i < prev_pass_edges.len() {
             let start_edge = &prev_pass_edges[i];
-            if start_edge.is_large_power() || start_edge.value().is_none() {
+            if start_edge.is_large_power() || start_edge.whole_value().is_none() {
                 next_pass_edges.push(start_edge.clone());
                 i += 1;
                 continue;
@@ -1278,7 +1357,7 @@ impl<'a> Lattice<'a> {
                 let can_combine = if prev_non_null.is_some_and(|p| p.is_large_power()) {
                     false
                 } else if let (Some(prev), Some(right_val), Some(right_base)) =
-                    (prev_non_null, right_edge.value(), right_edge.get_num_base())
+                    (prev_non_null, right_edge.whole_value(), right_edge.get_num_base())
                 {
                     prev.get_num_base().unwrap_or(0) > right_val as i64
                         && prev.get_num_base().unwrap_or(0) > right_base
@@ -1296,9 +1375,9 @@ impl<'a> Lattice<'a> {
             }
 
             if sub_edges.len() > 1 {
-                let new_value: f64 = sub_edges.iter().map(|e| e.value().unwrap_or(0.0)).sum();
+                let new_value: f64 = sub_edges.iter().map(|e| e.whole_value().unwrap_or(0.0)).sum();
                 let last = sub_edges.last().unwrap();
-                let new_edge = Edge::new_combined_numeric(
+                let mut new_edge = Edge::new_combined_numeric(
                     start_edge.start(),
                     last.end(),
                     new_value,
@@ -1308,6 +1387,10 @@ impl<'a> Lattice<'a> {
                     None,
                     sub_edges.iter().map(|e| e.orig_txt()).collect::<String>(),
                 );
+                new_edge.update(NumDataUpdates {
+                    exact: exact_sum(sub_edges.iter().map(|e| e.exact())),
+                    ..Default::default()
+                });
                 self.add_edge(new_edge.clone());
                 next_pass_edges.push(new_edge);
                 i += sub_edges.len();
@@ -1331,12 +1414,12 @@ impl<'a> Lattice<'a> {
                 if left.end() == right.start()
                     && left.is_active()
                     && !left.is_large_power()
-                    && left.value().is_some()
+                    && left.whole_value().is_some()
                     && right.is_active()
                     && right.is_large_power()
-                    && right.value().is_some()
+                    && right.whole_value().is_some()
                 {
-                    let new_value = left.value().unwrap() * right.value().unwrap();
+                    let new_value = left.whole_value().unwrap() * right.whole_value().unwrap();
                     let mut new_edge = Edge::new_combined_numeric(
                         left.start(),
                         right.end(),
@@ -1347,6 +1430,10 @@ impl<'a> Lattice<'a> {
                         None,
                         format!("{}{}", left.orig_txt(), right.orig_txt()),
                     );
+                    new_edge.update(NumDataUpdates {
+                        exact: exact_product(left.exact(), right.exact()),
+                        ..Default::default()
+                    });
                     if let Some(nd) = new_edge.get_num_data_mut() {
                         nd.is_large_power = true;
                     }
@@ -1368,7 +1455,7 @@ impl<'a> Lattice<'a> {
         let mut i = 0;
         while i < prev_pass_edges.len() {
             let start_edge = &prev_pass_edges[i];
-            if start_edge.value().is_none() {
+            if start_edge.whole_value().is_none() {
                 next_pass_edges.push(start_edge.clone());
                 i += 1;
                 continue;
@@ -1382,7 +1469,7 @@ impl<'a> Lattice<'a> {
                 let mut right_edge = raw_right_edge.clone();
                 if right_edge.start() != last_edge_end
                     || !right_edge.is_active()
-                    || right_edge.value().is_none()
+                    || right_edge.whole_value().is_none()
                 {
                     break;
                 }
@@ -1394,17 +1481,18 @@ impl<'a> Lattice<'a> {
                     && prev_base >= 1000
                     && is_power_of_10(prev_base)
                     && right_edge.end() - right_edge.start() == 1
-                    && (1.0..=9.0).contains(&right_edge.value().unwrap())
+                    && (1.0..=9.0).contains(&right_edge.whole_value().unwrap())
                     && let Some(num_data) = right_edge.get_num_data_mut()
                 {
                     let new_num_base = prev_base / 10;
                     num_data.value = Some(new_num_base as f64 * num_data.value.unwrap());
+                    num_data.exact = exact_product(Some(&ExactInt::from(new_num_base)), num_data.exact.as_ref());
                     num_data.num_base = Some(new_num_base);
                     right_edge.get_data_mut().r#type = "G4tag".to_string();
                 }
 
                 let can_combine = if let (Some(right_val), Some(right_base)) =
-                    (right_edge.value(), right_edge.get_num_base())
+                    (right_edge.whole_value(), right_edge.get_num_base())
                 {
                     prev_base > right_val as i64 && prev_base > right_base
                 } else {
@@ -1421,9 +1509,9 @@ impl<'a> Lattice<'a> {
             }
 
             if sub_edges.len() > 1 {
-                let new_value: f64 = sub_edges.iter().map(|e| e.value().unwrap_or(0.0)).sum();
+                let new_value: f64 = sub_edges.iter().map(|e| e.whole_value().unwrap_or(0.0)).sum();
                 let last = sub_edges.last().unwrap();
-                let new_edge = Edge::new_combined_numeric(
+                let mut new_edge = Edge::new_combined_numeric(
                     start_edge.start(),
                     last.end(),
                     new_value,
@@ -1433,6 +1521,10 @@ impl<'a> Lattice<'a> {
                     None,
                     sub_edges.iter().map(|e| e.orig_txt()).collect::<String>(),
                 );
+                new_edge.update(NumDataUpdates {
+                    exact: exact_sum(sub_edges.iter().map(|e| e.exact())),
+                    ..Default::default()
+                });
                 self.add_edge(new_edge.clone());
                 next_pass_edges.push(new_edge);
                 i += sub_edges.len();
@@ -1455,7 +1547,7 @@ impl<'a> Lattice<'a> {
             .map(|m| (m, "percentage"))
             .chain(self.uroman.fraction_connectors.iter().map(|c| (c, "fraction")))
             .collect();
-        markers.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        markers.sort_by_key(|(m, _)| std::cmp::Reverse(m.len()));
 
         // Use a label to efficiently skip to the next start position once a match is found.
         'outer: for start in 0..self.s_chars.len() {
@@ -1472,8 +1564,8 @@ impl<'a> Lattice<'a> {
                         if left_edge.end() != start || right_edge.start() != marker_end { continue; }
 
                         if let (Some(left_val), Some(right_val)) = (
-                            left_edge.value().and_then(|v| if v.fract() == 0.0 { Some(v as i64) } else { None }),
-                            right_edge.value().and_then(|v| if v.fract() == 0.0 { Some(v as i64) } else { None })
+                            left_edge.whole_value().and_then(|v| if v.fract() == 0.0 { Some(v as i64) } else { None }),
+                            right_edge.whole_value().and_then(|v| if v.fract() == 0.0 { Some(v as i64) } else { None })
                         ) {
                             let combined_start = left_edge.start();
                             let combined_end = right_edge.end();
@@ -1494,6 +1586,7 @@ impl<'a> Lattice<'a> {
                                         end: combined_end,
                                         txt: format!("{right_val}/{left_val}"),
                                         r#type: "fraction".to_string(),
+                                        source_script: None,
                                     },
                                     num_data: NumData {
                                         orig_txt: format!("{}/{}", right_val, left_val),
@@ -1551,7 +1644,7 @@ impl<'a> Lattice<'a> {
 
     #[inline]
     fn apply_g6_plus_minus_signs(&mut self, active_edges: &[Edge]) {
-        for edge in active_edges.iter().filter(|e| e.value().is_some()) {
+        for edge in active_edges.iter().filter(|e| e.whole_value().is_some()) {
             let edge_start_char_idx = edge.start();
 
             for minus_sign in &self.uroman.minus_signs {
@@ -2255,3 +2348,13 @@ fn is_power_of_10(mut n: i64) -> bool {
     }
     n == 1
 }
+
+/// Orders the edges of one span from the most to the least preferred, for a choice between
+/// edges that is reproducible across runs: by decreasing `Edge::score`, then by romanized
+/// text and then by type.
+fn tie_break(a: &Edge, b: &Edge) -> std::cmp::Ordering {
+    b.score()
+        .total_cmp(&a.score())
+        .then_with(|| a.txt().cmp(b.txt()))
+        .then_with(|| a.r#type().cmp(b.r#type()))
+}
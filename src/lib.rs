@@ -6,30 +6,108 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+#[cfg(feature = "parallel")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
 use std::any::TypeId;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt;
 use std::io::{self, BufRead, Write};
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, LazyLock};
 use thiserror::Error;
 
-pub use crate::edge::Edge;
+pub use crate::annotate::AnnotationGranularity;
+pub use crate::arabic::ArabicScheme;
+pub use crate::builder::{BuildError, UromanBuilder};
+pub use crate::cache::CacheStats;
+pub use crate::chinese::{ChineseScheme, ChineseTones, Jyutping};
+pub use crate::cyrillic::CyrillicScheme;
+pub use crate::edge::{Edge, LatticeNode};
+pub use crate::fallback::Fallback;
+pub use crate::georgian::GeorgianScheme;
+pub use crate::greek::GreekScheme;
+pub use crate::indic::IndicScheme;
+pub use crate::ipa::OutputTarget;
+pub use crate::japanese::{JapaneseScheme, LongVowelStyle};
+pub use crate::korean::KoreanScheme;
+pub use crate::language::LangInfo;
+pub use crate::options::{InvalidUtf8, Normalization, NumericMode};
+pub use crate::ruby::RubyGrouping;
+pub use crate::script::Script;
+#[cfg(feature = "async")]
+pub use crate::stream::RomanizeStream;
+pub use crate::symbol::EmojiPolicy;
+pub use crate::tibetan::TibetanScheme;
+pub use crate::validate::RuleWarning;
+use crate::cache::TokenCache;
 use crate::core::UromanInner;
+use crate::options::RomanizationOptions;
 use crate::lattice::Lattice;
-use crate::utils::decode_unicode_escapes;
-
+use crate::utils::{decode_unicode_escapes, panic_message};
+
+mod annotate;
+mod arabic;
+mod builder;
+mod cache;
+mod chinese;
+mod compiled;
 mod core;
+mod cyrillic;
 mod decompositions;
 mod edge;
+mod fallback;
+mod georgian;
+mod greek;
+mod hebrew;
+mod html;
+mod indic;
+mod ipa;
+mod japanese;
+mod json;
+mod korean;
+mod language;
 mod lattice;
+mod mongolian;
+mod nbest;
+mod options;
+mod ordinal;
+mod reversible;
 mod rom_rule;
+mod roman;
+mod ruby;
+mod scheme;
+mod script;
+mod segment;
+mod sentence;
+mod symbol;
+mod tibetan;
+mod tokens;
 mod utils;
+mod validate;
+mod verbatim;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "gzip")]
+mod gzip;
 
 #[cfg(feature = "python")]
 mod python;
 
+#[cfg(feature = "async")]
+mod stream;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 use rom_rule::RomRule;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -39,6 +117,17 @@ pub enum RomFormat {
     Edges,
     Alts,
     Lattice,
+    /// The edges of `Lattice` grouped into `LatticeNode`s, one for each position, whose
+    /// edges serialize with their `score`.
+    LatticeNodes,
+    /// The full lattice with alternatives, serialized to a JSON array string.
+    Json,
+    /// A JSON object with the input, its romanization and the edges of the best path,
+    /// `{"orig": ..., "rom": ..., "edges": [...]}`, on a single line. `romanize_file`
+    /// writes one object per input line, which makes a JSON Lines file. For a line with an
+    /// `::lcode` directive, `orig` is the text after the directive, and the object has an
+    /// `"lcode"` field with its language code.
+    Jsonl,
 }
 
 pub(crate) use rom_format::RomFormatType;
@@ -52,6 +141,7 @@ pub mod rom_format {
     pub struct Edges;
     pub struct Alts;
     pub struct Lattice;
+    pub struct Json;
 
     pub trait RomFormatType {
         type Output;
@@ -73,10 +163,15 @@ pub mod rom_format {
         type Output = Result<String, RomanizationError>;
     }
 
+    impl RomFormatType for Json {
+        type Output = String;
+    }
+
     pub trait IsStrFormat {}
     pub trait IsEdgeFormat {}
 
     impl IsStrFormat for Str {}
+    impl IsStrFormat for Json {}
     impl IsEdgeFormat for Edges {}
     impl IsEdgeFormat for Alts {}
     impl IsEdgeFormat for Lattice {}
@@ -100,7 +195,7 @@ impl<F: RomFormatType + IsStrFormat> RomanizationOutput<F> {
     pub fn as_str(&self) -> &str {
         match &self.result {
             RomanizationResult::Str(s) => s,
-            RomanizationResult::Edges(_) => unreachable!(),
+            RomanizationResult::Edges(_) | RomanizationResult::Lattice(_) => unreachable!(),
         }
     }
 }
@@ -109,7 +204,7 @@ impl<F: RomFormatType + IsEdgeFormat> RomanizationOutput<F> {
     pub fn to_edges(self) -> Vec<Edge> {
         match self.result {
             RomanizationResult::Edges(edges) => edges,
-            RomanizationResult::Str(_) => {
+            RomanizationResult::Str(_) | RomanizationResult::Lattice(_) => {
                 panic!("Logic error: An edge-based format produced a string result.")
             }
         }
@@ -120,11 +215,63 @@ impl<F: RomFormatType + IsEdgeFormat> RomanizationOutput<F> {
     pub fn as_edges(&self) -> &[Edge] {
         match &self.result {
             RomanizationResult::Edges(edges) => edges,
-            RomanizationResult::Str(_) => {
+            RomanizationResult::Str(_) | RomanizationResult::Lattice(_) => {
                 panic!("Logic error: An edge-based format produced a string result.")
             }
         }
     }
+
+    /// Returns every edge whose romanized text equals `txt`.
+    ///
+    /// Use `Edge::source_range` on the results to trace a romanization back to its source.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// # let uroman = Uroman::new();
+    /// let result = uroman.romanize_string::<rom_format::Lattice>("しし", Some("jpn"));
+    ///
+    /// let ranges: Vec<_> = result.edges_matching("shi").iter().map(|e| e.source_range()).collect();
+    /// assert_eq!(ranges, vec![0..1, 1..2]);
+    /// ```
+    pub fn edges_matching(&self, txt: &str) -> Vec<&Edge> {
+        self.as_edges().iter().filter(|edge| edge.txt() == txt).collect()
+    }
+
+    /// Returns the edges as a Graphviz DOT digraph, for inspecting competing edges.
+    ///
+    /// Nodes are the positions between characters at which edges start or end, and
+    /// every edge is labeled with its romanized text, type and score.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// # let uroman = Uroman::new();
+    /// let dot = uroman.romanize_string::<rom_format::Lattice>("北京", None).to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph lattice {"));
+    /// assert!(dot.contains("0 -> 2 [label=\"beijing\\nrom\\n0.90\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        edge::edges_to_dot(self.as_edges())
+    }
+}
+
+impl RomanizationOutput<rom_format::Lattice> {
+    /// Returns the edges of the lattice grouped by the position they start at.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// # let uroman = Uroman::new();
+    /// let nodes = uroman.romanize_string::<rom_format::Lattice>("しし", Some("jpn")).to_nodes();
+    ///
+    /// assert_eq!(nodes.len(), 3);
+    /// assert!(nodes[0].edges.iter().any(|edge| edge.end() == 1 && edge.txt() == "shi"));
+    /// ```
+    pub fn to_nodes(self) -> Vec<LatticeNode> {
+        LatticeNode::from_edges(self.to_edges())
+    }
 }
 
 impl<'a, F> IntoIterator for &'a RomanizationOutput<F>
@@ -187,10 +334,13 @@ where
 }
 
 #[derive(Debug, Serialize, Clone, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
 #[serde(untagged)]
 pub enum RomanizationResult {
     Str(String),
     Edges(Vec<Edge>),
+    /// The lattice of all edges, grouped by the position they start at.
+    Lattice(Vec<LatticeNode>),
 }
 
 impl RomanizationResult {
@@ -198,6 +348,7 @@ impl RomanizationResult {
         match self {
             RomanizationResult::Str(s) => Ok(s.clone()),
             RomanizationResult::Edges(edges) => Ok(serde_json::to_string_pretty(edges)?),
+            RomanizationResult::Lattice(nodes) => Ok(serde_json::to_string_pretty(nodes)?),
         }
     }
 }
@@ -221,6 +372,58 @@ pub enum RomanizationError {
 
     #[error("Internal logic error: {0}")]
     InternalError(String),
+
+    /// `start` and `end` are byte offsets into the romanized input, as are the offsets of
+    /// `Unmapped` and of the alignment: if the instance normalizes its input, they refer to
+    /// the normalized text.
+    #[error("No romanization rule for the characters at {start}..{end}")]
+    UnromanizableSpan { start: usize, end: usize },
+
+    #[error("A romanization rule failed internally: {0}")]
+    InternalRuleError(String),
+
+    #[error("Line {line} is not a romanization rule")]
+    InvalidRule { line: usize },
+
+    #[error("Invalid UTF-8 at byte {byte_offset}")]
+    InvalidUtf8 { byte_offset: usize },
+
+    #[error("The romanization of {input_len} bytes exceeds the limit of {limit} bytes")]
+    ExpansionLimit { input_len: usize, limit: usize },
+}
+
+/// A character that was passed through unchanged because no romanization rule covers it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Unmapped {
+    pub char: char,
+    /// Byte offset of the character in the romanized input.
+    pub byte_offset: usize,
+    /// The Unicode script of the character, if known (e.g., `CJK`).
+    pub script: Option<String>,
+}
+
+/// A replacement of a byte range of the source, as returned by `Uroman::romanize_edits`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edit {
+    /// Byte range of the source text that is replaced.
+    pub source: Range<usize>,
+    /// The romanization of the range.
+    pub replacement: String,
+}
+
+/// Pairs of source and romanized byte ranges, as returned by `Uroman::romanize_with_alignment`.
+pub type Alignment = Vec<(Range<usize>, Range<usize>)>;
+
+/// Prefix of a line in a file that sets the language code for the rest of the line.
+const LCODE_DIRECTIVE: &str = "::lcode ";
+
+/// The number of characters before `ch` that `Uroman::romanize_char` consults.
+const CHAR_CONTEXT: usize = 8;
+
+/// Strips a trailing `\n` and then a trailing `\r` from a line.
+fn trim_line_ending(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
 }
 
 static GLOBAL_UROMAN_INNER: LazyLock<Arc<UromanInner>> = LazyLock::new(|| {
@@ -231,82 +434,362 @@ static GLOBAL_UROMAN_INNER: LazyLock<Arc<UromanInner>> = LazyLock::new(|| {
 ///
 /// It holds the romanization rules and provides methods to romanize strings.
 /// This corresponds to the `Uroman` class in the Python implementation.
+///
+/// # Sharing across threads
+///
+/// `Uroman` is `Send` and `Sync`: romanizing takes `&self`, and the token cache is
+/// synchronized internally. Build one instance and share it, e.g. in an `Arc` or with
+/// `std::thread::scope`, rather than building one per request or thread. Cloning is
+/// cheap as well, since clones share the rules and the token cache.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use uroman::{Uroman, rom_format};
+/// let uroman = Arc::new(Uroman::new());
+/// let handles: Vec<_> = ["Київ", "Львів"]
+///     .into_iter()
+///     .map(|s| {
+///         let uroman = Arc::clone(&uroman);
+///         std::thread::spawn(move || {
+///             uroman.romanize_string::<rom_format::Str>(s, Some("ukr")).to_string()
+///         })
+///     })
+///     .collect();
+///
+/// let romanized: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+/// assert_eq!(romanized, vec!["Kyiv", "Lviv"]);
+/// ```
 #[derive(Debug, Clone, Default)]
 pub struct Uroman {
     inner: Arc<UromanInner>,
+    options: RomanizationOptions,
+    cache: Option<Arc<TokenCache>>,
 }
 
+// Sharing an instance across threads is part of the API, so fail to compile otherwise.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Uroman>();
+};
+
 impl Uroman {
     pub fn new() -> Self {
         Self {
             inner: Arc::clone(&GLOBAL_UROMAN_INNER),
+            options: RomanizationOptions::default(),
+            cache: None,
         }
     }
 
-    /// Romanizes a given string.
+    /// Creates an instance that caches the romanization of up to `capacity` tokens.
     ///
-    /// # Arguments
+    /// See `UromanBuilder::cache_capacity` for how the cache is used.
+    pub fn with_cache(capacity: usize) -> Self {
+        Self {
+            cache: Some(Arc::new(TokenCache::new(capacity))),
+            ..Self::new()
+        }
+    }
+
+    /// Adds the romanization rules in the file at `path`.
     ///
-    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
-    /// (e.g., eng, jpn, hin, ara, zho)
+    /// See `add_rules_from_str` for the format and precedence of the rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RomanizationError::Io` if the file cannot be read, and
+    /// `RomanizationError::InvalidRule` if a line is not a rule.
+    pub fn add_rules_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), RomanizationError> {
+        let rules = std::fs::read_to_string(path)?;
+        self.add_rules_from_str(&rules)
+    }
+
+    /// Checks the romanization rules in the file at `path` without adding them.
+    ///
+    /// See `validate_rules_str` for the warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RomanizationError::Io` if the file cannot be read, and
+    /// `RomanizationError::InvalidRule` if a line is not a rule.
+    pub fn validate_rules_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<RuleWarning>, RomanizationError> {
+        let rules = std::fs::read_to_string(path)?;
+        self.validate_rules_str(&rules)
+    }
+
+    /// Checks romanization rules in the format of `add_rules_from_str` without adding
+    /// them, and returns warnings, sorted by line, for the rules that are likely mistakes:
+    /// repeated or conflicting rules for the same source, sources that overlap, unknown
+    /// language codes and rules without a target.
+    ///
+    /// The rules are parsed as by `add_rules_from_str`, so a file without warnings is
+    /// added by it as written. The instance is not changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RomanizationError::InvalidRule` if a line has no `::s` slot.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{RuleWarning, Uroman};
+    /// let uroman = Uroman::new();
+    /// let warnings = uroman.validate_rules_str("::s Київ ::t Kiev ::lcode xxx")?;
+    ///
+    /// assert_eq!(
+    ///     warnings,
+    ///     [RuleWarning::UnknownLanguage { line: 1, lcode: "xxx".to_string() }]
+    /// );
+    /// # Ok::<(), uroman::RomanizationError>(())
+    /// ```
+    pub fn validate_rules_str(&self, rules: &str) -> Result<Vec<RuleWarning>, RomanizationError> {
+        validate::validate_rules(&self.inner, rules)
+            .map_err(|line| RomanizationError::InvalidRule { line })
+    }
+
+    /// Adds romanization rules in the format of uroman's `romanization-table.txt`, one per
+    /// line (e.g., `::s ß ::t ss`, or `::s г ::t h ::lcode ukr`).
+    ///
+    /// The rules for a source string replace all existing rules for it, both the built-in
+    /// ones and those added by an earlier call, so they always take precedence. Among
+    /// several rules for the same string, one is chosen as for the built-in rules, so a
+    /// string that should keep a default besides a rule restricted by `::lcode` needs an
+    /// unrestricted rule too. Empty lines and lines starting with `#` are ignored.
+    ///
+    /// Only this instance is affected, and its token cache, if any, is cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RomanizationError::InvalidRule` if a line has no `::s` slot, in which case
+    /// no rule is added.
     ///
     /// # Example
     /// ```
     /// # use uroman::{Uroman, rom_format};
-    /// # let uroman = Uroman::new();
-    /// let lcode = None;
-    /// let result = uroman.romanize_string::<rom_format::Str>("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", lcode);
+    /// let mut uroman = Uroman::new();
+    /// uroman.add_rules_from_str("::s Київ ::t Kiev")?;
     ///
-    /// let str = result.to_string();
+    /// assert_eq!(uroman.romanize_string::<rom_format::Str>("Київ", None).to_string(), "Kiev");
+    /// # Ok::<(), uroman::RomanizationError>(())
+    /// ```
+    pub fn add_rules_from_str(&mut self, rules: &str) -> Result<(), RomanizationError> {
+        Arc::make_mut(&mut self.inner)
+            .add_user_rules(rules)
+            .map_err(|line| RomanizationError::InvalidRule { line })?;
+        self.clear_cache();
+        Ok(())
+    }
+
+    /// Adds a rule that romanizes `source` as `target`, for the language `lcode` only or,
+    /// if `lcode` is `None`, for all languages.
     ///
-    /// println!("{str}");
+    /// `source` may be a single character or a longer token. The rule replaces the
+    /// existing rules for `source` with the same scope, so a rule for all languages
+    /// overrides the built-in default, while rules for specific languages still take
+    /// precedence for those. Does nothing if `source` is empty.
+    ///
+    /// Only this instance is affected, and its token cache, if any, is cleared.
+    ///
+    /// # Example
     /// ```
-    pub fn romanize_string<F: RomFormatType + 'static>(
-        &self,
-        s: &str,
-        lcode: Option<&str>,
-    ) -> RomanizationOutput<F> {
-        let mut lat = Lattice::new(s, &self.inner, lcode);
+    /// # use uroman::{Uroman, rom_format};
+    /// let mut uroman = Uroman::new();
+    /// uroman.add_rule("Київ", "Kiev", Some("rus"));
+    ///
+    /// assert_eq!(uroman.romanize_string::<rom_format::Str>("Київ", Some("rus")).to_string(), "Kiev");
+    /// assert_eq!(uroman.romanize_string::<rom_format::Str>("Київ", Some("ukr")).to_string(), "Kyiv");
+    /// ```
+    pub fn add_rule(&mut self, source: &str, target: &str, lcode: Option<&str>) {
+        if source.is_empty() {
+            return;
+        }
+        Arc::make_mut(&mut self.inner).add_user_rule(source, target, lcode);
+        self.clear_cache();
+    }
 
-        lat.pick_tibetan_vowel_edge();
-        lat.prep_braille();
-        lat.add_romanization();
-        lat.add_numbers();
-        lat.add_braille_numbers();
-        lat.add_rom_fall_back_singles();
+    /// Writes the rules of this instance, the built-in ones together with those added by
+    /// `add_rule` and `add_rules_from_str`, in the format of uroman's `romanization-table.txt`.
+    ///
+    /// The rules are sorted by source string, so exports can be compared with `diff`.
+    /// Passing the output to `add_rules_from_str` of another instance reproduces the rules
+    /// of this one. Rules derived from Unicode data, such as for Hangul syllables, are
+    /// included as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `writer` fails.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// let mut uroman = Uroman::new();
+    /// uroman.add_rule("ß", "sz", None);
+    ///
+    /// let mut rules = Vec::new();
+    /// uroman.export_rules(&mut rules).unwrap();
+    /// assert!(String::from_utf8(rules).unwrap().lines().any(|line| line == "::s ß ::t sz"));
+    /// ```
+    pub fn export_rules(&self, mut writer: impl Write) -> io::Result<()> {
+        self.inner.export_rules(&mut writer)
+    }
 
-        let type_id = TypeId::of::<F>();
+    /// Drops the rules specific to the language `lcode` from this instance, so that text
+    /// is romanized as if its data had never been loaded.
+    ///
+    /// This is the counterpart of `UromanBuilder::with_languages` for an existing instance.
+    /// Rules that apply to all languages are kept. Only this instance is affected, and its
+    /// token cache, if any, is cleared.
+    ///
+    /// Besides ISO 639-3 codes, `lcode` may be a code that rules are restricted to, such as
+    /// `srp2` for the alternative rules of Serbian.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BuildError::UnknownLanguage` if `lcode` is neither a known ISO 639-3 code
+    /// nor a code that rules are restricted to.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// let mut uroman = Uroman::new();
+    /// uroman.disable_language("ukr")?;
+    ///
+    /// assert!(!uroman.is_language_supported("ukr"));
+    /// assert_eq!(uroman.romanize_string::<rom_format::Str>("Київ", Some("ukr")).to_string(), "Kiyiv");
+    /// # Ok::<(), uroman::BuildError>(())
+    /// ```
+    pub fn disable_language(&mut self, lcode: &str) -> Result<(), BuildError> {
+        if !self.inner.is_known_language(lcode) && !self.inner.has_language_rules(lcode) {
+            return Err(BuildError::UnknownLanguage(lcode.to_string()));
+        }
+        if self.inner.is_language_retained(lcode) {
+            Arc::make_mut(&mut self.inner).disable_language(lcode);
+            self.clear_cache();
+        }
+        Ok(())
+    }
 
-        let result = if type_id == TypeId::of::<rom_format::Str>() {
-            let best_edges = lat.best_rom_edge_path(0, s.chars().count(), false);
-            RomanizationResult::Str(
-                best_edges.iter().map(|edge| edge.txt()).collect::<String>(),
-            )
-        } else if type_id == TypeId::of::<rom_format::Edges>() {
-            RomanizationResult::Edges(
-                lat.best_rom_edge_path(0, s.chars().count(), false)
-            )
-        } else if type_id == TypeId::of::<rom_format::Alts>() {
-            let mut best_edges = lat.best_rom_edge_path(0, s.chars().count(), false);
-            lat.add_alternatives(&mut best_edges);
+    /// Restores the built-in rules specific to the language `lcode`, after it was disabled
+    /// by `disable_language` or left out of `UromanBuilder::with_languages`.
+    ///
+    /// The rules dropped by `disable_language` are restored from this instance, including
+    /// those added with `add_rule` or `add_rules_from_str`. The rules of a language left
+    /// out of `UromanBuilder::with_languages` are restored from the built-in tables, which
+    /// are parsed for this if no instance of `Uroman::new` has been created, even for an
+    /// instance loaded with `Uroman::from_compiled_bytes`. Does nothing if the language is
+    /// enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BuildError::UnknownLanguage` if `lcode` is neither a known ISO 639-3 code
+    /// nor a code that rules are restricted to.
+    pub fn enable_language(&mut self, lcode: &str) -> Result<(), BuildError> {
+        if !self.inner.is_known_language(lcode)
+            && !self.inner.has_language_rules(lcode)
+            && !GLOBAL_UROMAN_INNER.has_language_rules(lcode)
+        {
+            return Err(BuildError::UnknownLanguage(lcode.to_string()));
+        }
+        if !self.inner.is_language_retained(lcode) {
+            Arc::make_mut(&mut self.inner).enable_language(lcode, || &GLOBAL_UROMAN_INNER);
+            self.clear_cache();
+        }
+        Ok(())
+    }
 
-            RomanizationResult::Edges(best_edges)
-        } else if type_id == TypeId::of::<rom_format::Lattice>() {
-            let mut all_edges = lat.all_edges(0, s.chars().count());
-            lat.add_alternatives(&mut all_edges);
+    /// Replaces the token cache, if any, with an empty one, after the rules changed.
+    fn clear_cache(&mut self) {
+        if let Some(cache) = &self.cache {
+            self.cache = Some(Arc::new(TokenCache::new(cache.capacity())));
+        }
+    }
 
-            RomanizationResult::Edges(all_edges)
-        } else {
-            unreachable!("Unknown RomFormatType provided");
-        };
+    /// Detects the Unicode scripts of a given string.
+    ///
+    /// Returns each script with the fraction of non-whitespace characters it covers,
+    /// sorted by descending fraction. Digits, punctuation and symbols count as
+    /// `Script::Common`.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, Script};
+    /// let scripts = Uroman::detect_script("こんにちは世界");
+    ///
+    /// assert_eq!(scripts, vec![(Script::Hiragana, 5.0 / 7.0), (Script::Han, 2.0 / 7.0)]);
+    /// ```
+    pub fn detect_script(s: &str) -> Vec<(Script, f32)> {
+        script::detect_scripts(&GLOBAL_UROMAN_INNER, s)
+    }
 
-        RomanizationOutput {
-            result,
-            _marker: PhantomData,
-        }
+    /// Guesses the ISO 639-3 language code of a given string.
+    ///
+    /// The guess is based on the dominant script (e.g., `jpn` for text with kana, `kor`
+    /// for Hangul) and on letters specific to one language. For scripts shared by many
+    /// languages, such as Latin, Cyrillic or Arabic, it returns `None` unless such a
+    /// letter occurs.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// assert_eq!(Uroman::guess_lcode("東京タワー").as_deref(), Some("jpn"));
+    /// assert_eq!(Uroman::guess_lcode("Їжак").as_deref(), Some("ukr"));
+    /// assert_eq!(Uroman::guess_lcode("Hello"), None);
+    /// ```
+    pub fn guess_lcode(s: &str) -> Option<String> {
+        script::guess_lcode(&GLOBAL_UROMAN_INNER, s).map(str::to_string)
     }
 
-    /// Decodes Unicode escape sequences before performing romanization.
+    /// Returns the languages supported by this instance, sorted by ISO 639-3 code.
+    ///
+    /// For an instance built with `UromanBuilder::with_languages`, only those languages
+    /// are returned. The languages of scripts whose `lang-*` feature is disabled (e.g.,
+    /// `rus` without `lang-cyrillic`) are not supported.
+    pub fn supported_languages(&self) -> Vec<LangInfo> {
+        self.inner.supported_languages()
+    }
+
+    /// Returns `true` if `lcode` is an ISO 639-3 code supported by this instance, as listed
+    /// by `Uroman::supported_languages`.
+    pub fn is_language_supported(&self, lcode: &str) -> bool {
+        self.inner.is_language_enabled(lcode)
+    }
+
+    /// Returns every character that the rules can write in a romanization under `lcode`.
+    ///
+    /// The alphabet is read from the rule tables rather than from romanizations: it has
+    /// the characters of the romanizations of the rules without an `::lcode` restriction
+    /// and of the rules of `lcode`, as changed by the options of the instance (e.g.,
+    /// `UromanBuilder::ascii_fold`), and the ASCII digits that numbers are written with.
+    /// Characters without a rule, which are kept as they are (see `Fallback`), the
+    /// romanization schemes, which replace the rules, and the alternatives of
+    /// `rom_format::Alts` are not included.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// let alphabet = Uroman::new().output_alphabet(Some("ukr"));
+    /// assert!(alphabet.contains(&'K') && alphabet.contains(&'7'));
+    ///
+    /// let lowercase = Uroman::builder().case_sensitive(false).build().unwrap();
+    /// assert!(!lowercase.output_alphabet(Some("ukr")).contains(&'K'));
+    /// ```
+    pub fn output_alphabet(&self, lcode: Option<&str>) -> BTreeSet<char> {
+        self.options.apply_to_alphabet(self.inner.output_alphabet(lcode))
+    }
+
+    /// Returns the hit and miss counts of the token cache, or `None` if caching is disabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// Returns a `UromanBuilder` for configuring an instance before construction.
+    pub fn builder() -> UromanBuilder {
+        UromanBuilder::new()
+    }
+
+    /// Romanizes a given string.
     ///
     /// # Arguments
     ///
@@ -318,22 +801,32 @@ impl Uroman {
     /// # use uroman::{Uroman, rom_format};
     /// # let uroman = Uroman::new();
     /// let lcode = None;
-    /// let result = uroman.romanize_escaped::<rom_format::Str>("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", lcode);
+    /// let result = uroman.romanize_string::<rom_format::Str>("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", lcode);
     ///
     /// let str = result.to_string();
     ///
     /// println!("{str}");
     /// ```
-    pub fn romanize_escaped<F: RomFormatType + 'static>(
+    pub fn romanize_string<F: RomFormatType + 'static>(
         &self,
         s: &str,
         lcode: Option<&str>,
     ) -> RomanizationOutput<F> {
-        let s = decode_unicode_escapes(s);
-        self.romanize_string::<F>(s.as_str(), lcode)
+        if TypeId::of::<F>() == TypeId::of::<rom_format::Str>() {
+            let mut romanized = String::with_capacity(s.len());
+            self.romanize_into(s, lcode, &mut romanized);
+            return RomanizationOutput {
+                result: RomanizationResult::Str(romanized),
+                _marker: PhantomData,
+            };
+        }
+        self.romanize_uncached::<F>(s, lcode)
     }
 
-    /// Decodes Unicode escape sequences and then romanizes the string using the specified `RomFormat`.
+    /// Romanizes a given string, appending the result to `out`.
+    ///
+    /// This is the string output of `romanize_string`, written into a buffer owned by the
+    /// caller, so that a loop can reuse one `String` rather than allocate one per call.
     ///
     /// # Arguments
     ///
@@ -342,30 +835,40 @@ impl Uroman {
     ///
     /// # Example
     /// ```
-    /// # use uroman::{Uroman, RomFormat};
+    /// # use uroman::Uroman;
     /// # let uroman = Uroman::new();
-    /// let lcode = None;
-    /// let result = uroman.romanize_with_format(
-    ///     "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ",
-    ///     lcode,
-    ///     Some(RomFormat::Edges),
-    /// );
-    ///
-    /// let str = result.to_string().unwrap();
-    ///
-    /// println!("{str}");
+    /// let mut out = String::new();
+    /// for s in ["ᚺᚨᛚᛚᛟ", "ᚹᛟᚱᛚᛞ"] {
+    ///     out.clear();
+    ///     uroman.romanize_into(s, None, &mut out);
+    ///     assert!(out == "hallo" || out == "world");
+    /// }
     /// ```
-    pub fn romanize_escaped_with_format(
-        &self,
-        s: &str,
-        lcode: Option<&str>,
-        rom_format: Option<RomFormat>,
-    ) -> RomanizationResult {
-        let s = decode_unicode_escapes(s);
-        self.romanize_with_format(&s, lcode, rom_format)
+    pub fn romanize_into(&self, s: &str, lcode: Option<&str>, out: &mut String) {
+        let start = out.len();
+        self.romanize_unbounded_into(s, lcode, out);
+
+        if let Some(limit) = self.options.expansion_limit(s.len())
+            && out.len() - start > limit
+        {
+            let mut end = start + limit;
+            while !out.is_char_boundary(end) {
+                end -= 1;
+            }
+            out.truncate(end);
+        }
     }
 
-    /// Romanizes a given string using `RomFormat`.
+    /// Romanizes a given string into a key for search and fuzzy matching.
+    ///
+    /// The key is the romanization under the options of the instance, normalized in one
+    /// pass over it, as follows:
+    ///
+    /// 1. Diacritics are stripped from each character that is ASCII without them, as by
+    ///    `UromanBuilder::ascii_fold` (e.g., `ā` to `a`, while `ø` is kept).
+    /// 2. Letters are lowercased, as by `UromanBuilder::case_sensitive(false)`.
+    /// 3. Each run of whitespace, including line breaks, becomes a single space, and
+    ///    whitespace at the start and end is removed.
     ///
     /// # Arguments
     ///
@@ -376,35 +879,1117 @@ impl Uroman {
     /// ```
     /// # use uroman::Uroman;
     /// # let uroman = Uroman::new();
-    /// let lcode = None;
-    /// let result = uroman.romanize_with_format(
-    ///     "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ",
-    ///     lcode,
-    ///     None, // `None` defaults to `RomFormat::Str`.
-    ///     // RomFormat::Str,
-    /// );
+    /// assert_eq!(uroman.romanize_key("  Київ   Львів\n", Some("ukr")), "kyiv lviv");
+    /// assert_eq!(uroman.romanize_key("ΘΕΣΣΑΛΟΝΊΚΗ", Some("ell")), "thessalonike");
+    /// ```
+    pub fn romanize_key(&self, s: &str, lcode: Option<&str>) -> String {
+        let mut romanized = String::with_capacity(s.len());
+        self.romanize_into(s, lcode, &mut romanized);
+        let mut key = String::with_capacity(romanized.len());
+        options::push_search_key(&romanized, &mut key);
+        key
+    }
+
+    /// Romanizes a given string like `Uroman::romanize_into`, but returns an error instead
+    /// of truncating the romanization if it exceeds `UromanBuilder::max_expansion_ratio`.
     ///
-    /// let str = result.to_string().unwrap();
+    /// # Errors
     ///
-    /// println!("{str}");
+    /// Returns `RomanizationError::ExpansionLimit` if the romanization is longer than the
+    /// limit. No other error is returned.
+    ///
+    /// # Example
     /// ```
-    pub fn romanize_with_format(
+    /// # use uroman::{RomanizationError, Uroman};
+    /// let uroman = Uroman::builder().max_expansion_ratio(1.0).build()?;
+    ///
+    /// assert_eq!(uroman.romanize_checked("Київ", Some("ukr"))?, "Kyiv");
+    /// assert!(matches!(
+    ///     uroman.romanize_checked("ЩЩ", None),
+    ///     Err(RomanizationError::ExpansionLimit { input_len: 4, limit: 4 })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn romanize_checked(
         &self,
         s: &str,
         lcode: Option<&str>,
-        rom_format: Option<RomFormat>,
+    ) -> Result<String, RomanizationError> {
+        let mut romanized = String::with_capacity(s.len());
+        self.romanize_unbounded_into(s, lcode, &mut romanized);
+        match self.options.expansion_limit(s.len()) {
+            Some(limit) if romanized.len() > limit => Err(RomanizationError::ExpansionLimit {
+                input_len: s.len(),
+                limit,
+            }),
+            _ => Ok(romanized),
+        }
+    }
+
+    /// Romanizes `s` into `out`, without the limit of `UromanBuilder::max_expansion_ratio`.
+    fn romanize_unbounded_into(&self, s: &str, lcode: Option<&str>, out: &mut String) {
+        let start = out.len();
+        match &self.cache {
+            Some(cache) => self.romanize_tokens_cached(cache, s, lcode, out),
+            None => self.romanize_uncached_into(s, lcode, out),
+        }
+
+        if self.options.collapse_whitespace && !self.options.reversible {
+            let collapsed = options::collapse_whitespace(&out[start..]);
+            out.truncate(start);
+            out.push_str(&collapsed);
+        }
+    }
+
+    /// Romanizes a given string and returns whether the romanization is byte-identical to it.
+    ///
+    /// Text that romanizes to itself without a lattice, such as ASCII without digits, is
+    /// recognized before any romanization is done, so the check costs little more than a
+    /// scan of `text` where no romanization is needed.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// assert_eq!(uroman.romanize_with_identity("Kyiv", Some("ukr")), ("Kyiv".to_string(), true));
+    /// assert_eq!(uroman.romanize_with_identity("Київ", Some("ukr")), ("Kyiv".to_string(), false));
+    /// ```
+    pub fn romanize_with_identity(&self, text: &str, lcode: Option<&str>) -> (String, bool) {
+        if self.keeps_text_as_is(text) {
+            return (text.to_string(), true);
+        }
+        let mut romanized = String::with_capacity(text.len());
+        self.romanize_into(text, lcode, &mut romanized);
+        let identity = romanized == text;
+        (romanized, identity)
+    }
+
+    /// Returns `true` if the romanization of `text` is byte-identical to it, as returned by
+    /// `Uroman::romanize_with_identity`.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// assert!(uroman.romanize_is_identity("Hello, world!", None));
+    /// assert!(!uroman.romanize_is_identity("Привет", Some("rus")));
+    /// ```
+    pub fn romanize_is_identity(&self, text: &str, lcode: Option<&str>) -> bool {
+        self.keeps_text_as_is(text) || self.romanize_with_identity(text, lcode).1
+    }
+
+    /// Returns `true` if `text` is known to romanize to itself without romanizing it.
+    fn keeps_text_as_is(&self, text: &str) -> bool {
+        self.keeps_as_is(text)
+            && !(self.options.collapse_whitespace
+                && !self.options.reversible
+                && options::collapse_whitespace(text) != text)
+    }
+
+    /// Romanizes raw bytes and returns the romanization as UTF-8 bytes.
+    ///
+    /// Bytes that are not valid UTF-8 are handled as set by `UromanBuilder::invalid_utf8`:
+    /// by default, each maximal invalid sequence becomes one U+FFFD REPLACEMENT CHARACTER,
+    /// which is passed through to the output. With `InvalidUtf8::Skip`, invalid sequences
+    /// are dropped, and the valid text around them is romanized as if they were absent.
+    ///
+    /// # Errors
+    ///
+    /// With `InvalidUtf8::Error`, returns `RomanizationError::InvalidUtf8` with the offset
+    /// of the first invalid byte. No other error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let romanized = uroman.romanize_bytes(b"\xD0\x96\xFF!", None)?;
+    ///
+    /// assert_eq!(romanized, "Zh\u{FFFD}!".as_bytes());
+    /// # Ok::<(), uroman::RomanizationError>(())
+    /// ```
+    pub fn romanize_bytes(
+        &self,
+        input: &[u8],
+        lcode: Option<&str>,
+    ) -> Result<Vec<u8>, RomanizationError> {
+        let text = match self.options.invalid_utf8 {
+            InvalidUtf8::Replace => String::from_utf8_lossy(input),
+            InvalidUtf8::Skip => match std::str::from_utf8(input) {
+                Ok(s) => Cow::Borrowed(s),
+                Err(_) => Cow::Owned(input.utf8_chunks().map(|chunk| chunk.valid()).collect()),
+            },
+            InvalidUtf8::Error => Cow::Borrowed(std::str::from_utf8(input).map_err(|e| {
+                RomanizationError::InvalidUtf8 { byte_offset: e.valid_up_to() }
+            })?),
+        };
+
+        Ok(self.romanize_checked(&text, lcode)?.into_bytes())
+    }
+
+    /// Romanizes each run of whitespace and non-whitespace characters separately,
+    /// looking them up in `cache` first, and appends the result to `out`.
+    fn romanize_tokens_cached(
+        &self,
+        cache: &TokenCache,
+        s: &str,
+        lcode: Option<&str>,
+        out: &mut String,
+    ) {
+        let mut rest = s;
+        while let Some(first) = rest.chars().next() {
+            let is_space = first.is_whitespace();
+            let token_len = rest
+                .find(|c: char| c.is_whitespace() != is_space)
+                .unwrap_or(rest.len());
+            let (token, tail) = rest.split_at(token_len);
+
+            out.push_str(&cache.get_or_insert_with(token, lcode, || {
+                let mut romanized = String::with_capacity(token.len());
+                self.romanize_uncached_into(token, lcode, &mut romanized);
+                romanized
+            }));
+            rest = tail;
+        }
+    }
+
+    /// Returns `true` if `s` romanizes to itself as by `RomanizationOptions::keeps_as_is`,
+    /// and no user rule can match it.
+    fn keeps_as_is(&self, s: &str) -> bool {
+        !self.inner.has_ascii_user_rules && self.options.keeps_as_is(s)
+    }
+
+    /// Appends the string output for `s` to `out`, without the token cache.
+    fn romanize_uncached_into(&self, s: &str, lcode: Option<&str>, out: &mut String) {
+        if self.keeps_as_is(s) {
+            out.push_str(s);
+            return;
+        }
+
+        let normalized = self.options.normalize_input(s);
+        let s = normalized.as_ref();
+        let mut lat = self.build_lattice(s, lcode);
+
+        let mut best_edges = lat.best_rom_edge_path(0, lat.max_vertex, false);
+        self.options.apply_to_edges(&mut best_edges, &lat.s_chars);
+        for edge in &best_edges {
+            out.push_str(edge.txt());
+        }
+    }
+
+    /// Romanizes `s` in a format other than `rom_format::Str`, which `romanize_into` handles.
+    fn romanize_uncached<F: RomFormatType + 'static>(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+    ) -> RomanizationOutput<F> {
+        let normalized = self.options.normalize_input(s);
+        let s = normalized.as_ref();
+        let mut lat = self.build_lattice(s, lcode);
+
+        let type_id = TypeId::of::<F>();
+
+        let len = lat.max_vertex;
+        let result = if type_id == TypeId::of::<rom_format::Edges>() {
+            RomanizationResult::Edges(self.best_edges(&mut lat))
+        } else if type_id == TypeId::of::<rom_format::Alts>() {
+            let mut best_edges = lat.best_rom_edge_path(0, len, false);
+            lat.add_alternatives(&mut best_edges);
+            self.options.prune_alternatives(&mut best_edges);
+            self.options.apply_to_edges(&mut best_edges, &lat.s_chars);
+            lat.set_source_scripts(&mut best_edges);
+            lat.remove_inserted_spaces(&mut best_edges);
+
+            RomanizationResult::Edges(best_edges)
+        } else if type_id == TypeId::of::<rom_format::Lattice>() {
+            let mut all_edges = lat.all_edges(0, len);
+            lat.add_alternatives(&mut all_edges);
+            self.options.prune_alternatives(&mut all_edges);
+            self.options.apply_to_edges(&mut all_edges, &lat.s_chars);
+            lat.set_source_scripts(&mut all_edges);
+            lat.remove_inserted_spaces(&mut all_edges);
+
+            RomanizationResult::Edges(all_edges)
+        } else if type_id == TypeId::of::<rom_format::Json>() {
+            let mut all_edges = lat.all_edges(0, len);
+            lat.add_alternatives(&mut all_edges);
+            self.options.prune_alternatives(&mut all_edges);
+            self.options.apply_to_edges(&mut all_edges, &lat.s_chars);
+            lat.remove_inserted_spaces(&mut all_edges);
+
+            let s_chars: Vec<char> = s.chars().collect();
+            RomanizationResult::Str(json::lattice_to_json(&s_chars, &all_edges))
+        } else {
+            unreachable!("Unknown RomFormatType provided");
+        };
+
+        RomanizationOutput {
+            result,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the edges of the best romanization path of `lat`, for `rom_format::Edges`.
+    fn best_edges(&self, lat: &mut Lattice) -> Vec<Edge> {
+        let mut best_edges = lat.best_rom_edge_path(0, lat.max_vertex, false);
+        self.options.apply_to_edges(&mut best_edges, &lat.s_chars);
+        lat.set_source_scripts(&mut best_edges);
+        lat.remove_inserted_spaces(&mut best_edges);
+        best_edges
+    }
+
+    /// Builds the lattice of all romanization edges of `s`, which must already be normalized.
+    ///
+    /// With `segment_words`, the lattice is built from the segmented text, so that the words
+    /// are romanized as if they were separated by spaces, and the edges taken from it must
+    /// be mapped back to `s` by `Lattice::remove_inserted_spaces`.
+    fn build_lattice<'a>(&'a self, s: &str, lcode: Option<&str>) -> Lattice<'a> {
+        let segmented = self
+            .options
+            .segment_words
+            .then(|| segment::segment_words(s, lcode))
+            .flatten();
+        let mut lat = match segmented {
+            Some((segmented, spaces)) => {
+                let mut lat = Lattice::new(&segmented, &self.inner, lcode);
+                lat.inserted_spaces = spaces;
+                lat
+            }
+            None => Lattice::new(s, &self.inner, lcode),
+        };
+
+        lat.pick_tibetan_vowel_edge();
+        lat.prep_braille();
+        lat.add_romanization();
+        if self.options.interprets_numbers() {
+            lat.add_numbers();
+        }
+        lat.add_braille_numbers();
+        lat.add_rom_fall_back_singles();
+        self.options.apply_to_lattice(&mut lat, lcode);
+        lat
+    }
+
+    /// Romanizes the text of an HTML snippet, leaving the markup intact.
+    ///
+    /// Text nodes and the values of `alt` and `title` attributes are romanized. Tags,
+    /// other attributes, comments, character references (e.g., `&amp;`) and the contents
+    /// of `script` and `style` elements are kept as is. The input is tokenized rather than
+    /// parsed, so invalid markup is passed through without being fixed.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let html = uroman.romanize_html(r#"<a href="/київ" title="Київ">Київ&nbsp;і Львів</a>"#, Some("ukr"));
+    ///
+    /// assert_eq!(html, r#"<a href="/київ" title="Kyiv">Kyiv&nbsp;i Lviv</a>"#);
+    /// ```
+    pub fn romanize_html(&self, html: &str, lcode: Option<&str>) -> String {
+        html::romanize_html(html, |text| {
+            self.romanize_string::<rom_format::Str>(text, lcode).to_string()
+        })
+    }
+
+    /// Returns the `k` highest-scoring distinct romanizations of a given string, highest first.
+    ///
+    /// Each romanization follows a full path through the lattice, including alternative
+    /// romanizations (see [`rom_format::Alts`]). Its score is the logarithm of the product
+    /// of the [`Edge::score`]s along the path, with a penalty for every edge, so paths of
+    /// few, long edges are preferred and the first romanization is usually the one returned
+    /// by [`Uroman::romanize_string`]. Scores are at most 0.0, and paths that produce the
+    /// same romanization are counted once, with the highest score.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let nbest = uroman.romanize_nbest("chromosome", None, 2);
+    ///
+    /// assert_eq!(nbest[0].0, "chromosome");
+    /// assert_eq!(nbest[1].0, "kromosome");
+    /// assert!(nbest[0].1 > nbest[1].1);
+    /// ```
+    pub fn romanize_nbest(&self, s: &str, lcode: Option<&str>, k: usize) -> Vec<(String, f32)> {
+        let normalized = self.options.normalize_input(s);
+        let s = normalized.as_ref();
+        let mut lat = self.build_lattice(s, lcode);
+
+        let mut all_edges = lat.all_edges(0, lat.max_vertex);
+        lat.add_alternatives(&mut all_edges);
+        self.options.prune_alternatives(&mut all_edges);
+        self.options.apply_to_edges(&mut all_edges, &lat.s_chars);
+        lat.remove_inserted_spaces(&mut all_edges);
+
+        nbest::nbest_romanizations(&all_edges, s.chars().count(), k)
+    }
+
+    /// Romanizes a given string, borrowing the input if romanization leaves it unchanged.
+    ///
+    /// This avoids allocating for input that is already romanized (e.g., pure ASCII text).
+    /// ASCII text without digits is returned without building its lattice, as by
+    /// `Uroman::romanize_is_identity`.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use std::borrow::Cow;
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// assert!(matches!(uroman.romanize_cow("Hello world", None), Cow::Borrowed(_)));
+    /// assert_eq!(uroman.romanize_cow("ᚺᚨᛚᛚᛟ", None), "hallo");
+    /// ```
+    pub fn romanize_cow<'s>(&self, s: &'s str, lcode: Option<&str>) -> Cow<'s, str> {
+        if self.keeps_text_as_is(s)
+            && self.options.expansion_limit(s.len()).is_none_or(|limit| s.len() <= limit)
+        {
+            return Cow::Borrowed(s);
+        }
+
+        let normalized = self.options.normalize_input(s);
+        let edges = self.output_edges(&normalized, lcode, s.len());
+        let s_chars: Vec<char> = normalized.chars().collect();
+
+        let mut pos = 0;
+        let unchanged = edges.iter().all(|edge| {
+            let is_same = edge.start() == pos
+                && s_chars
+                    .get(edge.start()..edge.end())
+                    .is_some_and(|span| edge.txt().chars().eq(span.iter().copied()));
+            pos = edge.end();
+            is_same
+        }) && pos == s_chars.len();
+
+        if unchanged {
+            normalized
+        } else {
+            Cow::Owned(edges.iter().map(|edge| edge.txt()).collect())
+        }
+    }
+
+    /// Romanizes a given string, failing instead of passing unromanizable text through.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Errors
+    ///
+    /// * `RomanizationError::UnromanizableSpan` if a letter or digit has no romanization rule,
+    ///   or the input has a private-use or unassigned character. `start` and `end` are byte
+    ///   offsets of the first such run of characters.
+    /// * `RomanizationError::InternalRuleError` if romanization panicked internally.
+    /// * `RomanizationError::ExpansionLimit` if the romanization is longer than the limit of
+    ///   `UromanBuilder::max_expansion_ratio`.
+    ///
+    /// # Panics
+    ///
+    /// This method never panics. The other `romanize_*` methods can currently panic in the
+    /// following places, all of which are caught here:
+    ///
+    /// * Number parsing, which unwraps the values of numeric edges when combining
+    ///   digits, multipliers and fractions (e.g., `D1 value parsing failed`).
+    /// * Rule application, which unwraps the best candidate edge of a span.
+    ///
+    /// A panic still reaches the installed panic hook, which prints it to stderr by default.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, RomanizationError};
+    /// # let uroman = Uroman::new();
+    /// assert_eq!(uroman.try_romanize("Київ", Some("ukr")).unwrap(), "Kyiv");
+    /// assert!(matches!(
+    ///     uroman.try_romanize("a𠀀", None),
+    ///     Err(RomanizationError::UnromanizableSpan { start: 1, end: 5 }),
+    /// ));
+    /// ```
+    pub fn try_romanize(&self, s: &str, lcode: Option<&str>) -> Result<String, RomanizationError> {
+        let mut edges = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.romanize_string::<rom_format::Edges>(s, lcode).to_edges()
+        }))
+        .map_err(|payload| RomanizationError::InternalRuleError(panic_message(&*payload)))?;
+        self.options.collapse_edge_whitespace(&mut edges);
+
+        if let Some(first) = edges.iter().position(Edge::is_unromanized) {
+            let start = edges[first].start();
+            let end = edges[first..]
+                .iter()
+                .take_while(|edge| edge.is_unromanized())
+                .last()
+                .map_or(start, Edge::end);
+            let normalized = self.options.normalize_input(s);
+            let byte_offset = |offset: usize| {
+                normalized
+                    .char_indices()
+                    .nth(offset)
+                    .map_or(normalized.len(), |(i, _)| i)
+            };
+            return Err(RomanizationError::UnromanizableSpan {
+                start: byte_offset(start),
+                end: byte_offset(end),
+            });
+        }
+
+        let romanized: String = edges.iter().map(|edge| edge.txt()).collect();
+        match self.options.expansion_limit(s.len()) {
+            Some(limit) if romanized.len() > limit => Err(RomanizationError::ExpansionLimit {
+                input_len: s.len(),
+                limit,
+            }),
+            _ => Ok(romanized),
+        }
+    }
+
+    /// Returns the edges of the best romanization path of `s`, which must already be
+    /// normalized, whose `txt` spell out the string output of `Uroman::romanize_into` for
+    /// an input of `input_len` bytes.
+    fn output_edges(&self, s: &str, lcode: Option<&str>, input_len: usize) -> Vec<Edge> {
+        let mut edges = self.best_edges(&mut self.build_lattice(s, lcode));
+        self.options.collapse_edge_whitespace(&mut edges);
+        if let Some(limit) = self.options.expansion_limit(input_len) {
+            options::truncate_edges(&mut edges, limit);
+        }
+        edges
+    }
+
+    /// Romanizes a given string and reports the characters that no rule covers.
+    ///
+    /// Unlike `try_romanize`, this always returns the romanized string. Characters without
+    /// a rule are passed through unchanged and listed in order of appearance.
+    /// If the instance normalizes its input, byte offsets refer to the normalized text.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let (romanized, unmapped) = uroman.romanize_with_diagnostics("Київ 𠀀", Some("ukr"));
+    ///
+    /// assert_eq!(romanized, "Kyiv 𠀀");
+    /// assert_eq!(unmapped[0].char, '𠀀');
+    /// assert_eq!(unmapped[0].byte_offset, 9);
+    /// ```
+    pub fn romanize_with_diagnostics(&self, s: &str, lcode: Option<&str>) -> (String, Vec<Unmapped>) {
+        let normalized = self.options.normalize_input(s);
+        let edges = self.output_edges(&normalized, lcode, s.len());
+        let s = normalized.as_ref();
+        let byte_offsets: Vec<usize> = s.char_indices().map(|(i, _)| i).collect();
+
+        let mut unmapped = Vec::new();
+        for edge in edges.iter().filter(|edge| edge.is_unromanized()) {
+            for (offset, c) in (edge.start()..edge.end()).zip(edge.txt().chars()) {
+                let script = self.inner.chr_script_name(c);
+                unmapped.push(Unmapped {
+                    char: c,
+                    byte_offset: byte_offsets[offset],
+                    script: (!script.is_empty()).then_some(script),
+                });
+            }
+        }
+
+        (edges.iter().map(|edge| edge.txt()).collect(), unmapped)
+    }
+
+    /// Romanizes a given string and aligns the output with the source.
+    ///
+    /// Each tuple pairs a byte range of `s` with the byte range of the romanized
+    /// output it produced. The tuples follow the best romanization path, so both
+    /// ranges are increasing and together cover the source and the output. A source
+    /// range may map to an empty output range (e.g., for dropped combining marks).
+    /// If the instance normalizes its input, source ranges refer to the normalized text.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let (romanized, alignment) = uroman.romanize_with_alignment("Ж北", None);
+    ///
+    /// assert_eq!(romanized, "Zhbei");
+    /// assert_eq!(alignment, vec![(0..2, 0..2), (2..5, 2..5)]);
+    /// ```
+    pub fn romanize_with_alignment(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+    ) -> (String, Alignment) {
+        let normalized = self.options.normalize_input(s);
+        self.align(&normalized, lcode, s.len())
+    }
+
+    /// Returns the romanization of `s`, which must already be normalized, and its
+    /// alignment with `s`, for an input of `input_len` bytes.
+    fn align(&self, s: &str, lcode: Option<&str>, input_len: usize) -> (String, Alignment) {
+        let edges = self.output_edges(s, lcode, input_len);
+        let byte_offsets: Vec<usize> = s
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(s.len()))
+            .collect();
+
+        let mut romanized = String::with_capacity(s.len());
+        let mut alignment = Vec::with_capacity(edges.len());
+        for edge in &edges {
+            let output_start = romanized.len();
+            romanized.push_str(edge.txt());
+            alignment.push((
+                byte_offsets[edge.start()]..byte_offsets[edge.end()],
+                output_start..romanized.len(),
+            ));
+        }
+
+        (romanized, alignment)
+    }
+
+    /// Like `romanize_with_alignment`, but source ranges refer to `text` even if the
+    /// instance normalizes its input. The aligned spans are grouped until they end at an
+    /// offset of the normalized text that has a counterpart in `text`.
+    pub(crate) fn romanize_with_text_alignment(
+        &self,
+        text: &str,
+        lcode: Option<&str>,
+    ) -> (String, Alignment) {
+        let (normalized, offsets) = self.options.normalize_input_with_offsets(text);
+        let (romanized, normalized_alignment) = self.align(&normalized, lcode, text.len());
+
+        let mut alignment = Vec::with_capacity(normalized_alignment.len());
+        let (mut i, mut source_start, mut output_start) = (0, 0, 0);
+        for (source_range, output_range) in normalized_alignment {
+            while offsets.get(i + 1).is_some_and(|&(_, end)| end <= source_range.end) {
+                i += 1;
+            }
+            let (source_end, normalized_end) = offsets[i];
+            if normalized_end != source_range.end {
+                continue;
+            }
+            alignment.push((source_start..source_end, output_start..output_range.end));
+            (source_start, output_start) = (source_end, output_range.end);
+        }
+        (romanized, alignment)
+    }
+
+    /// Romanizes a given string and returns only the source ranges that change.
+    ///
+    /// Adjacent changed spans are merged into one `Edit`, and spans romanized to
+    /// themselves, such as ASCII text, are omitted. Replacing each `Edit::source` range
+    /// with its `replacement`, from left to right, yields the romanized string of
+    /// `Uroman::romanize_with_alignment`. Source ranges refer to `text` even if the
+    /// instance normalizes its input: a change made by the normalization, such as an
+    /// expanded ligature, is part of the edit of the characters it applies to.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Edit, Uroman};
+    /// # let uroman = Uroman::new();
+    /// let edits = uroman.romanize_edits("Hi, Київ!", Some("ukr"));
+    ///
+    /// assert_eq!(edits, vec![Edit { source: 4..12, replacement: "Kyiv".to_string() }]);
+    /// ```
+    pub fn romanize_edits(&self, text: &str, lcode: Option<&str>) -> Vec<Edit> {
+        let (romanized, alignment) = self.romanize_with_text_alignment(text, lcode);
+
+        let mut edits: Vec<Edit> = Vec::new();
+        for (source_range, output_range) in alignment {
+            let replacement = &romanized[output_range];
+            if text[source_range.clone()] == *replacement {
+                continue;
+            }
+            match edits.last_mut() {
+                Some(edit) if edit.source.end == source_range.start => {
+                    edit.source.end = source_range.end;
+                    edit.replacement.push_str(replacement);
+                }
+                _ => edits.push(Edit {
+                    source: source_range,
+                    replacement: replacement.to_string(),
+                }),
+            }
+        }
+        edits
+    }
+
+    /// Romanizes one character as it would be romanized after `prev`, for incremental
+    /// romanization as text is typed.
+    ///
+    /// Only the last 8 characters of `prev` are consulted, which covers the
+    /// rules that look at the characters before a letter, such as the small tsu of Japanese
+    /// gemination or the position of a letter at the start of a word. The returned string is
+    /// the romanization of the edges that cover `ch`. If `ch` is romanized together with
+    /// characters of `prev`, such as `っか` as `kka`, the whole romanization of these
+    /// characters is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// assert_eq!(uroman.romanize_char("", 'か', Some("jpn")), "ka");
+    /// assert_eq!(uroman.romanize_char("っ", 'か', Some("jpn")), "kka");
+    /// assert_eq!(uroman.romanize_char("Ки", 'ї', Some("ukr")), "yi");
+    /// ```
+    pub fn romanize_char(&self, prev: &str, ch: char, lcode: Option<&str>) -> String {
+        let context_start = prev
+            .char_indices()
+            .rev()
+            .nth(CHAR_CONTEXT - 1)
+            .map_or(0, |(i, _)| i);
+        let context = &prev[context_start..];
+        let mut s = String::with_capacity(context.len() + ch.len_utf8());
+        s.push_str(context);
+        s.push(ch);
+
+        // The offset of `ch` in the normalized string, where `ch` may compose with `prev`.
+        let normalized_context = self.options.normalize_input(context);
+        let normalized = self.options.normalize_input(&s);
+        let offset = normalized
+            .char_indices()
+            .zip(normalized_context.chars())
+            .find(|&((_, a), b)| a != b)
+            .map_or(normalized_context.len().min(normalized.len()), |((i, _), _)| i);
+
+        let (romanized, alignment) = self.romanize_with_alignment(&normalized, lcode);
+        alignment
+            .iter()
+            .find(|(source, _)| source.end > offset)
+            .map(|(_, output)| romanized[output.start..].to_string())
+            .unwrap_or_default()
+    }
+
+    /// Romanizes a given string token by token, pairing each source token with its romanization.
+    ///
+    /// Tokens follow the word boundaries of the lattice: a word is a run of letters, marks
+    /// and digits, together with the characters romanized with them (e.g., the `.` of
+    /// `3.14`). With `include_separators`, runs of whitespace and each punctuation mark
+    /// are returned as tokens as well, so that the tokens concatenate to the source and
+    /// to the romanization. If the instance normalizes its input, the source tokens are
+    /// taken from the normalized text.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let tokens = uroman.romanize_tokens("Київ, Львів", Some("ukr"), false);
+    ///
+    /// assert_eq!(tokens, vec![
+    ///     ("Київ".to_string(), "Kyiv".to_string()),
+    ///     ("Львів".to_string(), "Lviv".to_string()),
+    /// ]);
+    /// ```
+    pub fn romanize_tokens(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+        include_separators: bool,
+    ) -> Vec<(String, String)> {
+        let normalized = self.options.normalize_input(s);
+        let (romanized, alignment) = self.romanize_with_alignment(&normalized, lcode);
+        tokens::romanized_tokens(&normalized, &romanized, &alignment, include_separators)
+    }
+
+    /// Splits `text` into sentences and romanizes each, for one sentence per line.
+    ///
+    /// Sentences end at the terminators of their script, such as `.`, `?`, `。`, `؟` and
+    /// the danda `।`, following the Unicode sentence boundary rules. `lcode` adds the
+    /// terminators of languages these rules do not cover, such as the Greek question mark
+    /// `;` under `ell`. The whitespace around each sentence and its romanization is dropped.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let sentences = uroman.romanize_sentences("Київ. Львів?", Some("ukr"));
+    ///
+    /// assert_eq!(sentences, vec!["Kyiv.", "Lviv?"]);
+    /// ```
+    pub fn romanize_sentences(&self, text: &str, lcode: Option<&str>) -> Vec<String> {
+        sentence::split_sentences(text, lcode)
+            .into_iter()
+            .map(|s| {
+                let romanized = self.romanize_string::<rom_format::Str>(s, lcode).to_string();
+                romanized.trim().to_string()
+            })
+            .collect()
+    }
+
+    /// Maps a romanization made in the reversible mode back to the original script.
+    ///
+    /// `lcode` must be the code the text was romanized with, which selects the scheme
+    /// that is read back: Extended Wylie for Tibetan under `bod`, and ISO 9 for Cyrillic
+    /// under any other code. Text that the reversible mode enclosed in backslashes, such
+    /// as the Latin words of the source, is copied as it is.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// let uroman = Uroman::builder().reversible(true).build().unwrap();
+    /// let romanized = uroman.romanize_string::<uroman::rom_format::Str>("Щука", None).to_string();
+    ///
+    /// assert_eq!(romanized, "Ŝuka");
+    /// assert_eq!(uroman.deromanize(&romanized, None), "Щука");
+    ///
+    /// let romanized = uroman.romanize_string::<uroman::rom_format::Str>("Щука Kyiv", None).to_string();
+    /// assert_eq!(romanized, "Ŝuka \\Kyiv\\");
+    /// assert_eq!(uroman.deromanize(&romanized, None), "Щука Kyiv");
+    /// ```
+    pub fn deromanize(&self, text: &str, lcode: Option<&str>) -> String {
+        reversible::deromanize(text, lcode)
+    }
+
+    /// Romanizes a given string as HTML, with the romanization as ruby text over the source.
+    ///
+    /// Each group of the source text selected by `grouping` that contains letters or digits
+    /// and is changed by the romanization becomes `<ruby>source<rt>romanization</rt></ruby>`;
+    /// the rest, such as spaces and punctuation, is kept as plain text. HTML-special
+    /// characters are escaped in both. If the instance normalizes its input, the source
+    /// text is the normalized text.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, RubyGrouping};
+    /// # let uroman = Uroman::new();
+    /// let html = uroman.romanize_ruby("漢字", Some("zho"), RubyGrouping::Character);
+    /// assert_eq!(html, "<ruby>漢<rt>han</rt></ruby><ruby>字<rt>zi</rt></ruby>");
+    ///
+    /// let html = uroman.romanize_ruby("漢字", Some("zho"), RubyGrouping::Word);
+    /// assert_eq!(html, "<ruby>漢字<rt>hanzi</rt></ruby>");
+    /// ```
+    pub fn romanize_ruby(&self, s: &str, lcode: Option<&str>, grouping: RubyGrouping) -> String {
+        let normalized = self.options.normalize_input(s);
+        let (romanized, alignment) = self.romanize_with_alignment(&normalized, lcode);
+        ruby::ruby_html(&normalized, &romanized, &alignment, grouping)
+    }
+
+    /// Romanizes a given string with the source text in parentheses after its romanization.
+    ///
+    /// The romanization of each group of the source text selected by `granularity` that
+    /// contains letters or digits and is changed by the romanization is followed by the
+    /// group in parentheses; the rest, such as spaces and punctuation between tokens, is
+    /// romanized as usual. If the instance normalizes its input, the source text is the
+    /// normalized text.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{AnnotationGranularity, Uroman};
+    /// # let uroman = Uroman::new();
+    /// let text = uroman.romanize_annotated("Київ і Kyiv.", Some("ukr"), AnnotationGranularity::Token);
+    /// assert_eq!(text, "Kyiv (Київ) i (і) Kyiv.");
+    ///
+    /// let text = uroman.romanize_annotated("Київ. Львів.", Some("ukr"), AnnotationGranularity::Sentence);
+    /// assert_eq!(text, "Kyiv. (Київ.) Lviv. (Львів.)");
+    /// ```
+    pub fn romanize_annotated(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+        granularity: AnnotationGranularity,
+    ) -> String {
+        let normalized = self.options.normalize_input(s);
+        let (romanized, alignment) = self.align(&normalized, lcode, s.len());
+        annotate::annotate(&normalized, lcode, &romanized, &alignment, granularity)
+    }
+
+    /// Decodes Unicode escape sequences before performing romanization.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, rom_format};
+    /// # let uroman = Uroman::new();
+    /// let lcode = None;
+    /// let result = uroman.romanize_escaped::<rom_format::Str>("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", lcode);
+    ///
+    /// let str = result.to_string();
+    ///
+    /// println!("{str}");
+    /// ```
+    pub fn romanize_escaped<F: RomFormatType + 'static>(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+    ) -> RomanizationOutput<F> {
+        let s = decode_unicode_escapes(s);
+        self.romanize_string::<F>(s.as_str(), lcode)
+    }
+
+    /// Decodes Unicode escape sequences and then romanizes the string using the specified `RomFormat`.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::{Uroman, RomFormat};
+    /// # let uroman = Uroman::new();
+    /// let lcode = None;
+    /// let result = uroman.romanize_with_format(
+    ///     "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ",
+    ///     lcode,
+    ///     Some(RomFormat::Edges),
+    /// );
+    ///
+    /// let str = result.to_string().unwrap();
+    ///
+    /// println!("{str}");
+    /// ```
+    pub fn romanize_escaped_with_format(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+        rom_format: Option<RomFormat>,
+    ) -> RomanizationResult {
+        let decoded = decode_unicode_escapes(s);
+        if rom_format == Some(RomFormat::Jsonl) {
+            // `orig` is the input as given, with its escapes.
+            return RomanizationResult::Str(self.romanize_jsonl(s, &decoded, lcode, None));
+        }
+        self.romanize_with_format(&decoded, lcode, rom_format)
+    }
+
+    /// Romanizes a given string using `RomFormat`.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let lcode = None;
+    /// let result = uroman.romanize_with_format(
+    ///     "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ",
+    ///     lcode,
+    ///     None, // `None` defaults to `RomFormat::Str`.
+    ///     // RomFormat::Str,
+    /// );
+    ///
+    /// let str = result.to_string().unwrap();
+    ///
+    /// println!("{str}");
+    /// ```
+    pub fn romanize_with_format(
+        &self,
+        s: &str,
+        lcode: Option<&str>,
+        rom_format: Option<RomFormat>,
     ) -> RomanizationResult {
         let rom_format = rom_format.unwrap_or(RomFormat::Str);
 
         match rom_format {
             RomFormat::Str => {
-                let str = self.romanize_string::<rom_format::Str>(s, lcode);
-                RomanizationResult::Str(str.to_string())
+                let mut romanized = String::with_capacity(s.len());
+                self.romanize_into(s, lcode, &mut romanized);
+                RomanizationResult::Str(romanized)
             }
             RomFormat::Edges => self.romanize_string::<rom_format::Edges>(s, lcode).result,
             RomFormat::Alts => self.romanize_string::<rom_format::Alts>(s, lcode).result,
             RomFormat::Lattice => self.romanize_string::<rom_format::Lattice>(s, lcode).result,
+            RomFormat::LatticeNodes => RomanizationResult::Lattice(
+                self.romanize_string::<rom_format::Lattice>(s, lcode).to_nodes(),
+            ),
+            RomFormat::Json => self.romanize_string::<rom_format::Json>(s, lcode).result,
+            RomFormat::Jsonl => RomanizationResult::Str(self.romanize_jsonl(s, s, lcode, None)),
+        }
+    }
+
+    /// Returns the `RomFormat::Jsonl` object of `s`, with `orig` as its original text.
+    ///
+    /// `rom` is the text of the edges, which are those of the string output, with its
+    /// collapsed whitespace and expansion limit. The `lcode` of a `::lcode` directive is
+    /// given as `directive_lcode`.
+    fn romanize_jsonl(
+        &self,
+        orig: &str,
+        s: &str,
+        lcode: Option<&str>,
+        directive_lcode: Option<&str>,
+    ) -> String {
+        let normalized = self.options.normalize_input(s);
+        let edges = self.output_edges(&normalized, lcode, s.len());
+        let rom: String = edges.iter().map(|edge| edge.txt()).collect();
+        json::jsonl_record(orig, &rom, &edges, directive_lcode)
+    }
+
+    /// Romanizes a mixed-language string, guessing a language code for each script run.
+    ///
+    /// The string is split into runs of the same script (Han, kana and Hangul count as one
+    /// script). Each run is romanized with the language code guessed by
+    /// `Uroman::guess_lcode`, or with `default_lcode` if no language can be guessed.
+    /// Whitespace between two runs is copied unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let result = uroman.romanize_segments("東京へ Київ", None);
+    ///
+    /// assert_eq!(result, "dongjinghe Kyiv");
+    /// ```
+    pub fn romanize_segments(&self, s: &str, default_lcode: Option<&str>) -> String {
+        let segments = script::script_runs(&self.inner, s).into_iter().map(|range| {
+            let lcode = script::guess_lcode(&self.inner, &s[range.clone()]).or(default_lcode);
+            (range, lcode)
+        });
+        self.romanize_ranges(s, segments, default_lcode)
+    }
+
+    /// Romanizes a mixed-language string with explicit language codes for byte ranges.
+    ///
+    /// Text outside of `segments` is romanized with `default_lcode`, except for
+    /// whitespace-only gaps, which are copied unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges are not sorted, overlap, or do not lie on character
+    /// boundaries of `s`.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let result = uroman.romanize_segments_with_lcodes("Київ Київ", &[(0..8, "ukr")], None);
+    ///
+    /// assert_eq!(result, "Kyiv Kiyiv");
+    /// ```
+    pub fn romanize_segments_with_lcodes(
+        &self,
+        s: &str,
+        segments: &[(Range<usize>, &str)],
+        default_lcode: Option<&str>,
+    ) -> String {
+        let segments = segments
+            .iter()
+            .map(|(range, lcode)| (range.clone(), Some(*lcode)));
+        self.romanize_ranges(s, segments, default_lcode)
+    }
+
+    fn romanize_ranges<'a>(
+        &self,
+        s: &str,
+        segments: impl Iterator<Item = (Range<usize>, Option<&'a str>)>,
+        default_lcode: Option<&str>,
+    ) -> String {
+        let romanize_gap = |gap: &str, romanized: &mut String| {
+            if gap.chars().all(char::is_whitespace) {
+                romanized.push_str(gap);
+            } else {
+                let result = self.romanize_string::<rom_format::Str>(gap, default_lcode);
+                romanized.push_str(result.as_str());
+            }
+        };
+
+        let mut romanized = String::with_capacity(s.len());
+        let mut pos = 0;
+        for (range, lcode) in segments {
+            assert!(range.start >= pos, "Segments must be sorted and must not overlap");
+            romanize_gap(&s[pos..range.start], &mut romanized);
+            let result = self.romanize_string::<rom_format::Str>(&s[range.clone()], lcode);
+            romanized.push_str(result.as_str());
+            pos = range.end;
         }
+        romanize_gap(&s[pos..], &mut romanized);
+
+        romanized
+    }
+
+    /// Romanizes many strings in parallel.
+    ///
+    /// The output order matches the order of `texts`. Without the `parallel` feature,
+    /// the strings are romanized one after another.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let result = uroman.romanize_batch(&["ᚺᚨᛚᛚᛟ", "ᚹᛟᚱᛚᛞ"], None);
+    ///
+    /// assert_eq!(result, vec!["hallo", "world"]);
+    /// ```
+    pub fn romanize_batch(&self, texts: &[&str], lcode: Option<&str>) -> Vec<String> {
+        #[cfg(feature = "parallel")]
+        let texts = texts.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let texts = texts.iter();
+
+        texts
+            .map(|s| self.romanize_string::<rom_format::Str>(s, lcode).to_string())
+            .collect()
+    }
+
+    /// Romanizes many strings in parallel, each with its own language code.
+    ///
+    /// The output order matches the order of `texts`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `texts` and `lcodes` have different lengths.
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let result = uroman.romanize_batch_with_lcodes(&["Київ", "Київ"], &[Some("ukr"), None]);
+    ///
+    /// assert_eq!(result, vec!["Kyiv", "Kiyiv"]);
+    /// ```
+    pub fn romanize_batch_with_lcodes(&self, texts: &[&str], lcodes: &[Option<&str>]) -> Vec<String> {
+        assert_eq!(
+            texts.len(),
+            lcodes.len(),
+            "`texts` and `lcodes` must have the same length"
+        );
+
+        #[cfg(feature = "parallel")]
+        let pairs = texts.par_iter().zip(lcodes.par_iter());
+        #[cfg(not(feature = "parallel"))]
+        let pairs = texts.iter().zip(lcodes.iter());
+
+        pairs
+            .map(|(s, lcode)| self.romanize_string::<rom_format::Str>(s, *lcode).to_string())
+            .collect()
     }
 
     /// Romanizes a stream of text line by line and writes the output to another stream.
@@ -438,7 +2023,6 @@ impl Uroman {
 
         let mut buffer = vec![];
         let default_lcode = lcode;
-        let lcode_directive = "::lcode ";
 
         while reader.read_until(b'\n', &mut buffer)? > 0 {
             line_number += 1;
@@ -458,53 +2042,13 @@ impl Uroman {
                     n_error_messages_output += 1;
                 }
             }
-            let mut line_trimmed = &*line_str;
-
-            if line_trimmed.ends_with('\n') {
-                line_trimmed = &line_trimmed[..line_trimmed.len() - 1];
-            }
-            if line_trimmed.ends_with('\r') {
-                line_trimmed = &line_trimmed[..line_trimmed.len() - 1];
-            }
-
-            if let Some(rest_of_line) = line_trimmed.strip_prefix(lcode_directive) {
-                let parts: Vec<&str> = rest_of_line.splitn(2, char::is_whitespace).collect();
-                let (lcode, text_to_romanize) =
-                    (parts.first().cloned(), parts.get(1).cloned().unwrap_or(""));
-
-                let result = if decode_unicode {
-                    self.romanize_escaped_with_format(text_to_romanize, lcode, Some(rom_format))
-                } else {
-                    self.romanize_with_format(text_to_romanize, lcode, Some(rom_format))
-                };
-
-                match rom_format {
-                    RomFormat::Str => {
-                        let prefix = format!("{}{}{} ", lcode_directive, lcode.unwrap_or(""), "");
-                        let output = prefix + &result.to_string().unwrap();
-                        writeln!(writer, "{output}")?;
-                    }
-                    _ => {
-                        let meta_edge = format!(r#"[0,0,"","lcode: {}"]"#, lcode.unwrap_or(""));
-                        let result_json = result.to_string().unwrap();
-                        if let Some(stripped) = result_json.strip_prefix('[') {
-                            writeln!(writer, "[{meta_edge},{stripped}")?;
-                        } else {
-                            writeln!(writer, "{result_json}")?;
-                        }
-                    }
-                }
-            } else {
-                let result = if decode_unicode {
-                    self.romanize_escaped_with_format(line_trimmed, default_lcode, Some(rom_format))
-                } else {
-                    self.romanize_with_format(line_trimmed, default_lcode, Some(rom_format))
-                };
-                let output = result
-                    .to_string()
-                    .expect("JSON serialization failed");
-                writeln!(writer, "{output}")?;
-            }
+            let output = self.romanize_file_line(
+                trim_line_ending(&line_str),
+                default_lcode,
+                rom_format,
+                decode_unicode,
+            )?;
+            writeln!(writer, "{output}")?;
 
             if let Some(max) = max_lines
                 && line_number >= max
@@ -527,13 +2071,61 @@ impl Uroman {
         Ok(())
     }
 
+    /// Lazily romanizes a stream of text line by line.
+    ///
+    /// Each item is the romanized line without its line ending. Lines are handled like
+    /// in `romanize_file` with `RomFormat::Str`, including `::lcode` directives, but
+    /// non-UTF-8 characters are replaced without printing a warning.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    ///
+    /// # Example
+    /// ```
+    /// # use uroman::Uroman;
+    /// # let uroman = Uroman::new();
+    /// let input = "ᚺᚨᛚᛚᛟ\r\n::lcode ukr Київ\n".as_bytes();
+    /// let lines: Vec<String> = uroman
+    ///     .romanize_lines(input, None)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(lines, vec!["hallo", "::lcode ukr Kyiv"]);
+    /// ```
+    pub fn romanize_lines<'a, R: BufRead + 'a>(
+        &'a self,
+        mut reader: R,
+        lcode: Option<&'a str>,
+    ) -> impl Iterator<Item = io::Result<String>> + 'a {
+        let mut buffer = vec![];
+
+        std::iter::from_fn(move || {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => None,
+                Ok(_) => {
+                    let line_str = String::from_utf8_lossy(&buffer);
+                    let line = trim_line_ending(&line_str);
+                    Some(
+                        self.romanize_file_line(line, lcode, RomFormat::Str, false)
+                            .map_err(io::Error::other),
+                    )
+                }
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+
     /// Romanizes a stream of text line by line in parallel for maximum performance.
     ///
     /// This version reads the entire input into memory to process lines concurrently using
     /// multiple CPU cores. It is significantly faster than `romanize_file` but requires
     /// more memory. For very large files, consider using the sequential `romanize_file`.
     ///
-    /// The output order is preserved.
+    /// The output order is preserved. Without the `parallel` feature, the lines are
+    /// romanized one after another.
     ///
     /// # Differences from `romanize_file`
     ///
@@ -567,47 +2159,18 @@ impl Uroman {
 
         let line_count = lines.len();
         let default_lcode = lcode;
-        let lcode_directive = "::lcode ";
         // UTF-8 error handling is simplified as `lines()` replaces invalid sequences.
         // The original byte-level diff check is not replicated here.
 
+        #[cfg(feature = "parallel")]
+        let lines = lines.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let lines = lines.iter();
+
         let results: Vec<String> = lines
-            .par_iter()
             .map(|line| {
-                if let Some(rest_of_line) = line.strip_prefix(lcode_directive) {
-                    let parts: Vec<&str> = rest_of_line.splitn(2, char::is_whitespace).collect();
-                    let (lcode, text_to_romanize) =
-                        (parts.first().cloned(), parts.get(1).cloned().unwrap_or(""));
-
-                    let result = if decode_unicode {
-                        self.romanize_escaped_with_format(text_to_romanize, lcode, Some(rom_format))
-                    } else {
-                        self.romanize_with_format(text_to_romanize, lcode, Some(rom_format))
-                    };
-
-                    let output = result.to_string().unwrap_or_default();
-
-                    match rom_format {
-                        RomFormat::Str => {
-                            format!("{}{}{} {}", lcode_directive, lcode.unwrap_or(""), "", output)
-                        }
-                        _ => {
-                            let meta_edge = format!(r#"[0,0,"","lcode: {}"]"#, lcode.unwrap_or(""));
-                            if let Some(stripped) = output.strip_prefix('[') {
-                                format!("[{meta_edge},{stripped}")
-                            } else {
-                                output
-                            }
-                        }
-                    }
-                } else {
-                    let result = if decode_unicode {
-                        self.romanize_escaped_with_format(line, default_lcode, Some(rom_format))
-                    } else {
-                        self.romanize_with_format(line, default_lcode, Some(rom_format))
-                    };
-                    result.to_string().unwrap_or_default()
-                }
+                self.romanize_file_line(line, default_lcode, rom_format, decode_unicode)
+                    .unwrap_or_default()
             })
             .collect();
 
@@ -622,4 +2185,65 @@ impl Uroman {
         writer.flush()?;
         Ok(())
     }
+
+    /// Romanizes a single line of a file, honoring a leading `::lcode <code>` directive.
+    ///
+    /// `line` must not contain the line ending.
+    fn romanize_file_line(
+        &self,
+        line: &str,
+        default_lcode: Option<&str>,
+        rom_format: RomFormat,
+        decode_unicode: bool,
+    ) -> Result<String, RomanizationError> {
+        let romanize = |s: &str, lcode: Option<&str>| {
+            if decode_unicode {
+                self.romanize_escaped_with_format(s, lcode, Some(rom_format))
+            } else {
+                self.romanize_with_format(s, lcode, Some(rom_format))
+            }
+        };
+
+        let Some(rest_of_line) = line.strip_prefix(LCODE_DIRECTIVE) else {
+            return romanize(line, default_lcode).to_string();
+        };
+
+        let parts: Vec<&str> = rest_of_line.splitn(2, char::is_whitespace).collect();
+        let (lcode, text_to_romanize) =
+            (parts.first().cloned(), parts.get(1).cloned().unwrap_or(""));
+        if rom_format == RomFormat::Jsonl {
+            let decoded = if decode_unicode {
+                decode_unicode_escapes(text_to_romanize)
+            } else {
+                text_to_romanize.to_string()
+            };
+            return Ok(self.romanize_jsonl(text_to_romanize, &decoded, lcode, lcode));
+        }
+        let output = romanize(text_to_romanize, lcode).to_string()?;
+
+        match rom_format {
+            RomFormat::Str => Ok(format!("{}{} {}", LCODE_DIRECTIVE, lcode.unwrap_or(""), output)),
+            // The language code is given by a node without edges, in the shape of the others.
+            RomFormat::LatticeNodes => {
+                let meta_node = serde_json::json!({
+                    "position": 0,
+                    "edges": [],
+                    "lcode": lcode.unwrap_or(""),
+                });
+                if let Some(stripped) = output.strip_prefix('[') {
+                    Ok(format!("[{meta_node},{stripped}"))
+                } else {
+                    Ok(output)
+                }
+            }
+            _ => {
+                let meta_edge = format!(r#"[0,0,"","lcode: {}"]"#, lcode.unwrap_or(""));
+                if let Some(stripped) = output.strip_prefix('[') {
+                    Ok(format!("[{meta_edge},{stripped}"))
+                } else {
+                    Ok(output)
+                }
+            }
+        }
+    }
 }
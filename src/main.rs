@@ -3,7 +3,8 @@
 use clap::{Parser, ValueEnum};
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
-use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::{fs, time};
 use thiserror::Error;
@@ -17,6 +18,9 @@ enum CliRomFormat {
     Edges,
     Alts,
     Lattice,
+    LatticeNodes,
+    Json,
+    Jsonl,
 }
 
 impl From<CliRomFormat> for RomFormat {
@@ -26,6 +30,9 @@ impl From<CliRomFormat> for RomFormat {
             CliRomFormat::Edges => RomFormat::Edges,
             CliRomFormat::Alts => RomFormat::Alts,
             CliRomFormat::Lattice => RomFormat::Lattice,
+            CliRomFormat::LatticeNodes => RomFormat::LatticeNodes,
+            CliRomFormat::Json => RomFormat::Json,
+            CliRomFormat::Jsonl => RomFormat::Jsonl,
         }
     }
 }
@@ -46,6 +53,9 @@ enum UromanError {
 
     #[error("Romanization failed: {0}")]
     Romanization(#[from] RomanizationError),
+
+    #[error("Line {line} of the TSV input has no column {column}")]
+    TsvMissingColumn { line: usize, column: usize },
 }
 
 #[derive(Parser, Debug)]
@@ -68,7 +78,7 @@ struct Cli {
     lcode: Option<String>,
 
     /// Output format of romanization. 'edges' provides offsets.
-    #[arg(short = 'f', long, value_enum, default_value_t = CliRomFormat::default())]
+    #[arg(short = 'f', long, visible_alias = "format", value_enum, default_value_t = CliRomFormat::default())]
     rom_format: CliRomFormat,
 
     /// Limit uroman to the first n lines of a file.
@@ -83,6 +93,22 @@ struct Cli {
     #[arg(short = 'p', long = "use-parallel", action = clap::ArgAction::SetTrue)]
     use_parallel: bool,
 
+    /// Read tab-separated rows and append the romanization of the text column to each row.
+    ///
+    /// Fields are split on every tab, without quoting, so fields cannot contain tabs or
+    /// newlines. Only the 'str' format is supported.
+    #[arg(long, requires = "text_col", conflicts_with_all = ["use_parallel", "rom_format"])]
+    tsv: bool,
+
+    /// Column holding the text to romanize in TSV mode, starting from 1.
+    #[arg(long, value_name = "N", requires = "tsv")]
+    text_col: Option<NonZeroUsize>,
+
+    /// Column holding the language code of each row in TSV mode, starting from 1.
+    /// Rows with an empty language code use '--lcode'.
+    #[arg(long, value_name = "M", requires = "tsv")]
+    lcode_col: Option<NonZeroUsize>,
+
     /// Run and display a few samples.
     #[arg(long, action = clap::ArgAction::SetTrue)]
     sample: bool,
@@ -90,6 +116,11 @@ struct Cli {
     /// Suppress progress indicators.
     #[arg(long, action = clap::ArgAction::SetTrue)]
     silent: bool,
+
+    /// Read gzip-compressed input and write gzip-compressed output.
+    #[cfg(feature = "gzip")]
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    gzip: bool,
 }
 
 fn main() {
@@ -128,7 +159,7 @@ fn run() -> Result<(), UromanError> {
         return Ok(());
     }
 
-    let mut writer = get_writer(&cli.output_filename)?;
+    let mut writer = get_writer(&cli)?;
 
     if !cli.direct_input.is_empty() {
         process_direct_input(&uroman, &cli, &mut writer)?;
@@ -138,7 +169,7 @@ fn run() -> Result<(), UromanError> {
         process_stream(&uroman, &cli, &mut writer)?;
     }
 
-    writer.flush()?;
+    writer.finish()?;
 
     if cli.sample {
         println!(
@@ -168,9 +199,13 @@ fn process_direct_input(
 }
 
 fn process_stream(uroman: &Uroman, cli: &Cli, writer: &mut dyn Write) -> Result<(), UromanError> {
-    let reader = get_reader(&cli.input_filename)?;
+    let reader = Utf8LineReader::new(get_reader(cli)?);
 
-    if cli.use_parallel {
+    if let Some(text_col) = cli.text_col {
+        return process_tsv(uroman, cli, reader, writer, text_col);
+    }
+
+    let result = if cli.use_parallel {
         uroman.romanize_file_parallel(
             reader,
             writer,
@@ -179,7 +214,7 @@ fn process_stream(uroman: &Uroman, cli: &Cli, writer: &mut dyn Write) -> Result<
             cli.max_lines,
             cli.decode_unicode,
             cli.silent,
-        )?;
+        )
     } else {
         uroman.romanize_file(
             reader,
@@ -189,35 +224,177 @@ fn process_stream(uroman: &Uroman, cli: &Cli, writer: &mut dyn Write) -> Result<
             cli.max_lines,
             cli.decode_unicode,
             cli.silent,
-        )?;
+        )
+    };
+    // Report I/O errors, such as invalid UTF-8 or a closed pipe, as such.
+    result.map_err(|err| match err {
+        RomanizationError::Io(e) => UromanError::Io(e),
+        err => err.into(),
+    })
+}
+
+fn get_reader(cli: &Cli) -> Result<Box<dyn BufRead>, UromanError> {
+    let reader: Box<dyn Read> = match &cli.input_filename {
+        Some(p) => Box::new(fs::File::open(p).map_err(|e| UromanError::InputFileOpen {
+            path: p.clone(),
+            source: e,
+        })?),
+        None => Box::new(io::stdin()),
+    };
+    #[cfg(feature = "gzip")]
+    if cli.gzip {
+        return Ok(Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(reader))));
+    }
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+fn process_tsv(
+    uroman: &Uroman,
+    cli: &Cli,
+    reader: impl BufRead,
+    writer: &mut dyn Write,
+    text_col: NonZeroUsize,
+) -> Result<(), UromanError> {
+    for (i, line) in reader.lines().enumerate() {
+        if cli.max_lines.is_some_and(|max| i >= max) {
+            break;
+        }
+        let line = line?;
+        let row = line.strip_suffix('\r').unwrap_or(&line);
+        let fields: Vec<&str> = row.split('\t').collect();
+        let field = |column: NonZeroUsize| {
+            fields
+                .get(column.get() - 1)
+                .copied()
+                .ok_or(UromanError::TsvMissingColumn {
+                    line: i + 1,
+                    column: column.get(),
+                })
+        };
+
+        let text = field(text_col)?;
+        let lcode = match cli.lcode_col {
+            Some(lcode_col) => Some(field(lcode_col)?).filter(|lcode| !lcode.is_empty()),
+            None => None,
+        }
+        .or(cli.lcode.as_deref());
+        let romanized = if cli.decode_unicode {
+            uroman.romanize_escaped::<rom_format::Str>(text, lcode)
+        } else {
+            uroman.romanize_string::<rom_format::Str>(text, lcode)
+        };
+        writeln!(writer, "{row}\t{}", romanized.to_string())?;
     }
     Ok(())
 }
 
-fn get_reader(path: &Option<PathBuf>) -> Result<Box<dyn BufRead>, UromanError> {
-    match path {
-        Some(p) => {
-            let file = fs::File::open(p).map_err(|e| UromanError::InputFileOpen {
-                path: p.clone(),
-                source: e,
-            })?;
-            Ok(Box::new(BufReader::new(file)))
+/// A reader that passes its input through line by line and fails with
+/// `io::ErrorKind::InvalidData` on the first line that is not valid UTF-8.
+struct Utf8LineReader<R> {
+    inner: R,
+    line: Vec<u8>,
+    pos: usize,
+    line_number: usize,
+}
+
+impl<R: BufRead> Utf8LineReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line: Vec::new(),
+            pos: 0,
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Utf8LineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Utf8LineReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.line.len() {
+            self.line.clear();
+            self.pos = 0;
+            if self.inner.read_until(b'\n', &mut self.line)? > 0 {
+                self.line_number += 1;
+                if let Err(e) = std::str::from_utf8(&self.line) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid UTF-8 on line {}: {e}", self.line_number),
+                    ));
+                }
+            }
+        }
+        Ok(&self.line[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.line.len());
+    }
+}
+
+/// The output file or stdout, compressed with `--gzip`.
+enum OutputWriter {
+    Plain(BufWriter<Box<dyn Write>>),
+    #[cfg(feature = "gzip")]
+    Gzip(BufWriter<flate2::write::GzEncoder<Box<dyn Write>>>),
+}
+
+impl OutputWriter {
+    /// Flushes the output and writes the end of the gzip stream, whose errors a drop
+    /// of the encoder would ignore.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(mut writer) => writer.flush(),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(writer) => {
+                let encoder = writer.into_inner().map_err(io::IntoInnerError::into_error)?;
+                encoder.finish()?.flush()
+            }
         }
-        None => Ok(Box::new(BufReader::new(io::stdin()))),
     }
 }
 
-fn get_writer(path: &Option<PathBuf>) -> Result<Box<dyn Write>, UromanError> {
-    match path {
-        Some(p) => {
-            let file = fs::File::create(p).map_err(|e| UromanError::OutputFileCreate {
-                path: p.clone(),
-                source: e,
-            })?;
-            Ok(Box::new(BufWriter::new(file)))
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputWriter::Plain(writer) => writer.write(buf),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(writer) => writer.write(buf),
         }
-        None => Ok(Box::new(BufWriter::new(io::stdout()))),
     }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(writer) => writer.flush(),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(writer) => writer.flush(),
+        }
+    }
+}
+
+fn get_writer(cli: &Cli) -> Result<OutputWriter, UromanError> {
+    let writer: Box<dyn Write> = match &cli.output_filename {
+        Some(p) => Box::new(fs::File::create(p).map_err(|e| UromanError::OutputFileCreate {
+            path: p.clone(),
+            source: e,
+        })?),
+        None => Box::new(io::stdout()),
+    };
+    #[cfg(feature = "gzip")]
+    if cli.gzip {
+        let encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+        return Ok(OutputWriter::Gzip(BufWriter::new(encoder)));
+    }
+    Ok(OutputWriter::Plain(BufWriter::new(writer)))
 }
 
 fn run_repl(uroman: &Uroman, cli: &Cli) -> Result<(), UromanError> {
@@ -341,8 +518,7 @@ fn show_samples(uroman: &Uroman) -> Result<(), UromanError> {
     println!("---------------------------------------");
 
     let num_samples = samples.len() as u128;
-    if num_samples > 0 {
-        let avg_duration_ns = total_duration_ns / num_samples;
+    if let Some(avg_duration_ns) = total_duration_ns.checked_div(num_samples) {
         let avg_duration_us = avg_duration_ns as f64 / 1_000.0;
         let avg_duration_ms = avg_duration_us / 1_000.0;
 
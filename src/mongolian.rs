@@ -0,0 +1,119 @@
+//! Transliterates the traditional Mongolian script as in scholarly works on Classical
+//! Mongolian (e.g., ᠮᠣᠩᠭᠣᠯ becomes `mongγol`).
+//!
+//! Mongolian is written vertically, and most letters take an isolated, initial, medial
+//! or final form. These forms are not encoded: a letter is stored once, and the font
+//! chooses its form by its position in the word, or as selected by a free variation
+//! selector after it. The selectors, and the Mongolian vowel separator that marks the
+//! detached form of a final `a` or `e`, therefore change only the glyph and are written
+//! as nothing. The velars, which the romanization tables write alike in every word,
+//! follow the vowel harmony of the word: ᠬ is `q` in a word with back vowels and `k`
+//! otherwise, and ᠭ is `γ` and `g`.
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+const QA: char = '\u{182C}';
+const GA: char = '\u{182D}';
+/// The narrow no-break space that joins a suffix to its stem (e.g., `"ᠭᠡᠷ\u{202F}ᠦᠨ"`).
+const SUFFIX_CONNECTOR: char = '\u{202F}';
+
+impl Lattice<'_> {
+    /// Replaces the edges of every letter and selector of the traditional Mongolian
+    /// script with its transliteration.
+    pub fn apply_mongolian_transliteration(&mut self) {
+        let chars = &self.s_chars;
+        let mut edges = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            if !is_letter(chars[start]) {
+                start += 1;
+                continue;
+            }
+            // A word runs across its selectors and suffixes, which follow its harmony.
+            let end = (start..chars.len())
+                .find(|&i| {
+                    !(is_letter(chars[i])
+                        || is_glyph_selector(chars[i])
+                        || chars[i] == SUFFIX_CONNECTOR
+                            && chars.get(i + 1).is_some_and(|&c| is_letter(c)))
+                })
+                .unwrap_or(chars.len());
+            let word = &chars[start..end];
+            let back = word.iter().any(|&c| is_back_vowel(c));
+            for (i, &c) in word.iter().enumerate() {
+                let txt = match c {
+                    QA if back => "q",
+                    QA => "k",
+                    GA if back => "γ",
+                    GA => "g",
+                    SUFFIX_CONNECTOR => "-",
+                    c if is_glyph_selector(c) => "",
+                    c => letter(c),
+                };
+                edges.push((start + i, txt));
+            }
+            start = end;
+        }
+
+        for (i, txt) in edges {
+            let edge = Edge::new_regular(i, i + 1, txt.to_string(), "rom mongolian".to_string());
+            self.replace_edges_in_span(i, i + 1, vec![edge]);
+        }
+    }
+}
+
+/// Returns `true` if `c` is a letter of the Mongolian alphabet, from ᠠ to ᡂ.
+fn is_letter(c: char) -> bool {
+    ('\u{1820}'..='\u{1842}').contains(&c)
+}
+
+/// Returns `true` if `c` is a free variation selector or the vowel separator, which only
+/// select the form of a letter.
+fn is_glyph_selector(c: char) -> bool {
+    matches!(c, '\u{180B}'..='\u{180F}')
+}
+
+/// Returns `true` if `c` is one of the back (masculine) vowels ᠠ, ᠣ and ᠤ.
+fn is_back_vowel(c: char) -> bool {
+    matches!(c, '\u{1820}' | '\u{1823}' | '\u{1824}')
+}
+
+/// Returns the transliteration of a letter other than ᠬ and ᠭ.
+fn letter(c: char) -> &'static str {
+    match c {
+        '\u{1820}' => "a",
+        '\u{1821}' => "e",
+        '\u{1822}' => "i",
+        '\u{1823}' => "o",
+        '\u{1824}' => "u",
+        '\u{1825}' => "ö",
+        '\u{1826}' => "ü",
+        '\u{1827}' => "ē",
+        '\u{1828}' => "n",
+        '\u{1829}' => "ng",
+        '\u{182A}' => "b",
+        '\u{182B}' => "p",
+        '\u{182E}' => "m",
+        '\u{182F}' => "l",
+        '\u{1830}' => "s",
+        '\u{1831}' => "š",
+        '\u{1832}' => "t",
+        '\u{1833}' => "d",
+        '\u{1834}' => "č",
+        '\u{1835}' => "ǰ",
+        '\u{1836}' => "y",
+        '\u{1837}' => "r",
+        '\u{1838}' => "w",
+        '\u{1839}' => "f",
+        '\u{183A}' => "k",
+        '\u{183B}' => "kh",
+        '\u{183C}' => "c",
+        '\u{183D}' => "z",
+        '\u{183E}' => "h",
+        '\u{183F}' => "ž",
+        '\u{1840}' => "lh",
+        '\u{1841}' => "zh",
+        _ => "ch",
+    }
+}
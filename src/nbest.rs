@@ -0,0 +1,95 @@
+//! Finds the highest-scoring distinct romanizations of a lattice.
+//!
+//! The score of a path is the sum of the logarithms of the `Edge::score` of its edges,
+//! with a penalty for every edge, so that a path of few, long edges is preferred, as
+//! with the best path. Scores are logarithms so that they do not underflow for long text.
+
+use std::collections::HashMap;
+
+use crate::edge::Edge;
+
+/// The factor by which every edge lowers the score of a path, besides its own score.
+const EDGE_PENALTY: f64 = 0.5;
+
+/// A partial path from the start of the text, with its romanization.
+struct Hypothesis {
+    score: f64,
+    text: String,
+}
+
+/// Returns the `k` highest-scoring distinct romanizations of the text of `len` characters
+/// covered by `edges`, highest first.
+///
+/// For every position, the search keeps the `k` best distinct romanizations of the text
+/// before it. This is exact: a romanization that is not among them at some position is
+/// outscored by `k` others that continue the same way, each with a distinct result.
+/// Positions without an outgoing edge are skipped, as by the best path.
+pub(crate) fn nbest_romanizations(edges: &[Edge], len: usize, k: usize) -> Vec<(String, f32)> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut outgoing: HashMap<usize, Vec<&Edge>> = HashMap::new();
+    // Edges that cover no characters, such as the spaces of `segment_words`, are written
+    // wherever a path passes their position, without lowering its score.
+    let mut inserted: HashMap<usize, &str> = HashMap::new();
+    for edge in edges.iter().filter(|edge| edge.end() <= len) {
+        if edge.start() < edge.end() {
+            outgoing.entry(edge.start()).or_default().push(edge);
+        } else {
+            inserted.entry(edge.start()).or_insert(edge.txt());
+        }
+    }
+
+    let mut beams: Vec<Vec<Hypothesis>> = (0..=len).map(|_| Vec::new()).collect();
+    beams[0].push(Hypothesis {
+        score: 0.0,
+        text: String::new(),
+    });
+    for pos in 0..len {
+        let mut beam = Vec::new();
+        prune(&mut beam, std::mem::take(&mut beams[pos]), k);
+        if let Some(txt) = inserted.get(&pos) {
+            for hypothesis in &mut beam {
+                hypothesis.text.push_str(txt);
+            }
+        }
+
+        let Some(edges) = outgoing.get(&pos) else {
+            beams[pos + 1].extend(beam);
+            continue;
+        };
+        for hypothesis in &beam {
+            for edge in edges {
+                beams[edge.end()].push(Hypothesis {
+                    score: hypothesis.score + (f64::from(edge.score()) * EDGE_PENALTY).ln(),
+                    text: format!("{}{}", hypothesis.text, edge.txt()),
+                });
+            }
+        }
+    }
+
+    let mut best = Vec::new();
+    prune(&mut best, std::mem::take(&mut beams[len]), k);
+    best.into_iter()
+        .map(|hypothesis| (hypothesis.text, hypothesis.score as f32))
+        .collect()
+}
+
+/// Moves the `k` highest-scoring hypotheses of `beam` with distinct texts into `out`,
+/// ordered by decreasing score and then by text.
+fn prune(out: &mut Vec<Hypothesis>, mut beam: Vec<Hypothesis>, k: usize) {
+    beam.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.text.cmp(&b.text))
+    });
+    for hypothesis in beam {
+        if out.len() == k {
+            break;
+        }
+        if !out.iter().any(|kept| kept.text == hypothesis.text) {
+            out.push(hypothesis);
+        }
+    }
+}
@@ -0,0 +1,817 @@
+//! Defines the `RomanizationOptions` applied by a configured `Uroman` instance.
+//!
+//! Options are collected by `UromanBuilder`. Most are applied to the edges of a
+//! romanization after the lattice has been decoded; the `Normalization` is applied
+//! to the input before the lattice is built, and the romanization schemes to the
+//! lattice itself.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd};
+use unicode_normalization::char::{decompose_compatible, is_combining_mark};
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::arabic::ArabicScheme;
+use crate::chinese::{ChineseScheme, ChineseTones, Jyutping};
+use crate::cyrillic::CyrillicScheme;
+use crate::edge::Edge;
+use crate::fallback::Fallback;
+use crate::georgian::GeorgianScheme;
+use crate::greek::GreekScheme;
+use crate::indic::IndicScheme;
+use crate::ipa::OutputTarget;
+use crate::japanese::{JapaneseScheme, LongVowelStyle};
+use crate::korean::KoreanScheme;
+use crate::lattice::Lattice;
+use crate::scheme::Scheme;
+use crate::symbol::{EmojiPolicy, ZERO_WIDTH_JOINER};
+use crate::tibetan::TibetanScheme;
+use crate::utils::{needs_mark_reordering, reorder_combining_marks};
+
+/// The Unicode normalization applied to the input before rule matching.
+///
+/// The romanization tables are written in precomposed form, so `Nfc` is the
+/// recommended setting for input of mixed or unknown origin. Note that NFC also maps
+/// CJK compatibility ideographs to their unified counterparts.
+///
+/// The offsets of edges and alignments are relative to the normalized text, which
+/// can differ in length from the input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Normalization {
+    /// The input is matched as is.
+    #[default]
+    None,
+    /// The input is converted to Normalization Form C (canonical composition).
+    Nfc,
+    /// The input is converted to Normalization Form D (canonical decomposition).
+    Nfd,
+}
+
+impl Normalization {
+    /// Normalizes `s`, borrowing it if it is already in the requested form.
+    pub(crate) fn apply<'a>(self, s: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalization::Nfc if !is_nfc(s) => Cow::Owned(s.nfc().collect()),
+            Normalization::Nfd if !is_nfd(s) => Cow::Owned(s.nfd().collect()),
+            _ => Cow::Borrowed(s),
+        }
+    }
+}
+
+/// How numbers are romanized.
+///
+/// | Input  | `Spell` | `AsciiDigits` | `Passthrough` |
+/// |--------|---------|---------------|---------------|
+/// | 2024   | 2024    | 2024          | 2024          |
+/// | ١٠     | 10      | 10            | ١٠            |
+/// | ١٬٠٠٠  | 1,000   | 1,000         | ١٬٠٠٠         |
+/// | 三百    | 300     | sanbai        | sanbai        |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NumericMode {
+    /// Numbers are interpreted and written with ASCII digits, including numerals written
+    /// with letters or ideographs (e.g., 三百 becomes `300`).
+    #[default]
+    Spell,
+    /// Digits are mapped one by one to the ASCII digits `0`-`9`. Numbers are not
+    /// interpreted, so numerals written with letters or ideographs are romanized as text.
+    AsciiDigits,
+    /// Digits and the separators of other scripts between them (e.g., the ٬ of ١٬٠٠٠)
+    /// are left as they are, and numerals written with letters or ideographs are
+    /// romanized as text.
+    Passthrough,
+}
+
+/// How `Uroman::romanize_bytes` handles bytes that are not valid UTF-8.
+///
+/// | Input        | `Replace` | `Skip` | `Error`                          |
+/// |--------------|-----------|--------|----------------------------------|
+/// | `b"a\xFFb"` | a�b       | ab     | `InvalidUtf8 { byte_offset: 1 }` |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InvalidUtf8 {
+    /// Each invalid sequence is replaced with U+FFFD REPLACEMENT CHARACTER, as by
+    /// `String::from_utf8_lossy`, which is romanized as itself.
+    #[default]
+    Replace,
+    /// Invalid sequences are removed.
+    Skip,
+    /// The input is rejected at the first invalid sequence.
+    Error,
+}
+
+/// Behavior settings shared by every `romanize_*` call of a `Uroman` instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RomanizationOptions {
+    /// If `false`, the romanized output is folded to lowercase.
+    pub case_sensitive: bool,
+    /// The character emitted where the rules produce a syllable-boundary apostrophe, or
+    /// `None` to omit it.
+    pub syllable_separator: Option<char>,
+    /// If `true`, diacritics are stripped from the romanized output (e.g., `ā` to `a`).
+    pub ascii_fold: bool,
+    /// If `true`, the output of an edge whose source starts with an uppercase letter is title-cased.
+    pub preserve_case: bool,
+    /// The normalization applied to the input before the lattice is built.
+    pub normalization: Normalization,
+    /// If `true`, the Halfwidth and Fullwidth Forms block is mapped to its standard forms.
+    pub normalize_fullwidth: bool,
+    /// If `true`, compatibility ligatures (e.g., `ﬁ`, `և`) are expanded to their letters.
+    pub expand_ligatures: bool,
+    /// If `true`, zero width joiners that only select a glyph in Indic and Arabic text are
+    /// removed, while zero width non-joiners are kept to block the rules spanning them.
+    pub normalize_joiners: bool,
+    /// If `true`, spaces are inserted between the words of Thai, Lao and Khmer.
+    pub segment_words: bool,
+    /// The scheme used for kana when `lcode` is `jpn`, or `None` for the table rules.
+    pub japanese_scheme: Option<JapaneseScheme>,
+    /// Overrides how the Japanese scheme writes long vowels.
+    pub long_vowels: Option<LongVowelStyle>,
+    /// The scheme used for Hangul when `lcode` is `kor`, or `None` for jamo-by-jamo romanization.
+    pub korean_scheme: Option<KoreanScheme>,
+    /// How pinyin tones are written when `lcode` is `zho`.
+    pub chinese_tones: ChineseTones,
+    /// The romanization of Mandarin used when `lcode` is `zho`.
+    pub chinese_scheme: ChineseScheme,
+    /// The scheme used for Georgian when `lcode` is `kat`, or `None` for the table rules.
+    pub georgian_scheme: Option<GeorgianScheme>,
+    /// The scheme used for Greek when `lcode` is `ell` or `grc`, or `None` for the table rules.
+    pub greek_scheme: Option<GreekScheme>,
+    /// The scheme used for Arabic when `lcode` is `ara`.
+    pub arabic_scheme: ArabicScheme,
+    /// If `true`, the harakat of vocalized Arabic are written under `ArabicScheme::Default`.
+    pub arabic_use_diacritics: bool,
+    /// If `true`, the Arabic definite article is assimilated to sun letters under
+    /// `ArabicScheme::Default`.
+    pub arabic_assimilate_article: bool,
+    /// If `true`, Hebrew is romanized from its niqqud when `lcode` is `heb`.
+    pub hebrew_niqqud: bool,
+    /// If `true`, the traditional Mongolian script is transliterated when `lcode` is `mon`.
+    pub traditional_mongolian: bool,
+    /// The scheme used for Cyrillic under any `lcode`.
+    pub cyrillic_scheme: CyrillicScheme,
+    /// The scheme used for Devanagari under any `lcode`, or `None` for the table rules.
+    pub indic_scheme: Option<IndicScheme>,
+    /// The scheme used for Tibetan when `lcode` is `bod`, or `None` for the table rules.
+    pub tibetan_scheme: Option<TibetanScheme>,
+    /// How Han characters are written in Jyutping when `lcode` is `yue`, or `None` for pinyin.
+    pub jyutping: Option<Jyutping>,
+    /// The schemes set for language codes, which take the place of the scheme options
+    /// above under those codes.
+    pub schemes: BTreeMap<String, Scheme>,
+    /// Edges with an `Edge::score` below this are dropped before decoding, where that keeps a full path.
+    pub min_edge_score: f32,
+    /// The maximum ratio of the bytes of the romanization to the bytes of the input, if any.
+    pub max_expansion_ratio: Option<f32>,
+    /// If `true`, Roman numerals in uppercase (e.g., `MCMLXXXIV`) are read as numbers.
+    pub recognize_roman_numerals: bool,
+    /// How numbers are romanized.
+    pub numeric_mode: NumericMode,
+    /// If `true`, runs of whitespace in the string output are collapsed to single spaces,
+    /// and each line is trimmed.
+    pub collapse_whitespace: bool,
+    /// If `true`, URLs, email addresses and @mentions are passed through verbatim.
+    pub preserve_tokens: bool,
+    /// If `true`, only romanizations that `Uroman::deromanize` can undo are applied.
+    pub reversible: bool,
+    /// How emoji and other symbols are romanized.
+    pub emoji_policy: EmojiPolicy,
+    /// What is written for a character that cannot be romanized.
+    pub fallback: Fallback,
+    /// The alphabet of the output.
+    pub output: OutputTarget,
+    /// How `Uroman::romanize_bytes` handles bytes that are not valid UTF-8.
+    pub invalid_utf8: InvalidUtf8,
+}
+
+impl Default for RomanizationOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            syllable_separator: Some('\''),
+            ascii_fold: false,
+            preserve_case: false,
+            normalization: Normalization::None,
+            normalize_fullwidth: false,
+            expand_ligatures: false,
+            normalize_joiners: false,
+            segment_words: false,
+            japanese_scheme: None,
+            long_vowels: None,
+            korean_scheme: None,
+            chinese_tones: ChineseTones::None,
+            chinese_scheme: ChineseScheme::Pinyin,
+            georgian_scheme: None,
+            greek_scheme: None,
+            arabic_scheme: ArabicScheme::Default,
+            arabic_use_diacritics: false,
+            arabic_assimilate_article: false,
+            hebrew_niqqud: false,
+            traditional_mongolian: false,
+            cyrillic_scheme: CyrillicScheme::Default,
+            indic_scheme: None,
+            tibetan_scheme: None,
+            jyutping: None,
+            schemes: BTreeMap::new(),
+            min_edge_score: 0.0,
+            max_expansion_ratio: None,
+            recognize_roman_numerals: false,
+            numeric_mode: NumericMode::Spell,
+            collapse_whitespace: false,
+            preserve_tokens: false,
+            reversible: false,
+            emoji_policy: EmojiPolicy::Keep,
+            fallback: Fallback::Keep,
+            output: OutputTarget::Latin,
+            invalid_utf8: InvalidUtf8::Replace,
+        }
+    }
+}
+
+impl RomanizationOptions {
+    /// Returns `true` if `apply_to_edges` would leave every edge unchanged, as it does in
+    /// the reversible mode.
+    pub(crate) fn is_default(&self) -> bool {
+        self.reversible
+            || self.case_sensitive
+            && self.syllable_separator == Some('\'')
+            && !self.ascii_fold
+            && !self.preserve_case
+            && self.output == OutputTarget::Latin
+    }
+
+    /// Returns `true` if `s` romanizes to itself, so that no lattice needs to be built.
+    ///
+    /// This holds for ASCII without digits, which are read as numbers, unless case is
+    /// changed, Roman numerals are recognized or, in the reversible mode, Latin letters
+    /// are escaped.
+    pub(crate) fn keeps_as_is(&self, s: &str) -> bool {
+        !self.reversible
+            && self.case_sensitive
+            && !self.preserve_case
+            && !self.recognize_roman_numerals
+            && s.bytes().all(|b| b.is_ascii() && !b.is_ascii_digit())
+    }
+
+    /// Applies the input normalizations, borrowing `s` if they leave it unchanged.
+    ///
+    /// Ligatures are expanded and fullwidth forms are mapped before the `Normalization`,
+    /// so that halfwidth katakana and their sound marks compose under NFC. Combining marks
+    /// are always put in canonical order, as in the source of the rules, so that a letter
+    /// with several marks is romanized the same whatever the order they were typed in.
+    pub(crate) fn normalize_input<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let mut mapped = Cow::Borrowed(s);
+        if self.expand_ligatures && mapped.chars().any(is_ligature) {
+            mapped = Cow::Owned(expand_ligatures(&mapped));
+        }
+        if self.normalize_fullwidth && mapped.chars().any(is_halfwidth_or_fullwidth_form) {
+            mapped = Cow::Owned(normalize_fullwidth(&mapped));
+        }
+        if self.normalize_joiners && has_rendering_joiner(&mapped) {
+            mapped = Cow::Owned(remove_rendering_joiners(&mapped));
+        }
+        let normalized = match mapped {
+            Cow::Borrowed(s) => self.normalization.apply(s),
+            Cow::Owned(s) => Cow::Owned(self.normalization.apply(&s).into_owned()),
+        };
+
+        if needs_mark_reordering(&normalized) {
+            Cow::Owned(reorder_combining_marks(&normalized))
+        } else {
+            normalized
+        }
+    }
+
+    /// Applies the input normalizations like `normalize_input`, and returns the pairs of
+    /// byte offsets of `s` and of the normalized text that correspond, in increasing order
+    /// and from `(0, 0)` to the ends of both.
+    ///
+    /// Each extended grapheme cluster of `s` is normalized on its own, and is merged with
+    /// the following ones where the normalization of the whole text changes it otherwise
+    /// (e.g., a joiner that is removed before a letter).
+    pub(crate) fn normalize_input_with_offsets<'a>(
+        &self,
+        s: &'a str,
+    ) -> (Cow<'a, str>, Vec<(usize, usize)>) {
+        let normalized = self.normalize_input(s);
+        if let Cow::Borrowed(_) = normalized {
+            let offsets = s.char_indices().map(|(i, _)| (i, i)).chain([(s.len(), s.len())]);
+            return (normalized, offsets.collect());
+        }
+
+        let mut offsets = vec![(0, 0)];
+        let (mut start, mut normalized_end) = (0, 0);
+        for (i, grapheme) in s.grapheme_indices(true) {
+            let end = i + grapheme.len();
+            let chunk = self.normalize_input(&s[start..end]);
+            if normalized[normalized_end..].starts_with(chunk.as_ref()) {
+                normalized_end += chunk.len();
+                start = end;
+                offsets.push((end, normalized_end));
+            }
+        }
+        if start < s.len() || normalized_end < normalized.len() {
+            if start == s.len() {
+                offsets.pop();
+            }
+            offsets.push((s.len(), normalized.len()));
+        }
+        (normalized, offsets)
+    }
+
+    /// Replaces the edges of the lattice covered by the scheme selected for `lcode`, if any,
+    /// by the script schemes, the `EmojiPolicy` and the `Fallback`, and adds the edges of
+    /// Roman numerals and of verbatim tokens if enabled.
+    ///
+    /// In the reversible mode, only the reversible schemes are applied.
+    pub(crate) fn apply_to_lattice(&self, lat: &mut Lattice, lcode: Option<&str>) {
+        if self.reversible {
+            lat.apply_reversible(lcode);
+            return;
+        }
+        if let Some(scheme) = lcode.and_then(|lcode| self.schemes.get(lcode)) {
+            let mut options = self.clone();
+            options.schemes.clear();
+            scheme.apply(&mut options);
+            return options.apply_to_lattice(lat, lcode);
+        }
+        if self.numeric_mode != NumericMode::Spell {
+            apply_numeric_mode(lat, self.numeric_mode);
+        } else if self.recognize_roman_numerals {
+            lat.add_roman_numerals();
+        }
+        lat.apply_cyrillic_scheme(self.cyrillic_scheme);
+        if let Some(scheme) = self.indic_scheme {
+            lat.apply_indic_scheme(scheme);
+        }
+        match lcode {
+            Some("jpn") => match self.japanese_scheme {
+                Some(scheme) => lat.apply_japanese_scheme(scheme, self.long_vowels),
+                None if self.long_vowels.is_some() => {
+                    lat.apply_japanese_scheme(JapaneseScheme::Hepburn, self.long_vowels)
+                }
+                None => {}
+            },
+            Some("kor") => {
+                if let Some(scheme) = self.korean_scheme {
+                    lat.apply_korean_scheme(scheme);
+                }
+            }
+            Some("zho") => match self.chinese_scheme {
+                ChineseScheme::Pinyin => lat.apply_chinese_tones(self.chinese_tones),
+                ChineseScheme::WadeGiles => lat.apply_wade_giles(),
+            },
+            Some("kat") => {
+                if let Some(scheme) = self.georgian_scheme {
+                    lat.apply_georgian_scheme(scheme);
+                }
+            }
+            Some("ell" | "grc") => {
+                if let Some(scheme) = self.greek_scheme {
+                    lat.apply_greek_scheme(scheme);
+                }
+            }
+            Some("heb") if self.hebrew_niqqud => lat.apply_hebrew_niqqud(),
+            Some("mon") if self.traditional_mongolian => lat.apply_mongolian_transliteration(),
+            Some("yue") => {
+                if let Some(jyutping) = self.jyutping {
+                    lat.apply_jyutping(jyutping);
+                }
+            }
+            Some("bod") => {
+                if let Some(scheme) = self.tibetan_scheme {
+                    lat.apply_tibetan_scheme(scheme);
+                }
+            }
+            Some("ara") => match self.arabic_scheme {
+                ArabicScheme::Default => {
+                    if self.arabic_assimilate_article {
+                        lat.apply_arabic_article();
+                    }
+                    if self.arabic_use_diacritics {
+                        lat.apply_arabic_diacritics();
+                    }
+                }
+                scheme => lat.apply_arabic_scheme(scheme),
+            },
+            _ => {}
+        }
+        if self.output == OutputTarget::Ipa {
+            lat.apply_ipa(lcode);
+        }
+        lat.apply_emoji_policy(self.emoji_policy);
+        lat.apply_fallback(&self.fallback);
+        if self.preserve_tokens {
+            lat.add_verbatim_tokens();
+        }
+        if self.min_edge_score > 0.0 {
+            lat.prune_edges(self.min_edge_score);
+        }
+    }
+
+    /// Returns `true` if the lattice gets the edges of numbers that are interpreted.
+    pub(crate) fn interprets_numbers(&self) -> bool {
+        self.numeric_mode == NumericMode::Spell
+    }
+
+    /// Returns the maximum length in bytes of the romanization of `input_len` bytes, if
+    /// `max_expansion_ratio` is set.
+    pub(crate) fn expansion_limit(&self, input_len: usize) -> Option<usize> {
+        self.max_expansion_ratio
+            .map(|ratio| (f64::from(ratio) * input_len as f64).ceil() as usize)
+    }
+
+    /// Applies `collapse_whitespace` to the `txt` of the edges of a romanization path, as
+    /// `Uroman::romanize_into` applies it to the string output.
+    pub(crate) fn collapse_edge_whitespace(&self, edges: &mut [Edge]) {
+        if self.collapse_whitespace && !self.reversible {
+            collapse_edge_whitespace(edges);
+        }
+    }
+
+    /// Removes the alternative edges added to `edges` that score below `min_edge_score`.
+    ///
+    /// Alternatives can always be dropped, since they share their span with another edge.
+    pub(crate) fn prune_alternatives(&self, edges: &mut Vec<Edge>) {
+        edges.retain(|edge| {
+            !edge.r#type().starts_with("rom-alt") || edge.score() >= self.min_edge_score
+        });
+    }
+
+    /// Applies the options to the `txt` of each edge in place.
+    ///
+    /// `s_chars` is the source string the edge offsets refer to.
+    pub(crate) fn apply_to_edges(&self, edges: &mut [Edge], s_chars: &[char]) {
+        if self.is_default() {
+            return;
+        }
+
+        for edge in edges.iter_mut() {
+            if edge.r#type() == "verbatim" || edge.r#type() == "ipa" {
+                continue;
+            }
+            let (start, end) = (edge.start(), edge.end());
+            let data = edge.get_data_mut();
+
+            // Under `OutputTarget::Ipa`, letters that are not transcribed fall back to Latin.
+            if self.output == OutputTarget::Ipa
+                && data.r#type != "orig"
+                && data.txt.chars().any(char::is_alphabetic)
+            {
+                data.r#type = format!("latin {}", data.r#type);
+            }
+
+            // The apostrophes of Wade-Giles and of the Georgian national system mark
+            // aspiration and ejectives, not syllable boundaries.
+            if self.syllable_separator != Some('\'')
+                && !matches!(data.r#type.as_str(), "rom wade-giles" | "rom georgian-national")
+                && data.txt.contains('\'')
+                && !s_chars
+                    .get(start..end)
+                    .is_some_and(|span| span.contains(&'\''))
+            {
+                let separator = self.syllable_separator.map(String::from).unwrap_or_default();
+                data.txt = data.txt.replace('\'', &separator);
+            }
+
+            if self.ascii_fold && !data.txt.is_ascii() {
+                data.txt = ascii_fold(&data.txt);
+            }
+
+            if !self.case_sensitive {
+                data.txt = data.txt.to_lowercase();
+            }
+
+            if self.preserve_case
+                && s_chars
+                    .get(start..end)
+                    .and_then(|span| span.iter().find(|c| c.is_alphabetic()))
+                    .is_some_and(|c| c.is_uppercase())
+            {
+                data.txt = title_case(&data.txt);
+            }
+        }
+    }
+
+    /// Maps the characters of `alphabet` as `apply_to_edges` maps the text of the edges,
+    /// and adds the ASCII digits numbers are written with.
+    pub(crate) fn apply_to_alphabet(&self, alphabet: BTreeSet<char>) -> BTreeSet<char> {
+        let mut mapped = BTreeSet::new();
+        for c in alphabet {
+            if c == '\'' && self.syllable_separator != Some('\'') {
+                mapped.extend(self.syllable_separator);
+                continue;
+            }
+            let mut txt = c.to_string();
+            if self.ascii_fold && !c.is_ascii() {
+                txt = ascii_fold(&txt);
+            }
+            if !self.case_sensitive {
+                txt = txt.to_lowercase();
+            }
+            if self.preserve_case {
+                mapped.extend(txt.to_uppercase().chars());
+            }
+            mapped.extend(txt.chars());
+        }
+        if self.interprets_numbers() || self.numeric_mode == NumericMode::AsciiDigits {
+            mapped.extend('0'..='9');
+        }
+        mapped
+    }
+}
+
+/// Collapses each run of whitespace in `s` to a single space and trims each line,
+/// keeping the line breaks (`\n` or `\r\n`).
+pub(crate) fn collapse_whitespace(s: &str) -> String {
+    let lines: Vec<String> = s
+        .split('\n')
+        .map(|line| {
+            let (line, cr) = match line.strip_suffix('\r') {
+                Some(line) => (line, "\r"),
+                None => (line, ""),
+            };
+            line.split_whitespace().collect::<Vec<_>>().join(" ") + cr
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Collapses the whitespace of a romanization path as `collapse_whitespace` collapses the
+/// concatenation of its `txt`.
+///
+/// A collapsed run of whitespace is output by the edge where it begins, and the edges
+/// of whitespace that is removed are left with an empty `txt`.
+fn collapse_edge_whitespace(edges: &mut [Edge]) {
+    let mut txts = vec![String::new(); edges.len()];
+    let mut line_has_content = false;
+    // The edge where the run of whitespace after the last character of the line begins.
+    let mut pending_space = None;
+    // The edge of the previous character, if it was a `\r`.
+    let mut cr: Option<usize> = None;
+    for (i, edge) in edges.iter().enumerate() {
+        for c in edge.txt().chars() {
+            if c == '\n' {
+                if let Some(j) = cr {
+                    txts[j].push('\r');
+                }
+                txts[i].push('\n');
+                (line_has_content, pending_space, cr) = (false, None, None);
+            } else if c.is_whitespace() {
+                if line_has_content && pending_space.is_none() {
+                    pending_space = Some(i);
+                }
+                cr = (c == '\r').then_some(i);
+            } else {
+                if let Some(j) = pending_space.take() {
+                    txts[j].push(' ');
+                }
+                txts[i].push(c);
+                (line_has_content, cr) = (true, None);
+            }
+        }
+    }
+    if let Some(j) = cr {
+        txts[j].push('\r');
+    }
+
+    for (edge, txt) in edges.iter_mut().zip(txts) {
+        edge.get_data_mut().txt = txt;
+    }
+}
+
+/// Truncates the concatenation of the `txt` of `edges` to at most `limit` bytes, at the
+/// last character that fits, as `Uroman::romanize_into` truncates its output.
+pub(crate) fn truncate_edges(edges: &mut [Edge], limit: usize) {
+    let mut len = 0;
+    for edge in edges.iter_mut() {
+        let txt = &mut edge.get_data_mut().txt;
+        if len + txt.len() > limit {
+            let mut end = limit - len;
+            while !txt.is_char_boundary(end) {
+                end -= 1;
+            }
+            txt.truncate(end);
+        }
+        len += txt.len();
+    }
+}
+
+/// Strips the combining marks from each character of `s` after NFD decomposition.
+///
+/// Characters that do not fold to ASCII this way (e.g., `ø`) are kept unchanged.
+fn ascii_fold(s: &str) -> String {
+    let mut folded = String::with_capacity(s.len());
+    for c in s.chars() {
+        push_ascii_folded(c, &mut folded);
+    }
+    folded
+}
+
+/// Appends `c` to `out` as `ascii_fold` folds it.
+fn push_ascii_folded(c: char, out: &mut String) {
+    if c.is_ascii() {
+        out.push(c);
+        return;
+    }
+    let start = out.len();
+    out.extend(c.nfd().filter(|&d| !is_combining_mark(d)));
+    if !out[start..].is_ascii() {
+        out.truncate(start);
+        out.push(c);
+    }
+}
+
+/// Appends the search key of the romanization `s` to `out` in a single pass: each
+/// character is folded as by `ascii_fold` and lowercased, and each run of whitespace,
+/// including line breaks, becomes a single space, with none at the start or end.
+pub(crate) fn push_search_key(s: &str, out: &mut String) {
+    let start = out.len();
+    let mut folded = String::new();
+    let mut space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            space = out.len() > start;
+            continue;
+        }
+        if space {
+            out.push(' ');
+            space = false;
+        }
+        if c.is_ascii() {
+            out.push(c.to_ascii_lowercase());
+            continue;
+        }
+        folded.clear();
+        push_ascii_folded(c, &mut folded);
+        out.extend(folded.chars().flat_map(char::to_lowercase));
+    }
+}
+
+/// Uppercases the first letter of `s` and lowercases the letters after it.
+///
+/// Multi-letter expansions of a single uppercase character (e.g., `Θ` to `TH`)
+/// thereby become `Th` rather than all-caps.
+fn title_case(s: &str) -> String {
+    let mut titled = String::with_capacity(s.len());
+    let mut seen_letter = false;
+    for c in s.chars() {
+        if !c.is_alphabetic() {
+            titled.push(c);
+        } else if seen_letter {
+            titled.extend(c.to_lowercase());
+        } else {
+            titled.extend(c.to_uppercase());
+            seen_letter = true;
+        }
+    }
+    titled
+}
+
+/// Returns `true` if `c` is a ligature with a compatibility decomposition into letters.
+///
+/// These are the Latin digraphs `Ĳ` and `Ǆ` to `ǌ` and `Ǳ` to `ǳ`, and the Latin and
+/// Armenian ligatures of the Alphabetic Presentation Forms block (`ﬀ` to `ﬆ`, `ﬓ` to
+/// `ﬗ`). Ligatures without a decomposition, such as `æ` and `œ`, are letters in their own
+/// right and are romanized by the rules. So is the Armenian `և`, which is `ev`, whereas
+/// its decomposition `եւ` is romanized letter by letter (`yew` at the start of a word).
+fn is_ligature(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0132}' | '\u{0133}' | '\u{01C4}'..='\u{01CC}' | '\u{01F1}'..='\u{01F3}'
+            | '\u{FB00}'..='\u{FB06}' | '\u{FB13}'..='\u{FB17}'
+    )
+}
+
+/// Replaces each ligature in `s` with its compatibility decomposition (e.g., `ﬁ` to `fi`).
+///
+/// The decomposition of a digraph with a caron (e.g., `ǆ`) keeps the caron as a combining
+/// mark, which the `Normalization::Nfc` composes again.
+fn expand_ligatures(s: &str) -> String {
+    let mut expanded = String::with_capacity(s.len());
+    for c in s.chars() {
+        if is_ligature(c) {
+            decompose_compatible(c, |d| expanded.push(d));
+        } else {
+            expanded.push(c);
+        }
+    }
+    expanded
+}
+
+/// Returns `true` if `c` is a letter or sign of the Indic scripts of the block from
+/// Devanagari to Sinhala or of the Arabic script, whose glyphs a zero width joiner or
+/// non-joiner after them can select.
+fn is_joining_letter(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0600}'..='\u{06FF}'
+            | '\u{0750}'..='\u{077F}'
+            | '\u{08A0}'..='\u{08FF}'
+            | '\u{0900}'..='\u{0DFF}'
+            | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}'
+    )
+}
+
+/// Returns `true` if a zero width joiner in `s` follows an Indic or Arabic character.
+fn has_rendering_joiner(s: &str) -> bool {
+    s.chars()
+        .zip(s.chars().skip(1))
+        .any(|(c, next)| next == ZERO_WIDTH_JOINER && is_joining_letter(c))
+}
+
+/// Removes each zero width joiner in `s` that follows an Indic or Arabic character.
+///
+/// After a virama, a joiner selects the half form of a consonant instead of a conjunct
+/// (e.g., `"क्\u{200D}ष"`), and in Arabic it selects a joining form, so it changes the glyph but not
+/// the sound, and removing it lets the rules for the cluster apply. Joiners elsewhere,
+/// as in emoji sequences, and all zero width non-joiners, which separate the letters of
+/// a cluster or of a word (e.g., `"क्\u{200C}ष"`, or Persian `"می\u{200C}خواهم"`), are kept.
+fn remove_rendering_joiners(s: &str) -> String {
+    let mut removed = String::with_capacity(s.len());
+    let mut prev = None;
+    for c in s.chars() {
+        if !(c == ZERO_WIDTH_JOINER && prev.is_some_and(is_joining_letter)) {
+            removed.push(c);
+        }
+        prev = Some(c);
+    }
+    removed
+}
+
+/// Returns `true` if `c` is in the Halfwidth and Fullwidth Forms block.
+fn is_halfwidth_or_fullwidth_form(c: char) -> bool {
+    ('\u{FF00}'..='\u{FFEF}').contains(&c)
+}
+
+/// Maps each run of Halfwidth and Fullwidth Forms in `s` to its NFKC form.
+///
+/// Fullwidth ASCII (e.g., `Ａ`, `１`, `！`) becomes ASCII, and halfwidth katakana become
+/// fullwidth katakana, composed with a following halfwidth sound mark (`ｶﾞ` to `ガ`).
+/// Characters outside the block are kept unchanged.
+fn normalize_fullwidth(s: &str) -> String {
+    let mut normalized = String::with_capacity(s.len());
+    let mut run = String::new();
+    for c in s.chars() {
+        if is_halfwidth_or_fullwidth_form(c) {
+            run.push(c);
+            continue;
+        }
+        normalized.extend(run.nfkc());
+        run.clear();
+        normalized.push(c);
+    }
+    normalized.extend(run.nfkc());
+    normalized
+}
+
+/// Replaces the edges of every decimal digit as selected by `mode`.
+fn apply_numeric_mode(lat: &mut Lattice, mode: NumericMode) {
+    let digits: Vec<(usize, char)> = lat
+        .s_chars
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, c)| c.general_category() == GeneralCategory::DecimalNumber)
+        .collect();
+    for (i, c) in digits {
+        let txt = match mode {
+            NumericMode::AsciiDigits => decimal_digit_value(c).to_string(),
+            _ => c.to_string(),
+        };
+        let edge = Edge::new_regular(i, i + 1, txt, "num".to_string());
+        lat.replace_edges_in_span(i, i + 1, vec![edge]);
+    }
+    if mode == NumericMode::Passthrough {
+        // Separators of other scripts between digits (e.g., the ٬ of ١٬٠٠٠) are kept too.
+        let is_digit = |c: &char| c.general_category() == GeneralCategory::DecimalNumber;
+        let separators: Vec<(usize, char)> = (1..lat.s_chars.len().saturating_sub(1))
+            .map(|i| (i, lat.s_chars[i]))
+            .filter(|&(i, c)| {
+                !c.is_ascii()
+                    && c.general_category() == GeneralCategory::OtherPunctuation
+                    && is_digit(&lat.s_chars[i - 1])
+                    && is_digit(&lat.s_chars[i + 1])
+            })
+            .collect();
+        for (i, c) in separators {
+            let edge = Edge::new_regular(i, i + 1, c.to_string(), "num".to_string());
+            lat.replace_edges_in_span(i, i + 1, vec![edge]);
+        }
+    }
+}
+
+/// Returns the value of a decimal digit.
+///
+/// Unicode assigns the decimal digits of each script in runs of `0` to `9`, so the
+/// value is the offset of `c` from the start of its run of digits, modulo 10.
+fn decimal_digit_value(c: char) -> u32 {
+    let run_offset = (1..)
+        .take_while(|&offset| {
+            char::from_u32(c as u32 - offset)
+                .is_some_and(|prev| prev.general_category() == GeneralCategory::DecimalNumber)
+        })
+        .count() as u32;
+    run_offset % 10
+}
@@ -0,0 +1,89 @@
+//! Tags the numeric edges of ordinal numbers (e.g., `1st`, `2º`, 第三) with the type
+//! `ordinal`.
+//!
+//! Only the type of the numeric edges changes, so the romanization itself is unaffected.
+//! The markers recognized are listed at `Edge::is_ordinal`.
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// Ordinal indicators that follow a number under any `lcode`.
+const INDICATORS: [&str; 5] = ["º", "ª", "ᵉ", ".º", ".ª"];
+
+impl Lattice<'_> {
+    /// Sets the type of every numeric edge that is marked as an ordinal to `ordinal`.
+    pub fn tag_ordinals(&mut self) {
+        let ordinals: Vec<Edge> = self
+            .edge_lattice
+            .values()
+            .flatten()
+            .filter(|edge| self.is_ordinal(edge))
+            .cloned()
+            .collect();
+
+        for edge in ordinals {
+            if let Some(edges) = self.edge_lattice.get_mut(&(edge.start(), edge.end()))
+                && let Some(mut ordinal) = edges.take(&edge)
+            {
+                ordinal.get_data_mut().r#type = "ordinal".to_string();
+                edges.insert(ordinal);
+            }
+        }
+    }
+
+    fn is_ordinal(&self, edge: &Edge) -> bool {
+        let Some(value) = edge
+            .whole_value()
+            .filter(|value| value.fract() == 0.0 && *value >= 0.0)
+        else {
+            return false;
+        };
+        if edge.get_num_data().is_some_and(|d| d.fraction.is_some()) {
+            return false;
+        }
+
+        let before = self.s_chars[..edge.start()].last();
+        let after = &self.s_chars[edge.end()..];
+        let lcode = self.lcode.as_deref();
+
+        if before == Some(&'第') || (lcode == Some("kor") && before == Some(&'제')) {
+            return true;
+        }
+        if INDICATORS
+            .iter()
+            .any(|indicator| prefix_len(after, indicator).is_some())
+            || (lcode == Some("jpn") && prefix_len(after, "番目").is_some())
+        {
+            return true;
+        }
+        // Word suffixes, which must end the word.
+        let suffixes: &[&str] = match lcode {
+            None | Some("eng") if edge.orig_txt().chars().all(|c| c.is_ascii_digit()) => {
+                &[english_suffix(value as u64)]
+            }
+            Some("fra") => &["er", "re", "ème", "e"],
+            _ => &[],
+        };
+        suffixes.iter().any(|suffix| {
+            prefix_len(after, suffix)
+                .is_some_and(|len| after.get(len).is_none_or(|c| !c.is_alphanumeric()))
+        })
+    }
+}
+
+/// Returns the length in characters of `prefix` if `chars` starts with it.
+fn prefix_len(chars: &[char], prefix: &str) -> Option<usize> {
+    let len = prefix.chars().count();
+    (chars.len() >= len && prefix.chars().zip(chars).all(|(a, &b)| a == b)).then_some(len)
+}
+
+/// Returns the English ordinal suffix of `n` (e.g., `st` for 21 and `th` for 11).
+fn english_suffix(n: u64) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
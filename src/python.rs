@@ -4,10 +4,15 @@
 //! allowing Python users to access the fast Rust romanization library.
 
 use pyo3::prelude::*;
-use pyo3::exceptions::PyValueError;
-use std::io::{BufReader, Cursor};
+use pyo3::exceptions::{PyIndexError, PyOSError, PyValueError};
+use pyo3::types::{PyBytes, PyDict, PyIterator};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Write};
+use std::path::{Path, PathBuf};
 
-use crate::{Uroman as RustUroman, RomFormat, Edge as RustEdge};
+use crate::{Uroman as RustUroman, RomFormat, RomanizationError, Edge as RustEdge, LatticeNode as RustLatticeNode};
 
 /// Python wrapper for the Uroman struct.
 ///
@@ -46,11 +51,13 @@ impl PyUroman {
     /// Args:
     ///     text (str): The text to romanize.
     ///     lcode (str, optional): ISO 639-3 language code (e.g., 'jpn', 'ara', 'zho').
-    ///     format (str, optional): Output format - 'str', 'edges', 'alts', or 'lattice'.
+    ///     format (str, optional): Output format - 'str', 'edges', 'alts', 'lattice',
+    ///                            'lattice_nodes', 'json', or 'jsonl'.
     ///                            Defaults to 'str'.
     ///
     /// Returns:
-    ///     str or list: Romanized text as a string (for 'str' format) or
+    ///     str or list: Romanized text as a string (for 'str', 'json' and 'jsonl' formats),
+    ///                  a list of LatticeNode objects (for 'lattice_nodes' format) or
     ///                  a list of Edge objects (for other formats).
     ///
     /// Example:
@@ -71,8 +78,11 @@ impl PyUroman {
             "edges" => RomFormat::Edges,
             "alts" => RomFormat::Alts,
             "lattice" => RomFormat::Lattice,
+            "lattice_nodes" => RomFormat::LatticeNodes,
+            "json" => RomFormat::Json,
+            "jsonl" => RomFormat::Jsonl,
             _ => return Err(PyValueError::new_err(
-                "Invalid format. Must be 'str', 'edges', 'alts', or 'lattice'."
+                "Invalid format. Must be 'str', 'edges', 'alts', 'lattice', 'lattice_nodes', 'json', or 'jsonl'."
             )),
         };
 
@@ -82,8 +92,11 @@ impl PyUroman {
             match result {
                 crate::RomanizationResult::Str(s) => Ok(s.into_pyobject(py).unwrap().into_any().unbind()),
                 crate::RomanizationResult::Edges(edges) => {
-                    let py_edges: Vec<PyEdge> = edges.into_iter().map(PyEdge::from).collect();
-                    Ok(py_edges.into_pyobject(py).unwrap().into_any().unbind())
+                    Ok(py_edges(&edges).into_pyobject(py).unwrap().into_any().unbind())
+                }
+                crate::RomanizationResult::Lattice(nodes) => {
+                    let py_nodes: Vec<PyLatticeNode> = nodes.into_iter().map(PyLatticeNode::from).collect();
+                    Ok(py_nodes.into_pyobject(py).unwrap().into_any().unbind())
                 }
             }
         })
@@ -110,8 +123,11 @@ impl PyUroman {
             "edges" => RomFormat::Edges,
             "alts" => RomFormat::Alts,
             "lattice" => RomFormat::Lattice,
+            "lattice_nodes" => RomFormat::LatticeNodes,
+            "json" => RomFormat::Json,
+            "jsonl" => RomFormat::Jsonl,
             _ => return Err(PyValueError::new_err(
-                "Invalid format. Must be 'str', 'edges', 'alts', or 'lattice'."
+                "Invalid format. Must be 'str', 'edges', 'alts', 'lattice', 'lattice_nodes', 'json', or 'jsonl'."
             )),
         };
 
@@ -121,8 +137,11 @@ impl PyUroman {
             match result {
                 crate::RomanizationResult::Str(s) => Ok(s.into_pyobject(py).unwrap().into_any().unbind()),
                 crate::RomanizationResult::Edges(edges) => {
-                    let py_edges: Vec<PyEdge> = edges.into_iter().map(PyEdge::from).collect();
-                    Ok(py_edges.into_pyobject(py).unwrap().into_any().unbind())
+                    Ok(py_edges(&edges).into_pyobject(py).unwrap().into_any().unbind())
+                }
+                crate::RomanizationResult::Lattice(nodes) => {
+                    let py_nodes: Vec<PyLatticeNode> = nodes.into_iter().map(PyLatticeNode::from).collect();
+                    Ok(py_nodes.into_pyobject(py).unwrap().into_any().unbind())
                 }
             }
         })
@@ -151,8 +170,11 @@ impl PyUroman {
             "edges" => RomFormat::Edges,
             "alts" => RomFormat::Alts,
             "lattice" => RomFormat::Lattice,
+            "lattice_nodes" => RomFormat::LatticeNodes,
+            "json" => RomFormat::Json,
+            "jsonl" => RomFormat::Jsonl,
             _ => return Err(PyValueError::new_err(
-                "Invalid format. Must be 'str', 'edges', 'alts', or 'lattice'."
+                "Invalid format. Must be 'str', 'edges', 'alts', 'lattice', 'lattice_nodes', 'json', or 'jsonl'."
             )),
         };
 
@@ -167,17 +189,323 @@ impl PyUroman {
             .map_err(|e| PyValueError::new_err(format!("UTF-8 conversion error: {}", e)))
     }
 
+    /// Romanizes a file line by line and writes the result to another file.
+    ///
+    /// The files are streamed, so they are never loaded into memory as a whole, and the
+    /// GIL is released meanwhile.
+    ///
+    /// Args:
+    ///     input_path (str or os.PathLike): The file to romanize, or '-' for stdin.
+    ///     output_path (str or os.PathLike): The file to write, or '-' for stdout.
+    ///     lcode (str, optional): ISO 639-3 language code.
+    ///     format (str, optional): Output format. Defaults to 'str'.
+    ///     decode_unicode (bool, optional): Whether to decode Unicode escapes. Defaults to False.
+    ///
+    /// Raises:
+    ///     OSError: If a file cannot be opened, read or written.
+    #[pyo3(signature = (input_path, output_path, lcode=None, format="str", decode_unicode=false))]
+    pub fn romanize_file(
+        &self,
+        py: Python<'_>,
+        input_path: PathBuf,
+        output_path: PathBuf,
+        lcode: Option<&str>,
+        format: &str,
+        decode_unicode: bool,
+    ) -> PyResult<()> {
+        let rom_format = match format {
+            "str" => RomFormat::Str,
+            "edges" => RomFormat::Edges,
+            "alts" => RomFormat::Alts,
+            "lattice" => RomFormat::Lattice,
+            "lattice_nodes" => RomFormat::LatticeNodes,
+            "json" => RomFormat::Json,
+            "jsonl" => RomFormat::Jsonl,
+            _ => return Err(PyValueError::new_err(
+                "Invalid format. Must be 'str', 'edges', 'alts', 'lattice', 'lattice_nodes', 'json', or 'jsonl'."
+            )),
+        };
+
+        py.allow_threads(|| {
+            let is_stdio = |path: &Path| path == Path::new("-");
+            let reader: Box<dyn BufRead> = if is_stdio(&input_path) {
+                Box::new(io::stdin().lock())
+            } else {
+                let file = File::open(&input_path).map_err(|e| {
+                    PyOSError::new_err(format!("Cannot open '{}': {}", input_path.display(), e))
+                })?;
+                Box::new(BufReader::new(file))
+            };
+            let mut writer: Box<dyn Write> = if is_stdio(&output_path) {
+                Box::new(io::stdout().lock())
+            } else {
+                let file = File::create(&output_path).map_err(|e| {
+                    PyOSError::new_err(format!("Cannot create '{}': {}", output_path.display(), e))
+                })?;
+                Box::new(BufWriter::new(file))
+            };
+
+            let message = |e: &dyn std::fmt::Display| {
+                format!(
+                    "Cannot romanize '{}' into '{}': {}",
+                    input_path.display(),
+                    output_path.display(),
+                    e
+                )
+            };
+            match self
+                .inner
+                .romanize_file(reader, &mut writer, lcode, rom_format, None, decode_unicode, true)
+            {
+                Ok(()) => writer.flush().map_err(|e| PyOSError::new_err(message(&e))),
+                Err(RomanizationError::Io(e)) => Err(PyOSError::new_err(message(&e))),
+                Err(e) => Err(PyValueError::new_err(message(&e))),
+            }
+        })
+    }
+
+    /// Lazily romanizes lines, yielding each romanized line as it is pulled.
+    ///
+    /// Lines are handled like in `romanize_text`, including `::lcode` directives, with a
+    /// trailing line ending removed. Only one line is held in memory at a time, and the
+    /// GIL is released while it is romanized.
+    ///
+    /// Args:
+    ///     lines (Iterable[str]): The lines to romanize, e.g. an open text file.
+    ///     lcode (str, optional): ISO 639-3 language code.
+    ///
+    /// Returns:
+    ///     Iterator[str]: The romanized lines, in the order of `lines`.
+    ///
+    /// Raises:
+    ///     TypeError: When a line that is not a string is pulled.
+    ///
+    /// Example:
+    ///     >>> uroman = Uroman()
+    ///     >>> with open("corpus.txt", encoding="utf-8") as f:
+    ///     ...     for line in uroman.romanize_iter(f, lcode="ukr"):
+    ///     ...         print(line)
+    #[pyo3(signature = (lines, lcode=None))]
+    pub fn romanize_iter(
+        &self,
+        lines: &Bound<'_, PyAny>,
+        lcode: Option<String>,
+    ) -> PyResult<PyRomanizedLines> {
+        Ok(PyRomanizedLines {
+            uroman: self.inner.clone(),
+            lines: lines.try_iter()?.unbind(),
+            lcode,
+        })
+    }
+
+    /// Romanizes many strings, in parallel when the library is built with rayon.
+    ///
+    /// The GIL is released while the strings are romanized, so that other Python
+    /// threads can run meanwhile.
+    ///
+    /// Args:
+    ///     texts (list[str]): The texts to romanize.
+    ///     lcode (str, optional): ISO 639-3 language code, applied to every text.
+    ///
+    /// Returns:
+    ///     list[str]: The romanized texts, in the order of `texts`.
+    ///
+    /// Example:
+    ///     >>> uroman = Uroman()
+    ///     >>> uroman.romanize_batch(["Київ", "Львів"], lcode="ukr")
+    ///     ['Kyiv', 'Lviv']
+    #[pyo3(signature = (texts, lcode=None))]
+    pub fn romanize_batch(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+        lcode: Option<&str>,
+    ) -> Vec<String> {
+        py.allow_threads(|| {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            self.inner.romanize_batch(&texts, lcode)
+        })
+    }
+
+    /// Romanizes text and aligns the output with the source.
+    ///
+    /// Each span pairs a range of the source with the range of the output it produced.
+    /// The spans follow the output, so both ranges are increasing and together cover the
+    /// source and the output. A source range may map to an empty output range (e.g., for
+    /// dropped combining marks).
+    ///
+    /// Source ranges refer to `text` even if the instance normalizes its input: a change
+    /// made by the normalization, such as a composed accent, is part of the span of the
+    /// characters it applies to.
+    ///
+    /// Offsets are UTF-8 byte offsets by default, as in Rust. Python strings are indexed
+    /// by code point, so pass `codepoints=True` to slice `text` and the output with them.
+    ///
+    /// Args:
+    ///     text (str): The text to romanize.
+    ///     lcode (str, optional): ISO 639-3 language code.
+    ///     codepoints (bool, optional): Return code point offsets instead of byte offsets.
+    ///         Defaults to False.
+    ///
+    /// Returns:
+    ///     tuple[str, list[tuple[int, int, int, int]]]: The romanized text and the spans,
+    ///     as `(src_start, src_end, out_start, out_end)`.
+    ///
+    /// Example:
+    ///     >>> uroman = Uroman()
+    ///     >>> uroman.romanize_with_offsets("Ж北", codepoints=True)
+    ///     ('Zhbei', [(0, 1, 0, 2), (1, 2, 2, 5)])
+    #[pyo3(signature = (text, lcode=None, codepoints=false))]
+    pub fn romanize_with_offsets(
+        &self,
+        py: Python<'_>,
+        text: &str,
+        lcode: Option<&str>,
+        codepoints: bool,
+    ) -> (String, Vec<(usize, usize, usize, usize)>) {
+        py.allow_threads(|| {
+            let (romanized, alignment) = self.inner.romanize_with_text_alignment(text, lcode);
+            let spans = if codepoints {
+                let source_offsets = codepoint_offsets(text);
+                let output_offsets = codepoint_offsets(&romanized);
+                alignment
+                    .into_iter()
+                    .map(|(source, output)| {
+                        (
+                            source_offsets[source.start],
+                            source_offsets[source.end],
+                            output_offsets[output.start],
+                            output_offsets[output.end],
+                        )
+                    })
+                    .collect()
+            } else {
+                alignment
+                    .into_iter()
+                    .map(|(source, output)| (source.start, source.end, output.start, output.end))
+                    .collect()
+            };
+            (romanized, spans)
+        })
+    }
+
+    /// Lists the languages supported by this instance, sorted by ISO 639-3 code.
+    ///
+    /// For an instance trimmed to some languages, only those languages are listed.
+    ///
+    /// Returns:
+    ///     list[dict]: One dict per language, with its `code` (str), its English `name`
+    ///     (str) and the `scripts` (list[str]) with rules specific to it.
+    ///
+    /// Example:
+    ///     >>> uroman = Uroman()
+    ///     >>> [lang["name"] for lang in uroman.supported_languages() if lang["code"] == "ukr"]
+    ///     ['Ukrainian']
+    pub fn supported_languages<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner
+            .supported_languages()
+            .into_iter()
+            .map(|language| {
+                let dict = PyDict::new(py);
+                dict.set_item("code", language.code)?;
+                dict.set_item("name", language.name)?;
+                let scripts: Vec<&str> = language.scripts.iter().map(|script| script.name()).collect();
+                dict.set_item("scripts", scripts)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Checks whether a language is supported by this instance.
+    ///
+    /// Args:
+    ///     code (str): ISO 639-3 language code.
+    ///
+    /// Returns:
+    ///     bool: True if `code` is a known code that the instance has not been trimmed of.
+    pub fn is_language_supported(&self, code: &str) -> bool {
+        self.inner.is_language_supported(code)
+    }
+
+    /// Serializes the compiled romanization tables and options of this instance.
+    ///
+    /// Returns:
+    ///     bytes: Data that `Uroman.from_compiled_bytes` loads without parsing the rules again.
+    pub fn to_compiled_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner.to_compiled_bytes())
+    }
+
+    /// Loads an instance from data produced by `Uroman.to_compiled_bytes`.
+    ///
+    /// Args:
+    ///     data (bytes): The compiled data.
+    ///
+    /// Returns:
+    ///     Uroman: The loaded instance.
+    ///
+    /// Raises:
+    ///     ValueError: If the data is invalid or was produced by another version.
+    #[staticmethod]
+    pub fn from_compiled_bytes(data: &[u8]) -> PyResult<Self> {
+        RustUroman::from_compiled_bytes(data)
+            .map(|inner| Self { inner })
+            .map_err(|e| PyValueError::new_err(format!("Invalid compiled data: {}", e)))
+    }
+
+    /// Pickles the instance as its compiled tables, so that unpickling it, e.g. in the
+    /// workers of a `multiprocessing.Pool`, does not parse the rules again.
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        let from_compiled_bytes = py.get_type::<Self>().getattr("from_compiled_bytes")?;
+        Ok((from_compiled_bytes, (self.to_compiled_bytes(py),)))
+    }
+
     /// Returns a string representation of the Uroman instance.
     fn __repr__(&self) -> String {
         "Uroman()".to_string()
     }
 }
 
+/// Iterator over romanized lines, returned by `Uroman.romanize_iter`.
+#[pyclass]
+pub struct PyRomanizedLines {
+    uroman: RustUroman,
+    lines: Py<PyIterator>,
+    lcode: Option<String>,
+}
+
+#[pymethods]
+impl PyRomanizedLines {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Pulls the next line and returns its romanization.
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        let Some(line) = self.lines.bind(py).clone().next() else {
+            return Ok(None);
+        };
+        let line: String = line?.extract()?;
+
+        py.allow_threads(|| {
+            self.uroman
+                .romanize_file_line(
+                    crate::trim_line_ending(&line),
+                    self.lcode.as_deref(),
+                    RomFormat::Str,
+                    false,
+                )
+                .map(Some)
+                .map_err(|e| PyValueError::new_err(format!("Romanization error: {}", e)))
+        })
+    }
+}
+
 /// Python wrapper for the Edge struct.
 ///
-/// Represents a romanization edge with position and text information.
+/// Represents a romanization edge with position and text information. Edges unpack
+/// like a `(start, end, text)` tuple and can be compared and put in sets.
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct PyEdge {
     #[pyo3(get)]
     pub start: usize,
@@ -193,6 +521,26 @@ pub struct PyEdge {
     pub value: Option<f64>,
     #[pyo3(get)]
     pub orig_text: Option<String>,
+    #[pyo3(get)]
+    pub alternatives: Vec<String>,
+    #[pyo3(get)]
+    pub score: f32,
+    /// The name of the script of the source characters (e.g., `Cyrillic` or `CJK`), if known.
+    #[pyo3(get)]
+    pub script: Option<String>,
+}
+
+/// Converts edges to `PyEdge`s, listing each alternative edge in the `alternatives`
+/// of the other edges with the same span rather than on its own.
+fn py_edges(edges: &[RustEdge]) -> Vec<PyEdge> {
+    edges
+        .iter()
+        .filter(|edge| !edge.is_alternative())
+        .map(|edge| PyEdge {
+            alternatives: edge.alternatives(edges).into_iter().map(String::from).collect(),
+            ..PyEdge::from(edge.clone())
+        })
+        .collect()
 }
 
 impl From<RustEdge> for PyEdge {
@@ -210,6 +558,9 @@ impl From<RustEdge> for PyEdge {
             } else {
                 None
             },
+            alternatives: Vec::new(),
+            score: edge.score(),
+            script: edge.source_script().map(|script| script.name().to_string()),
         }
     }
 }
@@ -228,6 +579,82 @@ impl PyEdge {
     fn __str__(&self) -> String {
         self.text.clone()
     }
+
+    /// Returns 3, the length of the `(start, end, text)` tuple that the edge unpacks to.
+    fn __len__(&self) -> usize {
+        3
+    }
+
+    /// Returns `start`, `end` or `text` for the index 0, 1 or 2 (or -3, -2 or -1).
+    fn __getitem__(&self, py: Python<'_>, index: isize) -> PyResult<PyObject> {
+        match index {
+            0 | -3 => Ok(self.start.into_pyobject(py)?.into_any().unbind()),
+            1 | -2 => Ok(self.end.into_pyobject(py)?.into_any().unbind()),
+            2 | -1 => Ok(self.text.as_str().into_pyobject(py)?.into_any().unbind()),
+            _ => Err(PyIndexError::new_err("Edge index out of range")),
+        }
+    }
+
+    /// Iterates over `start`, `end` and `text`, so that `start, end, text = edge` works.
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        (self.start, self.end, self.text.as_str()).into_pyobject(py)?.try_iter()
+    }
+
+    /// Returns `True` if all attributes of the edges are equal.
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    /// Returns a hash of all attributes, consistent with `__eq__`.
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.start, self.end, &self.text, &self.edge_type, self.is_numeric).hash(&mut hasher);
+        (self.value.map(f64::to_bits), &self.orig_text, &self.alternatives).hash(&mut hasher);
+        (self.score.to_bits(), &self.script).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Python wrapper for a node of a romanization lattice.
+///
+/// Represents a position in the text with the competing edges that start there.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyLatticeNode {
+    #[pyo3(get)]
+    pub position: usize,
+    #[pyo3(get)]
+    pub edges: Vec<PyEdge>,
+}
+
+impl From<RustLatticeNode> for PyLatticeNode {
+    fn from(node: RustLatticeNode) -> Self {
+        PyLatticeNode {
+            position: node.position,
+            edges: py_edges(&node.edges),
+        }
+    }
+}
+
+#[pymethods]
+impl PyLatticeNode {
+    /// Returns a string representation of the LatticeNode.
+    fn __repr__(&self) -> String {
+        format!("LatticeNode(position={}, edges={})", self.position, self.edges.len())
+    }
+}
+
+/// Returns the code point offset of each byte offset of `s` up to `s.len()`, with the
+/// offsets within a character mapped to its start.
+fn codepoint_offsets(s: &str) -> Vec<usize> {
+    let mut offsets = vec![0; s.len() + 1];
+    let mut codepoint = 0;
+    for (i, c) in s.char_indices() {
+        offsets[i..i + c.len_utf8()].fill(codepoint);
+        codepoint += 1;
+    }
+    offsets[s.len()] = codepoint;
+    offsets
 }
 
 /// Python module for uroman-rs.
@@ -239,10 +666,13 @@ fn uroman_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Add classes with clean names
     m.add_class::<PyUroman>()?;
     m.add_class::<PyEdge>()?;
+    m.add_class::<PyLatticeNode>()?;
+    m.add_class::<PyRomanizedLines>()?;
 
     // Add aliases for better API
     m.add("Uroman", m.getattr("PyUroman")?)?;
     m.add("Edge", m.getattr("PyEdge")?)?;
+    m.add("LatticeNode", m.getattr("PyLatticeNode")?)?;
 
     // Add module-level convenience function
     m.add_function(wrap_pyfunction!(romanize, m)?)?;
@@ -0,0 +1,230 @@
+//! Reversible romanization, which `deromanize` maps back to the original script.
+//!
+//! Each language code selects one reversible scheme: Extended Wylie for Tibetan under
+//! `bod`, and ISO 9 for Cyrillic under any other code. The characters of other scripts
+//! are kept as they are. A run of kept characters that `deromanize` would read as a
+//! romanization, such as a Latin word, is enclosed in backslashes (e.g., `\Kyiv\`), and a
+//! backslash of the source is doubled, so that the text is read back unchanged.
+
+use crate::cyrillic::{CyrillicScheme, ISO_9};
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+use crate::tibetan::{is_syllable_char, tibetan_syllable, wylie_syllable};
+
+const TSHEG: char = '\u{0F0B}';
+const SHAD: char = '\u{0F0D}';
+const ESCAPE: char = '\\';
+
+/// The reversible scheme of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReversibleScheme {
+    Iso9,
+    Wylie,
+}
+
+impl ReversibleScheme {
+    fn for_lcode(lcode: Option<&str>) -> Self {
+        match lcode {
+            Some("bod") => ReversibleScheme::Wylie,
+            _ => ReversibleScheme::Iso9,
+        }
+    }
+
+    /// Returns `true` if `deromanize` may read `c` as part of a romanization.
+    fn reads(self, c: char) -> bool {
+        match self {
+            ReversibleScheme::Iso9 => c
+                .to_lowercase()
+                .any(|lower| ISO_9.iter().any(|(_, rom)| rom.contains(lower))),
+            ReversibleScheme::Wylie => is_wylie_char(c) || matches!(c, ' ' | '/'),
+        }
+    }
+}
+
+/// Returns `true` if `c` can be part of the Wylie of a syllable.
+fn is_wylie_char(c: char) -> bool {
+    c.is_ascii_alphabetic() || "'.+-~`?".contains(c)
+}
+
+impl Lattice<'_> {
+    /// Romanizes the letters covered by the reversible scheme of `lcode` and replaces the
+    /// edges of every other character with a `verbatim` edge, which escapes the runs of
+    /// characters that `deromanize` would read as a romanization.
+    pub fn apply_reversible(&mut self, lcode: Option<&str>) {
+        let scheme = ReversibleScheme::for_lcode(lcode);
+        match scheme {
+            ReversibleScheme::Iso9 => {
+                self.apply_cyrillic_scheme(CyrillicScheme::Iso9);
+                self.retain_edges(|edge| edge.r#type() == "rom iso9");
+            }
+            ReversibleScheme::Wylie => {
+                self.retain_edges(|_| false);
+                self.add_reversible_wylie_edges();
+            }
+        }
+
+        let mut covered = vec![false; self.s_chars.len()];
+        for &(start, end) in self.edge_lattice.keys() {
+            covered[start..end].fill(true);
+        }
+        let mut start = 0;
+        while start < self.s_chars.len() {
+            let c = self.s_chars[start];
+            let end = if covered[start] || !scheme.reads(c) {
+                start + 1
+            } else {
+                (start..self.s_chars.len())
+                    .find(|&i| covered[i] || !scheme.reads(self.s_chars[i]))
+                    .unwrap_or(self.s_chars.len())
+            };
+            if !covered[start] {
+                let txt = match c {
+                    ESCAPE => format!("{ESCAPE}{ESCAPE}"),
+                    _ if end > start + 1 || scheme.reads(c) => {
+                        let run: String = self.s_chars[start..end].iter().collect();
+                        format!("{ESCAPE}{run}{ESCAPE}")
+                    }
+                    _ => c.to_string(),
+                };
+                self.add_edge(Edge::new_regular(start, end, txt, "verbatim".to_string()));
+            }
+            start = end;
+        }
+    }
+
+    /// Adds the Wylie edges of the Tibetan syllables that `tibetan_syllable` reads back
+    /// unchanged, and of the tsheg and shad marks, which are written ` ` and `/`.
+    fn add_reversible_wylie_edges(&mut self) {
+        let mut edges = Vec::new();
+        let mut start = 0;
+        while start < self.s_chars.len() {
+            let c = self.s_chars[start];
+            if is_syllable_char(c) {
+                let end = (start..self.s_chars.len())
+                    .find(|&i| !is_syllable_char(self.s_chars[i]))
+                    .unwrap_or(self.s_chars.len());
+                let syllable = &self.s_chars[start..end];
+                let wylie = wylie_syllable(syllable);
+                if tibetan_syllable(&wylie).is_some_and(|s| s.chars().eq(syllable.iter().copied()))
+                {
+                    edges.push(Edge::new_regular(
+                        start,
+                        end,
+                        wylie,
+                        "rom wylie".to_string(),
+                    ));
+                }
+                start = end;
+                continue;
+            }
+            let txt = match c {
+                TSHEG => " ",
+                SHAD => "/",
+                _ => {
+                    start += 1;
+                    continue;
+                }
+            };
+            edges.push(Edge::new_regular(
+                start,
+                start + 1,
+                txt.to_string(),
+                "rom wylie".to_string(),
+            ));
+            start += 1;
+        }
+        for edge in edges {
+            self.add_edge(edge);
+        }
+    }
+}
+
+/// Maps a romanization of the reversible mode under `lcode` back to the original script.
+///
+/// This inverts `apply_reversible`: escaped runs are copied without their backslashes,
+/// and the rest is read in the reversible scheme of `lcode`.
+pub(crate) fn deromanize(s: &str, lcode: Option<&str>) -> String {
+    let scheme = ReversibleScheme::for_lcode(lcode);
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find(ESCAPE) {
+        deromanize_into(scheme, &rest[..i], &mut out);
+        let escaped = &rest[i + 1..];
+        rest = if let Some(after) = escaped.strip_prefix(ESCAPE) {
+            out.push(ESCAPE);
+            after
+        } else if let Some(end) = escaped.find(ESCAPE) {
+            out.push_str(&escaped[..end]);
+            &escaped[end + 1..]
+        } else {
+            // A backslash that the reversible mode did not write.
+            out.push(ESCAPE);
+            escaped
+        };
+    }
+    deromanize_into(scheme, rest, &mut out);
+    out
+}
+
+/// Appends the text that `scheme` romanized as `s`, which has no escapes, to `out`.
+fn deromanize_into(scheme: ReversibleScheme, s: &str, out: &mut String) {
+    match scheme {
+        ReversibleScheme::Iso9 => deromanize_iso9(s, out),
+        ReversibleScheme::Wylie => deromanize_wylie(s, out),
+    }
+}
+
+/// Maps the ISO 9 romanization of Cyrillic in `s` back to Cyrillic.
+fn deromanize_iso9(s: &str, out: &mut String) {
+    // Longest romanizations first, so that `l̂` is not read as `l` and a combining mark.
+    let mut table = ISO_9.to_vec();
+    table.sort_by_key(|&(_, rom)| std::cmp::Reverse(rom.chars().count()));
+
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let found = table.iter().find_map(|&(letter, rom)| {
+            let len = rom.chars().count();
+            let candidate = chars.get(i..i + len)?;
+            let lower: String = candidate.iter().flat_map(|c| c.to_lowercase()).collect();
+            (lower == rom).then_some((letter, len))
+        });
+        match found {
+            Some((letter, len)) => {
+                if chars[i].is_uppercase() {
+                    out.extend(letter.to_uppercase());
+                } else {
+                    out.push(letter);
+                }
+                i += len;
+            }
+            None => {
+                out.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Maps the Wylie of Tibetan in `s` back to Tibetan, with each space as a tsheg.
+fn deromanize_wylie(s: &str, out: &mut String) {
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if is_wylie_char(c) {
+            let end = rest.find(|c| !is_wylie_char(c)).unwrap_or(rest.len());
+            let wylie = &rest[..end];
+            match tibetan_syllable(wylie) {
+                Some(syllable) => out.push_str(&syllable),
+                None => out.push_str(wylie),
+            }
+            rest = &rest[end..];
+            continue;
+        }
+        out.push(match c {
+            ' ' => TSHEG,
+            '/' => SHAD,
+            c => c,
+        });
+        rest = &rest[c.len_utf8()..];
+    }
+}
@@ -1,16 +1,18 @@
 //! Defines the `RomRule` struct and related parsing logic.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
     core::UromanInner, utils::{
-        dequote_string, has_value_in_double_colon_del_list, slot_value_in_double_colon_del_list,
+        dequote_string, has_value_in_double_colon_del_list, reorder_combining_marks,
+        slot_value_in_double_colon_del_list,
     }
 };
 
 #[allow(unused)]
 /// Represents a single romanization rule parsed from the data files.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct RomRule {
     pub s: String,
     pub t: Option<String>,
@@ -76,7 +78,7 @@ impl RomRule {
         line: &str,
         provenance: &str,
         file_format: &str,
-        uroman: &mut UromanInner,
+        uroman: &UromanInner,
     ) -> Option<Self> {
         let (s, t) = if file_format == "u2r" {
             let u_str = slot_value_in_double_colon_del_list(line, "u")?;
@@ -87,7 +89,7 @@ impl RomRule {
             (s, t)
         } else {
             let s = slot_value_in_double_colon_del_list(line, "s")
-                .map(|s_val| dequote_string(s_val).to_string())?;
+                .map(|s_val| reorder_combining_marks(dequote_string(s_val)))?;
             let t = slot_value_in_double_colon_del_list(line, "t")
                 .map(|s_val| dequote_string(s_val).to_string());
             (s, t)
@@ -175,5 +177,61 @@ impl RomRule {
     }
 }
 
+impl RomRule {
+    /// Formats the rule as a line of `romanization-table.txt`, which `from_line` parses back.
+    ///
+    /// Values that would be trimmed or dequoted when parsed are quoted.
+    pub fn to_line(&self) -> String {
+        let mut line = format!("::s {}", quote_value(&self.s));
+        if let Some(t) = &self.t {
+            line.push_str(&format!(" ::t {}", quote_value(t)));
+        }
+        if !self.t_alts.is_empty() {
+            let t_alts: Vec<String> = self.t_alts.iter().map(|t| quote_value(t)).collect();
+            line.push_str(&format!(" ::t-alt {}", t_alts.join(",")));
+        }
+        if let Some(t) = &self.t_at_end_of_syllable {
+            line.push_str(&format!(" ::t-end-of-syllable {}", quote_value(t)));
+        }
+        if !self.lcodes.is_empty() {
+            line.push_str(&format!(" ::lcode {}", self.lcodes.join(",")));
+        }
+        match &self.num {
+            Some(crate::core::Value::Int(num)) => line.push_str(&format!(" ::num {num}")),
+            Some(crate::core::Value::Float(num)) => line.push_str(&format!(" ::num {num}")),
+            Some(crate::core::Value::String(num)) => line.push_str(&format!(" ::num {num}")),
+            Some(crate::core::Value::Array(_)) | None => {}
+        }
+
+        let flags = [
+            (self.use_only_at_start_of_word, "use-only-at-start-of-word"),
+            (self.dont_use_at_start_of_word, "dont-use-at-start-of-word"),
+            (self.use_only_at_end_of_word, "use-only-at-end-of-word"),
+            (self.dont_use_at_end_of_word, "dont-use-at-end-of-word"),
+            (self.use_only_for_whole_word, "use-only-for-whole-word"),
+            (self.is_minus_sign, "is-minus-sign"),
+            (self.is_plus_sign, "is-plus-sign"),
+            (self.is_decimal_point, "is-decimal-point"),
+            (self.fraction_connector, "fraction-connector"),
+            (self.percentage_marker, "percentage-marker"),
+            (self.int_frac_connector, "int-frac-connector"),
+            (self.is_large_power, "is-large-power"),
+        ];
+        for (_, flag) in flags.iter().filter(|(is_set, _)| *is_set) {
+            line.push_str(&format!(" ::{flag}"));
+        }
+        line
+    }
+}
+
+/// Quotes `value` if it is empty, or if `from_line` would otherwise trim or dequote it.
+fn quote_value(value: &str) -> String {
+    if value.is_empty() || value.trim() != value || dequote_string(value) != value {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
 /// A collection of romanization rules, typically grouped by the source string `s`.
 pub type RomRules = HashMap<String, Vec<RomRule>>;
@@ -0,0 +1,134 @@
+//! Recognizes Roman numerals (e.g., `IV`, `MCMLXXXIV`) as numbers.
+//!
+//! Only words written entirely in the uppercase letters `IVXLCDM` that form a numeral
+//! in standard subtractive notation are recognized, so that `Mix`, `IIII` and `IC` are
+//! left alone. A lone `I` is read as the pronoun, and uppercase words and abbreviations
+//! that happen to be numerals, such as `MIX` and `DC`, are read as numbers only after a
+//! cue word such as `Chapter` (e.g., `Chapter DC`).
+
+use crate::edge::{Edge, NumDataUpdates};
+use crate::lattice::Lattice;
+
+const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Uppercase words and abbreviations that are valid numerals but rarely meant as such.
+const COMMON_WORDS: [&str; 14] = [
+    "CC", "CD", "CM", "CV", "DC", "DI", "DIV", "DIX", "LI", "MC", "MD", "MI", "MIX", "MM",
+];
+
+/// Words after which a numeral of `COMMON_WORDS` is read as a number, in lowercase.
+const CUE_WORDS: [&str; 14] = [
+    "act", "appendix", "article", "book", "canto", "chapter", "chap", "no", "part", "psalm",
+    "scene", "section", "vol", "volume",
+];
+
+impl Lattice<'_> {
+    /// Adds a numeric edge for every word that is a Roman numeral.
+    pub fn add_roman_numerals(&mut self) {
+        let chars = &self.s_chars;
+        let mut edges = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let end = (start..chars.len())
+                .find(|&i| !chars[i].is_alphanumeric())
+                .unwrap_or(chars.len());
+            if end == start {
+                start += 1;
+                continue;
+            }
+
+            let word: String = chars[start..end].iter().collect();
+            if word != "I"
+                && (!COMMON_WORDS.contains(&word.as_str()) || follows_cue_word(chars, start))
+                && let Some(value) = parse_roman(&word)
+            {
+                let mut edge = Edge::new_combined_numeric(
+                    start,
+                    end,
+                    f64::from(value),
+                    "roman".to_string(),
+                    Some("Latin".to_string()),
+                    None,
+                    None,
+                    word,
+                );
+                edge.update(NumDataUpdates {
+                    exact: Some(value.into()),
+                    ..Default::default()
+                });
+                edges.push(edge);
+            }
+            start = end;
+        }
+
+        for edge in edges {
+            self.add_edge(edge);
+        }
+    }
+}
+
+/// Returns `true` if the word before `start`, which may end in a period, is one of
+/// `CUE_WORDS` (e.g., `Chapter` or `Vol.`).
+fn follows_cue_word(chars: &[char], start: usize) -> bool {
+    let mut end = start;
+    while end > 0 && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    if end == start {
+        return false;
+    }
+    if end > 0 && chars[end - 1] == '.' {
+        end -= 1;
+    }
+    let word_start = (0..end)
+        .rev()
+        .find(|&i| !chars[i].is_alphabetic())
+        .map_or(0, |i| i + 1);
+    let word: String = chars[word_start..end].iter().flat_map(|c| c.to_lowercase()).collect();
+    CUE_WORDS.contains(&word.as_str())
+}
+
+/// Returns the value of `s` if it is a Roman numeral from 1 to 3999 in standard form.
+fn parse_roman(s: &str) -> Option<u32> {
+    if s.is_empty() || s.len() > 15 {
+        return None;
+    }
+    let mut rest = s;
+    let mut value = 0;
+    for (numeral_value, numeral) in NUMERALS {
+        while let Some(tail) = rest.strip_prefix(numeral) {
+            value += numeral_value;
+            rest = tail;
+        }
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    // Reject non-standard forms such as `IIII` or `VV` by comparing with the standard form.
+    (value <= 3999 && to_roman(value) == s).then_some(value)
+}
+
+fn to_roman(mut value: u32) -> String {
+    let mut roman = String::new();
+    for (numeral_value, numeral) in NUMERALS {
+        while value >= numeral_value {
+            roman.push_str(numeral);
+            value -= numeral_value;
+        }
+    }
+    roman
+}
@@ -0,0 +1,79 @@
+//! Renders a romanization as HTML ruby annotations over the source text.
+
+use std::ops::Range;
+use unicode_normalization::char::is_combining_mark;
+
+/// How `Uroman::romanize_ruby` groups the source text into ruby annotations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RubyGrouping {
+    /// Each span romanized by one edge, which is a single character except where a rule
+    /// or a number covers several (e.g., `<ruby>漢<rt>han</rt></ruby><ruby>字<rt>zi</rt></ruby>`).
+    #[default]
+    Character,
+    /// Each run of letters, marks and digits (e.g., `<ruby>漢字<rt>hanzi</rt></ruby>`).
+    ///
+    /// Since Chinese and Japanese are written without spaces, a word runs up to the next
+    /// space or punctuation mark.
+    Word,
+}
+
+/// Returns `s` with a ruby annotation over each group of `alignment` that has letters or
+/// digits and is changed by the romanization. The rest of `s` is kept as plain text.
+pub(crate) fn ruby_html(
+    s: &str,
+    romanized: &str,
+    alignment: &[(Range<usize>, Range<usize>)],
+    grouping: RubyGrouping,
+) -> String {
+    let mut groups: Vec<(Range<usize>, Range<usize>)> = Vec::with_capacity(alignment.len());
+    for (source, output) in alignment {
+        if let Some((last_source, last_output)) = groups.last_mut()
+            && grouping == RubyGrouping::Word
+            && is_word(&s[last_source.clone()])
+            && is_word(&s[source.clone()])
+        {
+            last_source.end = source.end;
+            last_output.end = output.end;
+        } else {
+            groups.push((source.clone(), output.clone()));
+        }
+    }
+
+    let mut html = String::with_capacity(s.len() * 4);
+    for (source, output) in groups {
+        let (base, rt) = (&s[source], &romanized[output]);
+        if base.chars().any(char::is_alphanumeric) && base != rt {
+            html.push_str(&format!(
+                "<ruby>{}<rt>{}</rt></ruby>",
+                escape_html(base),
+                escape_html(rt)
+            ));
+        } else {
+            html.push_str(&escape_html(base));
+        }
+    }
+    html
+}
+
+/// Returns `true` if `s` is not empty and consists of letters, marks and digits.
+pub(crate) fn is_word(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || is_combining_mark(c))
+}
+
+/// Escapes the characters that are special in HTML text and attribute values.
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,102 @@
+//! Resolves the scheme names of `UromanBuilder::schemes` for each language code.
+//!
+//! A scheme set for a language code takes the place of the scheme option of its script
+//! when a string is romanized under that code, so that, for example, Russian and
+//! Ukrainian can be romanized under different Cyrillic schemes by one instance.
+
+use serde::{Deserialize, Serialize};
+
+use crate::arabic::ArabicScheme;
+use crate::chinese::ChineseScheme;
+use crate::cyrillic::CyrillicScheme;
+use crate::georgian::GeorgianScheme;
+use crate::greek::GreekScheme;
+use crate::indic::IndicScheme;
+use crate::japanese::JapaneseScheme;
+use crate::korean::KoreanScheme;
+use crate::options::RomanizationOptions;
+use crate::tibetan::TibetanScheme;
+
+/// The languages written in Cyrillic that a Cyrillic scheme can be set for.
+const CYRILLIC_LCODES: &[&str] = &[
+    "bak", "bel", "bul", "chv", "kaz", "kir", "mkd", "mon", "rus", "sah", "srp", "tat", "tgk",
+    "ukr",
+];
+
+/// The languages written in Devanagari that an Indic scheme can be set for.
+const DEVANAGARI_LCODES: &[&str] = &["awa", "bho", "hin", "kok", "mai", "mar", "nep", "san"];
+
+/// A scheme set for a language code, with `None` for the table rules.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Scheme {
+    Japanese(Option<JapaneseScheme>),
+    Korean(Option<KoreanScheme>),
+    Chinese(ChineseScheme),
+    Georgian(Option<GeorgianScheme>),
+    Greek(Option<GreekScheme>),
+    Arabic(ArabicScheme),
+    Cyrillic(CyrillicScheme),
+    Indic(Option<IndicScheme>),
+    Tibetan(Option<TibetanScheme>),
+}
+
+impl Scheme {
+    /// Returns the scheme named `name` for `lcode`, or `None` if `lcode` has no scheme of
+    /// that name.
+    pub(crate) fn parse(lcode: &str, name: &str) -> Option<Self> {
+        let scheme = match (lcode, name) {
+            ("jpn", "default") => Scheme::Japanese(None),
+            ("jpn", "hepburn") => Scheme::Japanese(Some(JapaneseScheme::Hepburn)),
+            ("jpn", "kunrei") => Scheme::Japanese(Some(JapaneseScheme::Kunrei)),
+            ("jpn", "wapuro") => Scheme::Japanese(Some(JapaneseScheme::Wapuro)),
+            ("kor", "default") => Scheme::Korean(None),
+            ("kor", "revised" | "revised-romanization") => {
+                Scheme::Korean(Some(KoreanScheme::RevisedRomanization))
+            }
+            ("kor", "mccune" | "mccune-reischauer") => {
+                Scheme::Korean(Some(KoreanScheme::McCuneReischauer))
+            }
+            ("zho", "default" | "pinyin") => Scheme::Chinese(ChineseScheme::Pinyin),
+            ("zho", "wade-giles") => Scheme::Chinese(ChineseScheme::WadeGiles),
+            ("kat", "default") => Scheme::Georgian(None),
+            ("kat", "national") => Scheme::Georgian(Some(GeorgianScheme::National)),
+            ("kat", "iso") => Scheme::Georgian(Some(GeorgianScheme::Iso)),
+            ("ell" | "grc", "default") => Scheme::Greek(None),
+            ("ell" | "grc", "classical") => Scheme::Greek(Some(GreekScheme::Classical)),
+            ("ell" | "grc", "bgn-pcgn") => Scheme::Greek(Some(GreekScheme::ModernBgnPcgn)),
+            ("ara", "default") => Scheme::Arabic(ArabicScheme::Default),
+            ("ara", "ala-lc") => Scheme::Arabic(ArabicScheme::AlaLc),
+            ("bod", "default") => Scheme::Tibetan(None),
+            ("bod", "wylie") => Scheme::Tibetan(Some(TibetanScheme::Wylie)),
+            (lcode, name) if CYRILLIC_LCODES.contains(&lcode) => match name {
+                "default" => Scheme::Cyrillic(CyrillicScheme::Default),
+                "iso9" => Scheme::Cyrillic(CyrillicScheme::Iso9),
+                "bgn-pcgn" => Scheme::Cyrillic(CyrillicScheme::BgnPcgn),
+                _ => return None,
+            },
+            (lcode, name) if DEVANAGARI_LCODES.contains(&lcode) => match name {
+                "default" => Scheme::Indic(None),
+                "iast" => Scheme::Indic(Some(IndicScheme::Iast)),
+                "iso15919" => Scheme::Indic(Some(IndicScheme::Iso15919)),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(scheme)
+    }
+
+    /// Sets the scheme option of `options` that the scheme takes the place of.
+    pub(crate) fn apply(self, options: &mut RomanizationOptions) {
+        match self {
+            Scheme::Japanese(scheme) => options.japanese_scheme = scheme,
+            Scheme::Korean(scheme) => options.korean_scheme = scheme,
+            Scheme::Chinese(scheme) => options.chinese_scheme = scheme,
+            Scheme::Georgian(scheme) => options.georgian_scheme = scheme,
+            Scheme::Greek(scheme) => options.greek_scheme = scheme,
+            Scheme::Arabic(scheme) => options.arabic_scheme = scheme,
+            Scheme::Cyrillic(scheme) => options.cyrillic_scheme = scheme,
+            Scheme::Indic(scheme) => options.indic_scheme = scheme,
+            Scheme::Tibetan(scheme) => options.tibetan_scheme = scheme,
+        }
+    }
+}
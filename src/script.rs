@@ -0,0 +1,273 @@
+//! Defines the `Script` enum used by script detection.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use crate::core::UromanInner;
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A Unicode script, as named in the uroman data files.
+///
+/// Scripts without a dedicated variant are represented by `Script::Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Georgian,
+    Hebrew,
+    Arabic,
+    Syriac,
+    Thaana,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Oriya,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Sinhala,
+    Thai,
+    Lao,
+    Tibetan,
+    Myanmar,
+    Khmer,
+    Mongolian,
+    Ethiopic,
+    Cherokee,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Bopomofo,
+    /// Chinese characters, named `CJK` in the uroman data files.
+    Han,
+    /// Characters without a script, such as digits, punctuation and symbols.
+    Common,
+    Other(String),
+}
+
+const NAMED_SCRIPTS: &[(&str, Script)] = &[
+    ("Latin", Script::Latin),
+    ("Greek", Script::Greek),
+    ("Cyrillic", Script::Cyrillic),
+    ("Armenian", Script::Armenian),
+    ("Georgian", Script::Georgian),
+    ("Hebrew", Script::Hebrew),
+    ("Arabic", Script::Arabic),
+    ("Syriac", Script::Syriac),
+    ("Thaana", Script::Thaana),
+    ("Devanagari", Script::Devanagari),
+    ("Bengali", Script::Bengali),
+    ("Gurmukhi", Script::Gurmukhi),
+    ("Gujarati", Script::Gujarati),
+    ("Oriya", Script::Oriya),
+    ("Tamil", Script::Tamil),
+    ("Telugu", Script::Telugu),
+    ("Kannada", Script::Kannada),
+    ("Malayalam", Script::Malayalam),
+    ("Sinhala", Script::Sinhala),
+    ("Thai", Script::Thai),
+    ("Lao", Script::Lao),
+    ("Tibetan", Script::Tibetan),
+    ("Myanmar", Script::Myanmar),
+    ("Khmer", Script::Khmer),
+    ("Mongolian", Script::Mongolian),
+    ("Ethiopic", Script::Ethiopic),
+    ("Cherokee", Script::Cherokee),
+    ("Hangul", Script::Hangul),
+    ("Hiragana", Script::Hiragana),
+    ("Katakana", Script::Katakana),
+    ("Bopomofo", Script::Bopomofo),
+    ("CJK", Script::Han),
+];
+
+impl Script {
+    /// Returns the script with the given uroman script name (e.g., `CJK` for `Script::Han`).
+    ///
+    /// An empty name yields `Script::Common`.
+    pub fn from_name(name: &str) -> Self {
+        if name.is_empty() {
+            return Script::Common;
+        }
+        NAMED_SCRIPTS
+            .iter()
+            .find(|(script_name, _)| *script_name == name)
+            .map_or_else(|| Script::Other(name.to_string()), |(_, script)| script.clone())
+    }
+
+    /// Returns the uroman script name of the script.
+    pub fn name(&self) -> &str {
+        match self {
+            Script::Common => "Common",
+            Script::Other(name) => name,
+            script => NAMED_SCRIPTS
+                .iter()
+                .find(|(_, named)| named == script)
+                .map(|(name, _)| *name)
+                .expect("Every named script variant is listed in NAMED_SCRIPTS"),
+        }
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Returns each script in `s` with the fraction of non-whitespace characters it covers,
+/// sorted by descending fraction.
+pub(crate) fn detect_scripts(uroman: &UromanInner, s: &str) -> Vec<(Script, f32)> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    let mut total = 0;
+    for c in s.chars().filter(|c| !c.is_whitespace()) {
+        *counts
+            .entry(Script::from_name(&uroman.chr_script_name(c)))
+            .or_default() += 1;
+        total += 1;
+    }
+
+    let mut scripts: Vec<(Script, usize)> = counts.into_iter().collect();
+    scripts.sort_by(|(script_a, count_a), (script_b, count_b)| {
+        count_b.cmp(count_a).then_with(|| script_a.cmp(script_b))
+    });
+    scripts
+        .into_iter()
+        .map(|(script, count)| (script, count as f32 / total as f32))
+        .collect()
+}
+
+/// Returns the script of most of the non-whitespace characters of `chars` that are not
+/// `Script::Common`, `Script::Common` if there are none, or `None` if two scripts tie.
+fn dominant_script(uroman: &UromanInner, chars: &[char]) -> Option<Script> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for &c in chars.iter().filter(|c| !c.is_whitespace()) {
+        let script = Script::from_name(&uroman.chr_script_name(c));
+        if script != Script::Common {
+            *counts.entry(script).or_default() += 1;
+        }
+    }
+    if counts.is_empty() {
+        return (!chars.is_empty()).then_some(Script::Common);
+    }
+
+    let max = counts.values().copied().max().unwrap_or_default();
+    let mut dominant = counts.into_iter().filter(|&(_, count)| count == max);
+    match (dominant.next(), dominant.next()) {
+        (Some((script, _)), None) => Some(script),
+        _ => None,
+    }
+}
+
+impl Lattice<'_> {
+    /// Sets the source script of each of `edges` from the characters of its span.
+    pub fn set_source_scripts(&self, edges: &mut [Edge]) {
+        for edge in edges {
+            let span = self.s_chars.get(edge.source_range()).unwrap_or_default();
+            edge.get_data_mut().source_script = dominant_script(self.uroman, span);
+        }
+    }
+}
+
+/// Guesses the language of `s` from its dominant script and characteristic letters.
+///
+/// Returns `None` for scripts shared by many languages unless a letter specific to one
+/// of them occurs (e.g., `ї` for Ukrainian).
+pub(crate) fn guess_lcode(uroman: &UromanInner, s: &str) -> Option<&'static str> {
+    let scripts = detect_scripts(uroman, s);
+    let has_script = |script: Script| scripts.iter().any(|(s, _)| *s == script);
+    let has_any_char = |chars: &str| s.chars().any(|c| chars.contains(c));
+
+    // Japanese text mixes kana with Han characters, which may dominate.
+    if has_script(Script::Hiragana) || has_script(Script::Katakana) {
+        return Some("jpn");
+    }
+    if has_script(Script::Hangul) {
+        return Some("kor");
+    }
+
+    let (dominant, _) = scripts.iter().find(|(script, _)| *script != Script::Common)?;
+    match dominant {
+        Script::Han => Some("zho"),
+        Script::Greek => Some("ell"),
+        Script::Armenian => Some("hye"),
+        Script::Georgian => Some("kat"),
+        Script::Hebrew if has_any_char("װױײ") => Some("yid"),
+        Script::Hebrew => Some("heb"),
+        Script::Thai => Some("tha"),
+        Script::Lao => Some("lao"),
+        Script::Khmer => Some("khm"),
+        Script::Myanmar => Some("mya"),
+        Script::Tibetan => Some("bod"),
+        Script::Thaana => Some("div"),
+        Script::Sinhala => Some("sin"),
+        Script::Bengali => Some("ben"),
+        Script::Gurmukhi => Some("pan"),
+        Script::Gujarati => Some("guj"),
+        Script::Oriya => Some("ori"),
+        Script::Tamil => Some("tam"),
+        Script::Telugu => Some("tel"),
+        Script::Kannada => Some("kan"),
+        Script::Malayalam => Some("mal"),
+        Script::Cyrillic if has_any_char("ЇїЄєҐґ") => Some("ukr"),
+        Script::Cyrillic if has_any_char("Ўў") => Some("bel"),
+        Script::Cyrillic if has_any_char("ЂђЋћ") => Some("srp"),
+        Script::Cyrillic if has_any_char("ЃѓЌќЅѕ") => Some("mkd"),
+        Script::Arabic if has_any_char("ېۆۈ") => Some("uig"),
+        Script::Arabic if has_any_char("ښږ") => Some("pus"),
+        Script::Arabic if has_any_char("ےںٹڈڑ") => Some("urd"),
+        Script::Arabic if has_any_char("پچژگ") => Some("fas"),
+        _ => None,
+    }
+}
+
+/// Merges scripts that are written together in one language (e.g., Han and kana in Japanese).
+fn script_group(script: Script) -> Script {
+    match script {
+        Script::Hiragana | Script::Katakana | Script::Bopomofo | Script::Hangul => Script::Han,
+        script => script,
+    }
+}
+
+/// Splits `s` into runs of the same script, as byte ranges.
+///
+/// Whitespace, digits, punctuation and symbols belong to the surrounding run, except
+/// for whitespace between two runs, which is left out of both.
+pub(crate) fn script_runs(uroman: &UromanInner, s: &str) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut run_start = 0;
+    let mut run_script: Option<Script> = None;
+    // End of the last character of the current run that is not whitespace.
+    let mut last_non_space_end = 0;
+
+    for (i, c) in s.char_indices() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let script = script_group(Script::from_name(&uroman.chr_script_name(c)));
+        if script != Script::Common {
+            match &run_script {
+                None => run_script = Some(script),
+                Some(current) if *current != script => {
+                    // The whitespace in `last_non_space_end..i` is left between the runs.
+                    runs.push(run_start..last_non_space_end);
+                    run_start = i;
+                    run_script = Some(script);
+                }
+                Some(_) => {}
+            }
+        }
+        last_non_space_end = i + c.len_utf8();
+    }
+
+    if !s.is_empty() {
+        runs.push(run_start..s.len());
+    }
+    runs
+}
@@ -0,0 +1,186 @@
+//! Segments Thai, Lao and Khmer text into words for `segment_words`.
+//!
+//! These scripts are written without spaces between words. Each run of letters of the
+//! script is split by maximal matching against a bundled list of common words: of the
+//! segmentations that leave the fewest characters outside known words, the one with
+//! the fewest words is chosen. Characters outside known words are kept together
+//! between the known words around them.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A list of words and the characters of the script it belongs to.
+struct WordList {
+    words: HashSet<&'static str>,
+    max_word_chars: usize,
+    script: fn(char) -> bool,
+}
+
+impl WordList {
+    fn new(file: &'static str, script: fn(char) -> bool) -> Self {
+        let words: HashSet<&str> = file
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let max_word_chars = words.iter().map(|w| w.chars().count()).max().unwrap_or(0);
+        Self { words, max_word_chars, script }
+    }
+}
+
+static THAI_WORDS: LazyLock<WordList> = LazyLock::new(|| {
+    WordList::new(include_str!("../data/Thai_words.txt"), |c| {
+        ('\u{0E01}'..='\u{0E4E}').contains(&c)
+    })
+});
+
+static LAO_WORDS: LazyLock<WordList> = LazyLock::new(|| {
+    WordList::new(include_str!("../data/Lao_words.txt"), |c| {
+        ('\u{0E81}'..='\u{0ECE}').contains(&c) || ('\u{0EDC}'..='\u{0EDF}').contains(&c)
+    })
+});
+
+static KHMER_WORDS: LazyLock<WordList> = LazyLock::new(|| {
+    WordList::new(include_str!("../data/Khmer_words.txt"), |c| {
+        ('\u{1780}'..='\u{17D3}').contains(&c)
+    })
+});
+
+/// Returns the word list for `lcode`, if its script is segmented.
+fn word_list(lcode: Option<&str>) -> Option<&'static WordList> {
+    match lcode? {
+        "tha" => Some(&THAI_WORDS),
+        "lao" => Some(&LAO_WORDS),
+        "khm" => Some(&KHMER_WORDS),
+        _ => None,
+    }
+}
+
+/// Returns `true` if a word cannot start with `c`, as it belongs to the letter before it.
+fn is_dependent(c: char) -> bool {
+    matches!(
+        c.general_category(),
+        GeneralCategory::NonspacingMark | GeneralCategory::SpacingMark
+    ) || matches!(c, 'ะ' | 'า' | 'ำ' | 'ๅ' | 'ๆ' | 'ະ' | 'າ' | 'ຳ' | 'ໆ')
+}
+
+/// Returns `true` if a word cannot end with `c`, as it belongs to the letter after it:
+/// the leading vowels of Thai and Lao, and the Khmer coeng, which marks the next
+/// consonant as subscript.
+fn is_leading(c: char) -> bool {
+    matches!(c, 'เ' | 'แ' | 'โ' | 'ใ' | 'ไ' | 'ເ' | 'ແ' | 'ໂ' | 'ໃ' | 'ໄ' | '\u{17D2}')
+}
+
+/// Inserts a space between the words of each run of Thai, Lao or Khmer in `s`, if
+/// `lcode` is `tha`, `lao` or `khm`, and returns the segmented text with the character
+/// offsets of the inserted spaces in it. Returns `None` if no space is inserted.
+pub(crate) fn segment_words(s: &str, lcode: Option<&str>) -> Option<(String, Vec<usize>)> {
+    let list = word_list(lcode)?;
+    let chars: Vec<char> = s.chars().collect();
+
+    let mut segmented = String::with_capacity(s.len() + s.len() / 4);
+    let mut spaces = Vec::new();
+    let mut len = 0;
+    let mut start = 0;
+    while start < chars.len() {
+        if !(list.script)(chars[start]) {
+            segmented.push(chars[start]);
+            len += 1;
+            start += 1;
+            continue;
+        }
+        let end = (start..chars.len())
+            .find(|&i| !(list.script)(chars[i]))
+            .unwrap_or(chars.len());
+        for (i, word) in split_run(&chars[start..end], list).into_iter().enumerate() {
+            if i > 0 {
+                segmented.push(' ');
+                spaces.push(len);
+                len += 1;
+            }
+            segmented.extend(word);
+            len += word.len();
+        }
+        start = end;
+    }
+
+    (!spaces.is_empty()).then_some((segmented, spaces))
+}
+
+impl Lattice<'_> {
+    /// Maps the offsets of `edges` from the segmented text of the lattice back to the text
+    /// it was built from, so that the edge of each inserted space covers no characters and
+    /// offsets refer to the text passed in by the caller.
+    pub fn remove_inserted_spaces(&self, edges: &mut [Edge]) {
+        if self.inserted_spaces.is_empty() {
+            return;
+        }
+        let unsegmented = |position: usize| {
+            position - self.inserted_spaces.partition_point(|&space| space < position)
+        };
+        for edge in edges {
+            let data = edge.get_data_mut();
+            data.start = unsegmented(data.start);
+            data.end = unsegmented(data.end);
+        }
+    }
+}
+
+/// The last word of the best segmentation of a prefix of a run.
+#[derive(Clone, Copy)]
+struct Step {
+    /// The number of characters outside known words, and the number of words.
+    cost: (usize, usize),
+    /// Where the word starts.
+    start: usize,
+    /// Whether the word is in the word list.
+    known: bool,
+}
+
+/// Splits a run of letters of one script into words.
+fn split_run<'a>(run: &'a [char], list: &WordList) -> Vec<&'a [char]> {
+    let n = run.len();
+    let is_boundary =
+        |i: usize| i == 0 || i == n || (!is_dependent(run[i]) && !is_leading(run[i - 1]));
+
+    // `best[i]` ends the best segmentation of `run[..i]`.
+    let mut best: Vec<Option<Step>> = vec![None; n + 1];
+    best[0] = Some(Step { cost: (0, 0), start: 0, known: true });
+    for i in 0..n {
+        let Some(Step { cost: (unknown, words), .. }) = best[i] else {
+            continue;
+        };
+        let mut relax = |end: usize, cost: (usize, usize), known: bool| {
+            if best[end].is_none_or(|step| cost < step.cost) {
+                best[end] = Some(Step { cost, start: i, known });
+            }
+        };
+
+        for end in (i + 1..=n.min(i + list.max_word_chars)).filter(|&end| is_boundary(end)) {
+            let word: String = run[i..end].iter().collect();
+            if list.words.contains(word.as_str()) {
+                relax(end, (unknown, words + 1), true);
+            }
+        }
+        if let Some(end) = (i + 1..=n).find(|&end| is_boundary(end)) {
+            relax(end, (unknown + end - i, words + 1), false);
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut end = n;
+    while end > 0 {
+        let Step { start, known, .. } = best[end].expect("every boundary is reachable");
+        match spans.last_mut() {
+            // Characters outside known words are kept in one word.
+            Some((next_start, _, false)) if !known => *next_start = start,
+            _ => spans.push((start, end, known)),
+        }
+        end = start;
+    }
+    spans.iter().rev().map(|&(start, end, _)| &run[start..end]).collect()
+}
@@ -0,0 +1,28 @@
+//! Splits text into sentences for `Uroman::romanize_sentences`.
+//!
+//! Sentence boundaries follow the Unicode Text Segmentation rules (UAX #29), which end a
+//! sentence at the terminators of many scripts, such as `.`, `。`, `؟`, `।` and `።`, and
+//! do not end one at a full stop followed by a lowercase letter. Languages whose
+//! terminators are not recognized by these rules add their own.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Returns the characters that end a sentence in `lcode` in addition to those of UAX #29.
+fn extra_terminators(lcode: Option<&str>) -> &'static [char] {
+    match lcode {
+        // The Greek question mark (U+037E) is canonically equivalent to `;`, which it is
+        // written as after normalization and in most Greek text.
+        Some("ell" | "grc") => &['\u{037E}', ';'],
+        _ => &['\u{037E}'],
+    }
+}
+
+/// Splits `s` into sentences, without the whitespace around them.
+pub(crate) fn split_sentences<'a>(s: &'a str, lcode: Option<&str>) -> Vec<&'a str> {
+    let terminators = extra_terminators(lcode);
+    s.split_sentence_bounds()
+        .flat_map(|sentence| sentence.split_inclusive(terminators))
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
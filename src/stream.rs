@@ -0,0 +1,149 @@
+//! Romanizes an async stream of byte chunks, behind the `async` feature.
+//!
+//! Chunks are buffered and romanized up to the last line break they contain, so that
+//! neither a character nor a sequence matched by the rules is split at a boundary
+//! between chunks. Text without line breaks is romanized once `MAX_PENDING` bytes are
+//! buffered, up to the last whitespace or, failing that, up to the last grapheme.
+
+use bytes::Bytes;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Uroman, rom_format};
+
+/// The number of bytes buffered without a line break before they are romanized.
+const MAX_PENDING: usize = 64 * 1024;
+
+/// A stream of the romanized chunks of a stream of byte chunks, returned by
+/// `Uroman::romanize_stream`.
+///
+/// Invalid UTF-8 is replaced with U+FFFD REPLACEMENT CHARACTER, as by
+/// `String::from_utf8_lossy`. Empty chunks are not yielded.
+pub struct RomanizeStream<S> {
+    uroman: Uroman,
+    lcode: Option<String>,
+    inner: S,
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl Uroman {
+    /// Romanizes a stream of byte chunks incrementally, yielding the romanized text as it
+    /// becomes available.
+    ///
+    /// The concatenation of the yielded chunks is the romanization of the lines of the
+    /// input, whatever the boundaries between its chunks. The stream holds a clone of the
+    /// instance, which shares its rules and cache.
+    ///
+    /// # Arguments
+    ///
+    /// lcode: [ISO 639-3 language code](https://www.loc.gov/standards/iso639-2/php/code_list.php)
+    /// (e.g., eng, jpn, hin, ara, zho)
+    pub fn romanize_stream<S>(&self, stream: S, lcode: Option<&str>) -> RomanizeStream<S>
+    where
+        S: Stream<Item = Bytes> + Unpin,
+    {
+        RomanizeStream {
+            uroman: self.clone(),
+            lcode: lcode.map(str::to_string),
+            inner: stream,
+            pending: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+impl<S> RomanizeStream<S> {
+    /// Removes the first `len` buffered bytes and returns their romanization.
+    fn romanize_pending(&mut self, len: usize) -> String {
+        let bytes: Vec<u8> = self.pending.drain(..len).collect();
+        let text = String::from_utf8_lossy(&bytes);
+        self.uroman
+            .romanize_string::<rom_format::Str>(&text, self.lcode.as_deref())
+            .to_string()
+    }
+}
+
+impl<S> Stream for RomanizeStream<S>
+where
+    S: Stream<Item = Bytes> + Unpin,
+{
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        let this = &mut *self;
+        loop {
+            let len = if this.done {
+                if this.pending.is_empty() {
+                    return Poll::Ready(None);
+                }
+                this.pending.len()
+            } else {
+                match Pin::new(&mut this.inner).poll_next(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(None) => {
+                        this.done = true;
+                        continue;
+                    }
+                    Poll::Ready(Some(chunk)) => {
+                        this.pending.extend_from_slice(&chunk);
+                        complete_prefix_len(&this.pending)
+                    }
+                }
+            };
+
+            if len > 0 {
+                let romanized = this.romanize_pending(len);
+                if !romanized.is_empty() {
+                    return Poll::Ready(Some(romanized));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the length of the prefix of `pending` that can be romanized on its own: up to
+/// the last line break, or, with `MAX_PENDING` bytes and no line break, up to the last
+/// whitespace or the last grapheme.
+fn complete_prefix_len(pending: &[u8]) -> usize {
+    // A line break byte is never part of a multi-byte character.
+    if let Some(i) = pending.iter().rposition(|&b| b == b'\n') {
+        return i + 1;
+    }
+    if pending.len() < MAX_PENDING {
+        return 0;
+    }
+
+    let complete = pending.len() - incomplete_suffix_len(pending);
+    let Ok(s) = std::str::from_utf8(&pending[..complete]) else {
+        return complete;
+    };
+    if let Some((i, c)) = s.char_indices().rev().find(|(_, c)| c.is_whitespace()) {
+        return i + c.len_utf8();
+    }
+    // The last grapheme may be continued by the next chunk.
+    match s.grapheme_indices(true).next_back() {
+        Some((i, _)) if i > 0 => i,
+        _ => complete,
+    }
+}
+
+/// Returns the number of bytes at the end of `bytes` that start a character but do not
+/// complete it.
+fn incomplete_suffix_len(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let b = bytes[bytes.len() - len];
+        if b & 0xC0 != 0x80 {
+            let char_len = match b {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            return if char_len > len { len } else { 0 };
+        }
+    }
+    0
+}
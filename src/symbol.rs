@@ -0,0 +1,118 @@
+//! Applies an `EmojiPolicy` to emoji and other symbols.
+//!
+//! A symbol is a character of the general category `So` (e.g., 😀, ♥, ©). An emoji
+//! sequence, such as a symbol with a skin tone modifier or variation selector, symbols
+//! joined with a zero width joiner, or a flag of two regional indicators, is handled as
+//! one unit and covered by a single edge of the type `symbol`.
+
+use serde::{Deserialize, Serialize};
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// How emoji and other symbols are romanized.
+///
+/// | Input | `Keep` | `Drop` | `Name`               |
+/// |-------|--------|--------|----------------------|
+/// | 😀    | 😀     |        | `:grinning_face:`    |
+/// | 👍🏽    | 👍🏽     |        | `:thumbs_up_sign:`   |
+/// | 🇺🇸    | 🇺🇸     |        | `:flag_us:`          |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EmojiPolicy {
+    /// Symbols are passed through as they are, as by the romanization tables.
+    #[default]
+    Keep,
+    /// Symbols are removed.
+    Drop,
+    /// Each symbol is replaced with its lowercase Unicode name between colons, with
+    /// underscores for spaces. Skin tone modifiers and variation selectors are dropped,
+    /// and a flag is written with its region code (e.g., `:flag_us:`).
+    Name,
+}
+
+pub(crate) const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+fn is_symbol(c: char) -> bool {
+    c.general_category() == GeneralCategory::OtherSymbol
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Returns `true` for the characters that modify the symbol before them.
+fn is_modifier(c: char) -> bool {
+    // Skin tones, variation selectors, the keycap mark and the tags of subdivision flags.
+    matches!(c, '\u{1F3FB}'..='\u{1F3FF}' | '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}' | '\u{E0020}'..='\u{E007F}')
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every emoji sequence and symbol as selected by `policy`.
+    pub fn apply_emoji_policy(&mut self, policy: EmojiPolicy) {
+        if policy == EmojiPolicy::Keep {
+            return;
+        }
+
+        let chars = &self.s_chars;
+        let mut edges = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            if !is_symbol(chars[start]) {
+                start += 1;
+                continue;
+            }
+
+            let mut end = start + 1;
+            if is_regional_indicator(chars[start])
+                && chars.get(end).copied().is_some_and(is_regional_indicator)
+            {
+                end += 1;
+            }
+            loop {
+                match chars.get(end) {
+                    Some(&c) if is_modifier(c) => end += 1,
+                    Some(&ZERO_WIDTH_JOINER)
+                        if chars.get(end + 1).copied().is_some_and(is_symbol) =>
+                    {
+                        end += 2
+                    }
+                    _ => break,
+                }
+            }
+
+            let txt = match policy {
+                EmojiPolicy::Name => symbol_names(&chars[start..end]),
+                _ => String::new(),
+            };
+            edges.push(Edge::new_regular(start, end, txt, "symbol".to_string()));
+            start = end;
+        }
+
+        for edge in edges {
+            let (start, end) = (edge.start(), edge.end());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
+
+/// Returns the `:name:` of each symbol of an emoji sequence.
+fn symbol_names(sequence: &[char]) -> String {
+    if let [a, b] = sequence
+        && is_regional_indicator(*a)
+        && is_regional_indicator(*b)
+    {
+        let region: String = [*a, *b]
+            .iter()
+            .filter_map(|&c| char::from_u32(c as u32 - 0x1F1E6 + u32::from(b'a')))
+            .collect();
+        return format!(":flag_{region}:");
+    }
+
+    sequence
+        .iter()
+        .filter(|&&c| is_symbol(c))
+        .filter_map(|&c| unicode_names2::name(c))
+        .map(|name| format!(":{}:", name.to_string().to_lowercase().replace(' ', "_")))
+        .collect()
+}
@@ -0,0 +1,358 @@
+//! Romanizes Tibetan according to a selectable `TibetanScheme`.
+//!
+//! The romanization tables write Tibetan letter by letter. With a scheme, every
+//! syllable (the letters between two tsheg marks) is romanized as a whole, which is
+//! needed to find its root letter and place the inherent vowel after it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// A romanization scheme for Tibetan, used when `lcode` is `bod`.
+///
+/// | Tibetan        | `Wylie`              |
+/// |----------------|----------------------|
+/// | བོད་           | bod                  |
+/// | བཀྲ་ཤིས་བདེ་ལེགས། | bkra shis bde legs/ |
+/// | གཡག            | g.yag                |
+/// | སངས་རྒྱས        | sangs rgyas          |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TibetanScheme {
+    /// Extended Wylie, a reversible ASCII transliteration. Syllables are separated by spaces
+    /// and the shad is written `/`. A `.` separates a prefix from a root letter that would
+    /// otherwise be read as part of a stack (e.g., `g.yag` for གཡག versus `gyag` for གྱག).
+    Wylie,
+}
+
+const TSHEG: char = '\u{0F0B}';
+const AA: char = '\u{0F71}';
+
+/// The letters that can precede the root letter of a syllable.
+const PREFIXES: &str = "གདབམའ";
+/// The letters that can follow the root letter of a syllable.
+const SUFFIXES: &str = "གངདནབམའརལས";
+
+/// A base letter together with its subjoined letters and vowel signs.
+#[derive(Default)]
+struct Stack {
+    base: char,
+    subjoined: Vec<char>,
+    vowels: Vec<char>,
+    marks: String,
+}
+
+impl Lattice<'_> {
+    /// Replaces the edges of every Tibetan syllable with its romanization under `scheme`.
+    pub fn apply_tibetan_scheme(&mut self, scheme: TibetanScheme) {
+        let TibetanScheme::Wylie = scheme;
+
+        let chars = &self.s_chars;
+        let mut edges = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let c = chars[start];
+            if is_syllable_char(c) {
+                let end = (start..chars.len())
+                    .find(|&i| !is_syllable_char(chars[i]))
+                    .unwrap_or(chars.len());
+                edges.push((start, end, wylie_syllable(&chars[start..end])));
+                start = end;
+                continue;
+            }
+            let txt = match c {
+                // A tsheg ends a syllable; it is written as a space between two syllables.
+                TSHEG
+                    if chars
+                        .get(start + 1)
+                        .is_some_and(|&next| is_syllable_char(next)) =>
+                {
+                    Some(" ")
+                }
+                TSHEG => Some(""),
+                '\u{0F0D}' => Some("/"),
+                '\u{0F0E}' => Some("//"),
+                _ => None,
+            };
+            if let Some(txt) = txt {
+                edges.push((start, start + 1, txt.to_string()));
+            }
+            start += 1;
+        }
+
+        for (start, end, txt) in edges {
+            let edge = Edge::new_regular(start, end, txt, "rom wylie".to_string());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
+
+/// Returns `true` if `c` is a letter or sign that belongs to a Tibetan syllable.
+pub(crate) fn is_syllable_char(c: char) -> bool {
+    matches!(c, '\u{0F40}'..='\u{0F6C}' | '\u{0F71}'..='\u{0F7F}' | '\u{0F80}'..='\u{0F84}' | '\u{0F90}'..='\u{0FBC}')
+}
+
+/// Romanizes the letters and signs of a single syllable.
+pub(crate) fn wylie_syllable(syllable: &[char]) -> String {
+    let mut stacks: Vec<Stack> = Vec::new();
+    for &c in syllable {
+        match (c, stacks.last_mut()) {
+            ('\u{0F40}'..='\u{0F6C}', _) | (_, None) => stacks.push(Stack {
+                base: c,
+                ..Stack::default()
+            }),
+            ('\u{0F90}'..='\u{0FBC}', Some(stack)) => stack.subjoined.push(c),
+            ('\u{0F7E}', Some(stack)) => stack.marks.push('M'),
+            ('\u{0F7F}', Some(stack)) => stack.marks.push('H'),
+            ('\u{0F82}', Some(stack)) => stack.marks.push_str("~M`"),
+            ('\u{0F83}', Some(stack)) => stack.marks.push_str("~M"),
+            ('\u{0F84}', Some(stack)) => stack.marks.push('?'),
+            (_, Some(stack)) => stack.vowels.push(c),
+        }
+    }
+
+    let root = root_index(&stacks);
+    let mut wylie = String::new();
+    for (i, stack) in stacks.iter().enumerate() {
+        if i == 1
+            && root == 1
+            && stack.subjoined.is_empty()
+            && stacks[0].base == 'ག'
+            && stack.base == 'ཡ'
+        {
+            wylie.push('.');
+        }
+        // ཨ only carries its vowel.
+        if stack.base != 'ཨ' || !stack.subjoined.is_empty() {
+            wylie.push_str(letter(stack.base));
+        }
+        for &subjoined in &stack.subjoined {
+            let base = char::from_u32(subjoined as u32 - 0x50).unwrap_or(subjoined);
+            wylie.push_str(match subjoined {
+                '\u{0FBA}' => "w",
+                '\u{0FBB}' => "y",
+                '\u{0FBC}' => "r",
+                _ => letter(base),
+            });
+        }
+        if i == root || !stack.vowels.is_empty() {
+            wylie.push_str(&vowel(&stack.vowels));
+        }
+        wylie.push_str(&stack.marks);
+    }
+    wylie
+}
+
+/// The Wylie of the vowel signs, with the inherent vowel `a`, longest first.
+const VOWELS: [(&str, &str); 12] = [
+    ("-I", "\u{0F71}\u{0F80}"),
+    ("-i", "\u{0F80}"),
+    ("ai", "\u{0F7B}"),
+    ("au", "\u{0F7D}"),
+    ("A", "\u{0F71}"),
+    ("I", "\u{0F71}\u{0F72}"),
+    ("U", "\u{0F71}\u{0F74}"),
+    ("a", ""),
+    ("e", "\u{0F7A}"),
+    ("i", "\u{0F72}"),
+    ("o", "\u{0F7C}"),
+    ("u", "\u{0F74}"),
+];
+
+/// The Wylie of the marks that follow the vowel, longest first.
+const MARKS: [(&str, &str); 5] = [
+    ("~M`", "\u{0F82}"),
+    ("~M", "\u{0F83}"),
+    ("M", "\u{0F7E}"),
+    ("H", "\u{0F7F}"),
+    ("?", "\u{0F84}"),
+];
+
+/// The letters that can be written above the root letter of a stack.
+const SUPERSCRIPTS: &str = "རལས";
+/// The letters that can be written below the root letter of a stack.
+const SUBSCRIPTS: &str = "ཡརལཝ";
+
+enum WylieToken {
+    Letter(char),
+    Vowel(&'static str),
+    Mark(&'static str),
+    Separator,
+}
+
+/// Maps the Wylie of a single syllable, as written by `wylie_syllable`, back to Tibetan.
+///
+/// The letters before a vowel are read as a stack, after a single prefix if the stack
+/// would not be valid otherwise (e.g., `bkra` as བ and ཀྲ). Returns `None` if `wylie`
+/// is not made of Wylie letters, vowels and marks.
+pub(crate) fn tibetan_syllable(wylie: &str) -> Option<String> {
+    // `r` is read as ར rather than the fixed-form ཪ.
+    let letters: Vec<(&str, char)> = ('\u{0F40}'..='\u{0F6C}')
+        .filter(|&c| c != 'ཪ')
+        .map(|c| (letter(c), c))
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+
+    let mut tokens = Vec::new();
+    let mut rest = wylie;
+    while !rest.is_empty() {
+        let candidates = letters
+            .iter()
+            .map(|&(name, c)| (name, WylieToken::Letter(c)))
+            .chain(VOWELS.iter().map(|&(name, v)| (name, WylieToken::Vowel(v))))
+            .chain(MARKS.iter().map(|&(name, m)| (name, WylieToken::Mark(m))))
+            .chain([(".", WylieToken::Separator)]);
+        // The longest match, so that `tsh` is not read as `ts` and `h`.
+        let (name, token) = candidates
+            .filter(|(name, _)| rest.starts_with(name))
+            .max_by_key(|(name, _)| name.len())?;
+        tokens.push(token);
+        rest = &rest[name.len()..];
+    }
+
+    let mut tibetan = String::new();
+    let mut pending: Vec<char> = Vec::new();
+    let mut after_vowel = false;
+    for token in tokens {
+        match token {
+            WylieToken::Letter(c) => {
+                pending.push(c);
+                after_vowel = false;
+            }
+            WylieToken::Vowel(signs) if after_vowel => tibetan.push_str(signs),
+            WylieToken::Vowel(signs) => {
+                let split = (0..pending.len())
+                    .find(|&i| is_valid_stack(&pending[i..]))
+                    .unwrap_or(0);
+                tibetan.extend(&pending[..split]);
+                match pending.get(split..).and_then(<[char]>::split_first) {
+                    Some((&base, subjoined)) => {
+                        tibetan.push(base);
+                        tibetan.extend(subjoined.iter().map(|&c| subjoined_form(c)));
+                    }
+                    None => tibetan.push('ཨ'),
+                }
+                tibetan.push_str(signs);
+                pending.clear();
+                after_vowel = true;
+            }
+            WylieToken::Mark(mark) => {
+                tibetan.extend(pending.drain(..));
+                tibetan.push_str(mark);
+            }
+            WylieToken::Separator => {
+                tibetan.extend(pending.drain(..));
+                after_vowel = false;
+            }
+        }
+    }
+    tibetan.extend(pending);
+    Some(tibetan)
+}
+
+/// Returns `true` if `letters` can form one stack: a root letter, possibly under a
+/// superscript and over subscripts (e.g., རྒྱ).
+fn is_valid_stack(letters: &[char]) -> bool {
+    let root = match letters {
+        [] => return false,
+        [_] => return true,
+        [first, _, ..] if SUPERSCRIPTS.contains(*first) => 1,
+        _ => 0,
+    };
+    letters[root + 1..].iter().all(|&c| SUBSCRIPTS.contains(c))
+}
+
+/// Returns the subjoined form of the letter `c`, which is written below a stack.
+fn subjoined_form(c: char) -> char {
+    char::from_u32(c as u32 + 0x50).unwrap_or(c)
+}
+
+/// Returns the index of the root letter, which carries the vowel of the syllable.
+fn root_index(stacks: &[Stack]) -> usize {
+    if let Some(i) = stacks.iter().position(|stack| !stack.vowels.is_empty()) {
+        return i;
+    }
+    // Prefixes and suffixes are single letters, so a stack is the root.
+    if let Some(i) = stacks.iter().position(|stack| !stack.subjoined.is_empty()) {
+        return i;
+    }
+    let is_prefix = stacks
+        .first()
+        .is_some_and(|stack| PREFIXES.contains(stack.base));
+    match stacks.len() {
+        0..=2 => 0,
+        // A suffix followed by the secondary suffix ས (e.g., `sangs`).
+        3 if stacks[2].base == 'ས' && SUFFIXES.contains(stacks[1].base) => 0,
+        _ if is_prefix => 1,
+        _ => 0,
+    }
+}
+
+fn vowel(vowels: &[char]) -> String {
+    match vowels {
+        [] => "a".to_string(),
+        [AA] => "A".to_string(),
+        [AA, '\u{0F72}'] | ['\u{0F73}'] => "I".to_string(),
+        [AA, '\u{0F74}'] | ['\u{0F75}'] => "U".to_string(),
+        [AA, '\u{0F80}'] | ['\u{0F81}'] => "-I".to_string(),
+        _ => vowels
+            .iter()
+            .map(|&v| match v {
+                '\u{0F72}' => "i",
+                '\u{0F74}' => "u",
+                '\u{0F7A}' => "e",
+                '\u{0F7B}' => "ai",
+                '\u{0F7C}' => "o",
+                '\u{0F7D}' => "au",
+                '\u{0F80}' => "-i",
+                _ => "",
+            })
+            .collect(),
+    }
+}
+
+fn letter(c: char) -> &'static str {
+    match c {
+        'ཀ' => "k",
+        'ཁ' => "kh",
+        'ག' => "g",
+        '\u{0F43}' => "g+h",
+        'ང' => "ng",
+        'ཅ' => "c",
+        'ཆ' => "ch",
+        'ཇ' => "j",
+        'ཉ' => "ny",
+        'ཊ' => "T",
+        'ཋ' => "Th",
+        'ཌ' => "D",
+        '\u{0F4D}' => "D+h",
+        'ཎ' => "N",
+        'ཏ' => "t",
+        'ཐ' => "th",
+        'ད' => "d",
+        '\u{0F52}' => "d+h",
+        'ན' => "n",
+        'པ' => "p",
+        'ཕ' => "ph",
+        'བ' => "b",
+        '\u{0F57}' => "b+h",
+        'མ' => "m",
+        'ཙ' => "ts",
+        'ཚ' => "tsh",
+        'ཛ' => "dz",
+        '\u{0F5C}' => "dz+h",
+        'ཝ' => "w",
+        'ཞ' => "zh",
+        'ཟ' => "z",
+        'འ' => "'",
+        'ཡ' => "y",
+        'ར' | 'ཪ' => "r",
+        'ལ' => "l",
+        'ཤ' => "sh",
+        'ཥ' => "Sh",
+        'ས' => "s",
+        'ཧ' => "h",
+        '\u{0F69}' => "k+Sh",
+        _ => "",
+    }
+}
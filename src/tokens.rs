@@ -0,0 +1,57 @@
+//! Pairs the words of a source text with their romanization.
+
+use std::ops::Range;
+use unicode_normalization::char::is_combining_mark;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenClass {
+    Word,
+    Whitespace,
+    Punctuation,
+}
+
+impl TokenClass {
+    fn of(s: &str) -> Self {
+        if s.chars()
+            .any(|c| c.is_alphanumeric() || is_combining_mark(c))
+        {
+            TokenClass::Word
+        } else if s.chars().all(char::is_whitespace) {
+            TokenClass::Whitespace
+        } else {
+            TokenClass::Punctuation
+        }
+    }
+}
+
+/// Splits `s` into tokens along `alignment` and pairs each with its romanization.
+///
+/// A token is a run of spans with letters, marks or digits, a run of whitespace, or a
+/// single span of other characters. Whitespace and other tokens are only returned if
+/// `include_separators` is `true`.
+pub(crate) fn romanized_tokens(
+    s: &str,
+    romanized: &str,
+    alignment: &[(Range<usize>, Range<usize>)],
+    include_separators: bool,
+) -> Vec<(String, String)> {
+    let mut tokens: Vec<(TokenClass, Range<usize>, Range<usize>)> = Vec::new();
+    for (source, output) in alignment {
+        let class = TokenClass::of(&s[source.clone()]);
+        match tokens.last_mut() {
+            Some((last_class, last_source, last_output))
+                if *last_class == class && class != TokenClass::Punctuation =>
+            {
+                last_source.end = source.end;
+                last_output.end = output.end;
+            }
+            _ => tokens.push((class, source.clone(), output.clone())),
+        }
+    }
+
+    tokens
+        .into_iter()
+        .filter(|(class, _, _)| include_separators || *class == TokenClass::Word)
+        .map(|(_, source, output)| (s[source].to_string(), romanized[output].to_string()))
+        .collect()
+}
@@ -1,7 +1,9 @@
 //! Utility functions for parsing uroman data files.
 
 use regex::Regex;
+use std::any::Any;
 use std::sync::{LazyLock, OnceLock};
+use unicode_normalization::char::canonical_combining_class;
 
 use crate::core::Value;
 
@@ -74,21 +76,34 @@ pub fn decode_unicode_escapes(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut last_end = 0;
 
-    for m in HAS_ESCAPE_RE.find_iter(s) {
+    let mut matches = HAS_ESCAPE_RE.find_iter(s).peekable();
+    while let Some(m) = matches.next() {
         result.push_str(&s[last_end..m.start()]);
+        last_end = m.end();
 
         let full_escape_sequence = m.as_str();
         let hex_part = &full_escape_sequence[2..];
 
-        let codepoint = u32::from_str_radix(hex_part, 16).unwrap();
+        let mut codepoint = u32::from_str_radix(hex_part, 16).unwrap();
+
+        // A character above U+FFFF may be escaped as a UTF-16 surrogate pair
+        // (e.g., `\ud840\udc00` for U+20000), as in JSON.
+        if (0xD800..0xDC00).contains(&codepoint)
+            && let Some(low) = matches.peek().filter(|low| {
+                low.start() == m.end() && low.as_str().starts_with("\\u")
+            })
+            && let Ok(low_codepoint @ 0xDC00..0xE000) = u32::from_str_radix(&low.as_str()[2..], 16)
+        {
+            codepoint = 0x10000 + ((codepoint - 0xD800) << 10) + (low_codepoint - 0xDC00);
+            last_end = low.end();
+            matches.next();
+        }
 
         if codepoint > 0x80 {
             result.push(std::char::from_u32(codepoint).unwrap_or(std::char::REPLACEMENT_CHARACTER));
         } else {
             result.push_str(full_escape_sequence);
         }
-
-        last_end = m.end();
     }
 
     result.push_str(&s[last_end..]);
@@ -102,4 +117,49 @@ pub fn capitalize(s: &str) -> String {
         None => String::new(),
         Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
     }
-}
\ No newline at end of file
+}
+/// Extracts the message of a panic payload caught by `std::panic::catch_unwind`.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Returns `true` if a combining mark in `s` follows one of a higher canonical
+/// combining class, so that `reorder_combining_marks` would change `s`.
+pub fn needs_mark_reordering(s: &str) -> bool {
+    let mut prev = 0;
+    s.chars().any(|c| {
+        let class = canonical_combining_class(c);
+        let out_of_order = class != 0 && prev > class;
+        prev = class;
+        out_of_order
+    })
+}
+
+/// Sorts each run of combining marks in `s` by canonical combining class, keeping the
+/// order of marks of the same class, as in the canonical ordering of Unicode.
+///
+/// A base letter with several marks thus has a single spelling, whatever the order the
+/// marks were typed in (e.g., a Hebrew letter with a dagesh and a vowel point).
+pub fn reorder_combining_marks(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut marks: Vec<(u8, char)> = Vec::new();
+    for c in s.chars() {
+        let class = canonical_combining_class(c);
+        if class == 0 {
+            marks.sort_by_key(|&(class, _)| class);
+            result.extend(marks.drain(..).map(|(_, mark)| mark));
+            result.push(c);
+        } else {
+            marks.push((class, c));
+        }
+    }
+    marks.sort_by_key(|&(class, _)| class);
+    result.extend(marks.into_iter().map(|(_, mark)| mark));
+    result
+}
@@ -0,0 +1,162 @@
+//! Checks custom rules for `Uroman::validate_rules_str` without adding them.
+
+use std::fmt;
+
+use crate::core::UromanInner;
+use crate::rom_rule::RomRule;
+
+/// A likely mistake in a file of custom rules, as found by `Uroman::validate_rules_file`.
+///
+/// Lines are numbered from 1, counting empty lines and comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleWarning {
+    /// The rule repeats an earlier rule for the same source, languages and target.
+    DuplicateRule {
+        line: usize,
+        first_line: usize,
+        source: String,
+    },
+    /// The rule gives the source of an earlier rule for the same languages another
+    /// target, so only one of the rules is applied.
+    ConflictingRule {
+        line: usize,
+        first_line: usize,
+        source: String,
+    },
+    /// The source of the rule starts with the end of the source of another rule, so the
+    /// rules compete for the characters they share (e.g., `ab` and `bc` in `abc`).
+    OverlappingSources {
+        line: usize,
+        other_line: usize,
+        source: String,
+        other_source: String,
+    },
+    /// An `::lcode` of the rule is not a known ISO 639-3 code, so the rule never applies.
+    UnknownLanguage { line: usize, lcode: String },
+    /// The rule has neither a `::t` nor a `::num` slot, so it romanizes nothing.
+    MissingTarget { line: usize, source: String },
+}
+
+impl fmt::Display for RuleWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleWarning::DuplicateRule {
+                line,
+                first_line,
+                source,
+            } => {
+                write!(
+                    f,
+                    "Line {line} repeats the rule for '{source}' on line {first_line}"
+                )
+            }
+            RuleWarning::ConflictingRule {
+                line,
+                first_line,
+                source,
+            } => write!(
+                f,
+                "Line {line} conflicts with the rule for '{source}' on line {first_line}"
+            ),
+            RuleWarning::OverlappingSources {
+                line,
+                other_line,
+                source,
+                other_source,
+            } => write!(
+                f,
+                "Line {line}: '{source}' overlaps with '{other_source}' on line {other_line}"
+            ),
+            RuleWarning::UnknownLanguage { line, lcode } => {
+                write!(f, "Line {line}: unknown language code '{lcode}'")
+            }
+            RuleWarning::MissingTarget { line, source } => {
+                write!(f, "Line {line}: the rule for '{source}' has no target")
+            }
+        }
+    }
+}
+
+/// Returns `true` if `a` and `b` can apply to the same text, as their restrictions to
+/// languages and to positions in a word allow.
+fn same_scope(a: &RomRule, b: &RomRule) -> bool {
+    let languages_overlap = (a.lcodes.is_empty() && b.lcodes.is_empty())
+        || a.lcodes.iter().any(|lcode| b.lcodes.contains(lcode));
+    languages_overlap
+        && a.use_only_at_start_of_word == b.use_only_at_start_of_word
+        && a.dont_use_at_start_of_word == b.dont_use_at_start_of_word
+        && a.use_only_at_end_of_word == b.use_only_at_end_of_word
+        && a.dont_use_at_end_of_word == b.dont_use_at_end_of_word
+        && a.use_only_for_whole_word == b.use_only_for_whole_word
+}
+
+/// Returns `true` if a proper suffix of `a` is a proper prefix of `b`.
+fn overlaps(a: &str, b: &str) -> bool {
+    if a.contains(b) || b.contains(a) {
+        return false;
+    }
+    a.char_indices()
+        .skip(1)
+        .any(|(i, _)| b.starts_with(&a[i..]))
+}
+
+/// Parses `rules` as `add_user_rules` does and returns the warnings for them, sorted by
+/// line, or the number of the first line that is not a rule.
+pub(crate) fn validate_rules(uroman: &UromanInner, rules: &str) -> Result<Vec<RuleWarning>, usize> {
+    let parsed = uroman.parse_user_rules(rules)?;
+
+    let mut warnings = Vec::new();
+    for (i, (line, rule)) in parsed.iter().enumerate() {
+        let line = *line;
+        for lcode in rule
+            .lcodes
+            .iter()
+            .filter(|lcode| !uroman.is_known_language(lcode))
+        {
+            warnings.push(RuleWarning::UnknownLanguage {
+                line,
+                lcode: lcode.clone(),
+            });
+        }
+        if rule.t.is_none() && rule.num.is_none() {
+            warnings.push(RuleWarning::MissingTarget {
+                line,
+                source: rule.s.clone(),
+            });
+        }
+
+        let earlier = parsed[..i]
+            .iter()
+            .find(|(_, other)| other.s == rule.s && same_scope(rule, other));
+        if let Some((first_line, other)) = earlier {
+            let (first_line, source) = (*first_line, rule.s.clone());
+            warnings.push(if other.t == rule.t && other.t_alts == rule.t_alts {
+                RuleWarning::DuplicateRule {
+                    line,
+                    first_line,
+                    source,
+                }
+            } else {
+                RuleWarning::ConflictingRule {
+                    line,
+                    first_line,
+                    source,
+                }
+            });
+        }
+
+        // Each overlapping source is reported once, with its first rule.
+        for (j, (other_line, other)) in parsed[..i].iter().enumerate() {
+            let (a, b) = (&other.s, &rule.s);
+            if (overlaps(a, b) || overlaps(b, a)) && !parsed[..j].iter().any(|(_, r)| r.s == *a) {
+                warnings.push(RuleWarning::OverlappingSources {
+                    line,
+                    other_line: *other_line,
+                    source: rule.s.clone(),
+                    other_source: other.s.clone(),
+                });
+            }
+        }
+    }
+    Ok(warnings)
+}
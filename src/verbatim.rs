@@ -0,0 +1,42 @@
+//! Passes URLs, email addresses and @mentions through verbatim.
+//!
+//! Each such token is covered by a single edge of the type `verbatim`, whose text is the
+//! token itself, so that links stay clickable and handles resolvable.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::edge::Edge;
+use crate::lattice::Lattice;
+
+/// URLs, email addresses and @mentions, tried in this order at each position.
+static TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:[a-zA-Z][a-zA-Z0-9+.-]*://|www\.)[^\s<>"]+|[\w.+-]+@[\w-]+(?:\.[\w-]+)+|@\w+"#)
+        .unwrap()
+});
+
+/// Punctuation that ends a sentence rather than a URL when it is the last character.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '\'', '"'];
+
+impl Lattice<'_> {
+    /// Replaces the edges of every URL, email address and @mention with a `verbatim` edge.
+    ///
+    /// A mention must not follow a letter or digit, so that `a@b` is left alone.
+    pub fn add_verbatim_tokens(&mut self) {
+        let s: String = self.s_chars.iter().collect();
+        let mut spans = Vec::new();
+        for m in TOKEN_RE.find_iter(&s) {
+            let token = m.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+            let start = s[..m.start()].chars().count();
+            if token.starts_with('@') && start > 0 && self.s_chars[start - 1].is_alphanumeric() {
+                continue;
+            }
+            spans.push((start, start + token.chars().count(), token.to_string()));
+        }
+
+        for (start, end, token) in spans {
+            let edge = Edge::new_regular(start, end, token, "verbatim".to_string());
+            self.replace_edges_in_span(start, end, vec![edge]);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! WebAssembly bindings for uroman-rs using wasm-bindgen.
+//!
+//! The romanization data is embedded in the binary, so a `WasmUroman` is ready to use
+//! as soon as the module is instantiated, without fetching any files. Build without the
+//! default features, which pull in the CLI and rayon:
+//!
+//! ```bash
+//! cargo build --release --target wasm32-unknown-unknown --no-default-features --features wasm,all-languages
+//! ```
+//!
+//! # Example
+//! ```js
+//! import { WasmUroman } from "./uroman.js";
+//!
+//! const uroman = new WasmUroman();
+//! console.log(uroman.romanize("Київ", "ukr")); // Kyiv
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Uroman, rom_format};
+
+/// JavaScript wrapper for the Uroman struct.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct WasmUroman {
+    inner: Uroman,
+}
+
+#[wasm_bindgen]
+impl WasmUroman {
+    /// Creates a new Uroman instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: Uroman::new(),
+        }
+    }
+
+    /// Romanizes `text`, optionally for the ISO 639-3 language code `lcode`.
+    pub fn romanize(&self, text: &str, lcode: Option<String>) -> String {
+        self.inner
+            .romanize_string::<rom_format::Str>(text, lcode.as_deref())
+            .to_string()
+    }
+}
+
+impl Default for WasmUroman {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -72,6 +72,28 @@ def test_edges_format():
     assert hasattr(edges[0], "end"), "Edge should have 'end' attribute"
 
 
+def test_alts_format():
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    edges = uroman.romanize("chromosome", format="alts")
+
+    assert edges[0].text == "chr", f"Expected 'chr', got '{edges[0].text}'"
+    assert edges[0].alternatives == ["kr"], f"Expected ['kr'], got {edges[0].alternatives}"
+    assert all(edge.edge_type != "rom-alt" for edge in edges), "Alternatives should not be edges"
+
+
+def test_lattice_format():
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    nodes = uroman.romanize("しし", lcode="jpn", format="lattice_nodes")
+
+    assert [node.position for node in nodes] == [0, 1, 2], "Expected a node for each position"
+    assert any(edge.text == "shi" and edge.end == 1 for edge in nodes[0].edges)
+    assert all(0.0 < edge.score <= 1.0 for node in nodes for edge in node.edges)
+
+
 def test_multiline():
     from uroman_rs import Uroman
 
@@ -82,6 +104,107 @@ def test_multiline():
     assert len(lines) == 2, f"Expected 2 lines, got {len(lines)}"
 
 
+def test_romanize_batch():
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    texts = ["Київ", "Львів", ""]
+    assert uroman.romanize_batch(texts, lcode="ukr") == ["Kyiv", "Lviv", ""]
+    assert uroman.romanize_batch(texts) == [uroman.romanize(text) for text in texts]
+
+
+def test_pickle():
+    import pickle
+
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    restored = pickle.loads(pickle.dumps(uroman))
+    assert restored.romanize("Київ", lcode="ukr") == "Kyiv"
+    assert Uroman.from_compiled_bytes(uroman.to_compiled_bytes()).romanize("ᚺᚨᛚᛚᛟ") == "hallo"
+
+
+def test_romanize_file(tmp_path):
+    import pytest
+
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    input_path, output_path = tmp_path / "in.txt", tmp_path / "out.txt"
+    input_path.write_text("Київ\nЛьвів\n", encoding="utf-8")
+
+    uroman.romanize_file(input_path, output_path, lcode="ukr")
+    assert output_path.read_text(encoding="utf-8") == "Kyiv\nLviv\n"
+
+    with pytest.raises(OSError, match="missing.txt"):
+        uroman.romanize_file(tmp_path / "missing.txt", output_path)
+
+
+def test_edge_protocols():
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    edges = uroman.romanize("Київ", lcode="ukr", format="edges")
+    start, end, text = edges[0]
+    assert (start, end, text) == (edges[0].start, edges[0].end, edges[0].text)
+    assert len(edges[0]) == 3 and edges[0][-1] == edges[0].text
+
+    again = uroman.romanize("Київ", lcode="ukr", format="edges")
+    assert edges == again
+    assert len(set(edges) | set(again)) == len(edges)
+
+
+def test_romanize_iter():
+    import pytest
+
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    lines = uroman.romanize_iter(iter(["Київ\n", "Львів"]), lcode="ukr")
+    assert next(lines) == "Kyiv"
+    assert list(lines) == ["Lviv"]
+
+    lines = uroman.romanize_iter(["Київ", 42])
+    next(lines)
+    with pytest.raises(TypeError):
+        next(lines)
+
+
+
+def test_romanize_with_offsets():
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    assert uroman.romanize_with_offsets("Ж北") == ("Zhbei", [(0, 2, 0, 2), (2, 5, 2, 5)])
+
+    text = "Ʒ Київ"
+    romanized, spans = uroman.romanize_with_offsets(text, lcode="ukr", codepoints=True)
+    assert romanized == uroman.romanize(text, lcode="ukr")
+    assert "".join(romanized[o0:o1] for _, _, o0, o1 in spans) == romanized
+    assert "".join(text[s0:s1] for s0, s1, _, _ in spans) == text
+
+    # The marks are reordered before romanization, but the spans refer to `text`.
+    text = "a\u0301\u0323Ж"
+    romanized, spans = uroman.romanize_with_offsets(text, codepoints=True)
+    assert romanized == uroman.romanize(text)
+    assert "".join(text[s0:s1] for s0, s1, _, _ in spans) == text
+    assert spans[-1] == (3, 4, len(romanized) - 2, len(romanized))
+
+def test_supported_languages():
+    import json
+
+    from uroman_rs import Uroman
+
+    uroman = Uroman()
+    languages = uroman.supported_languages()
+    ukr = next(lang for lang in languages if lang["code"] == "ukr")
+    assert ukr == {"code": "ukr", "name": "Ukrainian", "scripts": ["Cyrillic"]}
+    assert [lang["code"] for lang in languages] == sorted(lang["code"] for lang in languages)
+    assert json.loads(json.dumps(languages)) == languages
+
+    assert uroman.is_language_supported("ukr")
+    assert not uroman.is_language_supported("xxx")
+
 def test_comparison():
     import uroman as ur
     import uroman_rs
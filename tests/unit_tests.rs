@@ -480,3 +480,2554 @@ fn test_generic_tifinagh() {
         "amagrad 1 ar d ttlalan middn gan ilellitn mgaddan gh waddur d izrfan, yili ak darsn unlli d ufrak, illa flla sn ad ttmyawasn ngratsn s tagmat.",
     );
 }
+
+#[test]
+fn test_builder() {
+    let uroman = Uroman::builder().build().unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kyiv");
+
+    let uroman = Uroman::builder()
+        .with_languages(&["ukr"])
+        .case_sensitive(false)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "kyiv");
+
+    let uroman = Uroman::builder().apostrophe('ʼ').build().unwrap();
+    assert_romanizes_to_str(&uroman, "Ӏ", None, "ʼ");
+    assert_romanizes_to_str(&uroman, "don't", None, "don't");
+}
+
+#[test]
+fn test_builder_unknown_language() {
+    let result = Uroman::builder().with_languages(&["jpn", "xx1"]).build();
+    assert_eq!(
+        result.unwrap_err(),
+        uroman::BuildError::UnknownLanguage("xx1".to_string())
+    );
+}
+
+#[test]
+fn test_romanize_cow() {
+    use std::borrow::Cow;
+
+    let uroman = Uroman::new();
+    assert!(matches!(uroman.romanize_cow("", None), Cow::Borrowed("")));
+    assert!(matches!(
+        uroman.romanize_cow("Hello, world 123!", None),
+        Cow::Borrowed("Hello, world 123!")
+    ));
+
+    let result = uroman.romanize_cow("Hello, Київ", Some("ukr"));
+    assert!(matches!(result, Cow::Owned(_)));
+    assert_eq!(result, "Hello, Kyiv");
+
+    // ASCII text is borrowed unless an option changes it.
+    let lowercase = Uroman::builder().case_sensitive(false).build().unwrap();
+    assert_eq!(lowercase.romanize_cow("Hello", None), "hello");
+    let limited = Uroman::builder().max_expansion_ratio(0.5).build().unwrap();
+    assert_eq!(limited.romanize_cow("Hello", None), "Hel");
+}
+
+#[test]
+fn test_try_romanize() {
+    use uroman::RomanizationError;
+
+    let uroman = Uroman::new();
+    assert_eq!(uroman.try_romanize("ᚺᚨᛚᛚᛟ €5", None).unwrap(), "hallo €5");
+    assert_eq!(uroman.try_romanize("̈", None).unwrap(), "");
+    assert!(matches!(
+        uroman.try_romanize("ab 𠀀𠀀 c𠀀", None),
+        Err(RomanizationError::UnromanizableSpan { start: 3, end: 11 })
+    ));
+
+    // Private-use and unassigned characters are not dropped silently.
+    assert!(matches!(
+        uroman.try_romanize("a\u{E000}b", None),
+        Err(RomanizationError::UnromanizableSpan { start: 1, end: 4 })
+    ));
+    assert!(matches!(
+        uroman.try_romanize("Ж\u{0378}", None),
+        Err(RomanizationError::UnromanizableSpan { start: 2, end: 4 })
+    ));
+}
+
+#[test]
+fn test_romanize_with_diagnostics() {
+    let uroman = Uroman::new();
+
+    let (romanized, unmapped) = uroman.romanize_with_diagnostics("ᚺᚨᛚᛚᛟ, €5", None);
+    assert_eq!(romanized, "hallo, €5");
+    assert!(unmapped.is_empty());
+
+    let (romanized, unmapped) = uroman.romanize_with_diagnostics("a𠀀b𠀂", None);
+    assert_eq!(romanized, "a𠀀b𠀂");
+    let offsets: Vec<(char, usize)> = unmapped.iter().map(|u| (u.char, u.byte_offset)).collect();
+    assert_eq!(offsets, vec![('𠀀', 1), ('𠀂', 6)]);
+
+    let (romanized, unmapped) = uroman.romanize_with_diagnostics("a\u{E000}\u{0378}", None);
+    assert_eq!(romanized, "a\u{E000}\u{0378}");
+    let offsets: Vec<(char, usize)> = unmapped.iter().map(|u| (u.char, u.byte_offset)).collect();
+    assert_eq!(offsets, vec![('\u{E000}', 1), ('\u{0378}', 4)]);
+}
+
+#[test]
+fn test_romanize_batch() {
+    let uroman = Uroman::new();
+    let texts: Vec<&str> = ["ᚺᚨᛚᛚᛟ", "Київ", "", "こんにちは"].repeat(50);
+
+    let expected: Vec<String> = texts
+        .iter()
+        .map(|s| uroman.romanize_string::<rom_format::Str>(s, None).to_string())
+        .collect();
+    assert_eq!(uroman.romanize_batch(&texts, None), expected);
+
+    let lcodes: Vec<Option<&str>> = texts.iter().map(|_| Some("ukr")).collect();
+    let result = uroman.romanize_batch_with_lcodes(&texts, &lcodes);
+    assert_eq!(result[1], "Kyiv");
+    assert_eq!(result.len(), texts.len());
+}
+
+#[test]
+fn test_compiled_bytes_roundtrip() {
+    use uroman::BuildError;
+
+    let uroman = Uroman::builder().case_sensitive(false).build().unwrap();
+    let bytes = uroman.to_compiled_bytes();
+    let loaded = Uroman::from_compiled_bytes(&bytes).unwrap();
+    assert_romanizes_to_str(&loaded, "Київ", Some("ukr"), "kyiv");
+    assert_romanizes_to_str(&loaded, "こんにちは", None, "konnichiha");
+
+    assert!(matches!(
+        Uroman::from_compiled_bytes(b"not compiled"),
+        Err(BuildError::InvalidCompiledData(_))
+    ));
+
+    let mut stale = bytes.clone();
+    stale[8] = stale[8].wrapping_add(1);
+    assert!(matches!(
+        Uroman::from_compiled_bytes(&stale),
+        Err(BuildError::CompiledVersionMismatch { .. })
+    ));
+
+    assert!(matches!(
+        Uroman::from_compiled_bytes(&bytes[..bytes.len() / 2]),
+        Err(BuildError::InvalidCompiledData(_))
+    ));
+}
+
+#[test]
+fn test_edge_serialization() {
+    let uroman = Uroman::new();
+    let edges = uroman.romanize_string::<rom_format::Edges>("К 12", None).to_edges();
+    let json = serde_json::to_value(&edges).unwrap();
+
+    assert_eq!(
+        json[0],
+        serde_json::json!({
+            "start": 0, "end": 1, "txt": "K", "type": "rom",
+            "is_numeric": false, "value": null, "orig_txt": null,
+        })
+    );
+    assert_eq!(
+        json[2],
+        serde_json::json!({
+            "start": 2, "end": 4, "txt": "12", "type": "D1",
+            "is_numeric": true, "value": 12.0, "orig_txt": "12",
+        })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_edge_deserialization() {
+    use uroman::{Edge, RomFormat, RomanizationResult};
+
+    let uroman = Uroman::new();
+    let edges = uroman.romanize_string::<rom_format::Edges>("К 12", None).to_edges();
+    let json = serde_json::to_string(&edges).unwrap();
+    let deserialized: Vec<Edge> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, edges);
+    assert_eq!(deserialized[2].value(), Some(12.0));
+    assert_eq!(deserialized[2].orig_txt(), "12");
+
+    let result = uroman.romanize_with_format("К 12", None, Some(RomFormat::Edges));
+    let json = serde_json::to_string(&result).unwrap();
+    let deserialized: RomanizationResult = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, result);
+}
+
+#[test]
+fn test_json_format() {
+    use uroman::RomFormat;
+
+    let uroman = Uroman::new();
+    let result = uroman.romanize_string::<rom_format::Json>("a𝔸Ж", None);
+    let json: serde_json::Value = serde_json::from_str(result.as_str()).unwrap();
+
+    let edge = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|edge| edge["start"] == 2)
+        .unwrap();
+    assert_eq!(edge["end"], 3);
+    assert_eq!(edge["byte_start"], 5);
+    assert_eq!(edge["byte_end"], 7);
+    assert_eq!(edge["utf16_start"], 3);
+    assert_eq!(edge["utf16_end"], 4);
+    assert_eq!(edge["txt"], "Zh");
+    assert!(edge["alternatives"].is_array());
+
+    let result = uroman.romanize_with_format("a𝔸Ж", None, Some(RomFormat::Json));
+    assert_eq!(result.to_string().unwrap(), uroman.romanize_string::<rom_format::Json>("a𝔸Ж", None).to_string());
+}
+
+#[test]
+fn test_romanize_with_alignment() {
+    let uroman = Uroman::new();
+
+    // One source character expands to several output characters.
+    let (romanized, alignment) = uroman.romanize_with_alignment("a北", None);
+    assert_eq!(romanized, "abei");
+    assert_eq!(alignment, vec![(0..1, 0..1), (1..4, 1..4)]);
+
+    // Several source characters collapse into a single edge.
+    let (romanized, alignment) = uroman.romanize_with_alignment("a北京", None);
+    assert_eq!(romanized, "abeijing");
+    assert_eq!(alignment, vec![(0..1, 0..1), (1..7, 1..8)]);
+
+    let (romanized, alignment) = uroman.romanize_with_alignment("", None);
+    assert_eq!(romanized, "");
+    assert!(alignment.is_empty());
+}
+
+#[test]
+fn test_edges_matching() {
+    let uroman = Uroman::new();
+
+    let result = uroman.romanize_string::<rom_format::Edges>("北京 北", None);
+    let ranges: Vec<_> = result
+        .edges_matching("bei")
+        .iter()
+        .map(|edge| edge.source_range())
+        .collect();
+    assert_eq!(ranges, vec![3..4]);
+    assert!(result.edges_matching("xyz").is_empty());
+
+    let result = uroman.romanize_string::<rom_format::Lattice>("北京", None);
+    let ranges: Vec<_> = result
+        .edges_matching("bei")
+        .iter()
+        .map(|edge| edge.source_range())
+        .collect();
+    assert_eq!(ranges, vec![0..1]);
+}
+
+#[test]
+fn test_romanize_lines_matches_romanize_file() {
+    use uroman::RomFormat;
+
+    let uroman = Uroman::new();
+    let input = "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ  \r\n\n::lcode ukr Київ\nこんにちは";
+
+    let lines: Vec<String> = uroman
+        .romanize_lines(input.as_bytes(), None)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines, vec!["hallo world  ", "", "::lcode ukr Kyiv", "konnichiha"]);
+
+    let mut output = Vec::new();
+    uroman
+        .romanize_file(input.as_bytes(), &mut output, None, RomFormat::Str, None, false, true)
+        .unwrap();
+    let file_lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+    assert_eq!(lines, file_lines);
+}
+
+#[test]
+fn test_token_cache() {
+    use uroman::CacheStats;
+
+    assert_eq!(Uroman::new().cache_stats(), None);
+
+    let uroman = Uroman::with_cache(2);
+    assert_romanizes_to_str(&uroman, "ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", None, "hallo world");
+    assert_eq!(uroman.cache_stats(), Some(CacheStats { hits: 0, misses: 3 }));
+
+    // "ᚹᛟᚱᛚᛞ" and " " are still cached; "ᚺᚨᛚᛚᛟ" was evicted.
+    assert_romanizes_to_str(&uroman, "ᚹᛟᚱᛚᛞ ᚹᛟᚱᛚᛞ\tᚺᚨᛚᛚᛟ", None, "world world\thallo");
+    assert_eq!(uroman.cache_stats(), Some(CacheStats { hits: 3, misses: 5 }));
+
+    // The cache is keyed by language code, too.
+    let uroman = Uroman::builder().cache_capacity(10).build().unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", None, "Kiyiv");
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kyiv");
+    assert_eq!(uroman.cache_stats(), Some(CacheStats { hits: 0, misses: 2 }));
+}
+
+#[test]
+fn test_detect_script() {
+    use uroman::Script;
+
+    assert!(Uroman::detect_script("  ").is_empty());
+    assert_eq!(Uroman::detect_script("Київ"), vec![(Script::Cyrillic, 1.0)]);
+    assert_eq!(
+        Uroman::detect_script("abc 日本 12"),
+        vec![(Script::Latin, 3.0 / 7.0), (Script::Han, 2.0 / 7.0), (Script::Common, 2.0 / 7.0)]
+    );
+    assert_eq!(Uroman::detect_script("ᚺᚨᛚᛚᛟ")[0].0, Script::Other("Runic".to_string()));
+    assert_eq!(Script::from_name("CJK"), Script::Han);
+    assert_eq!(Script::Han.name(), "CJK");
+}
+
+#[test]
+fn test_guess_lcode() {
+    let guess = |s| Uroman::guess_lcode(s);
+
+    assert_eq!(guess("こんにちは世界").as_deref(), Some("jpn"));
+    assert_eq!(guess("안녕하세요").as_deref(), Some("kor"));
+    assert_eq!(guess("北京欢迎你").as_deref(), Some("zho"));
+    assert_eq!(guess("Καλημέρα").as_deref(), Some("ell"));
+    assert_eq!(guess("Слава Україні").as_deref(), Some("ukr"));
+    assert_eq!(guess("Привет").as_deref(), None);
+    assert_eq!(guess("مرحبا").as_deref(), None);
+    assert_eq!(guess("Hello, world!"), None);
+    assert_eq!(guess("123 !"), None);
+}
+
+#[test]
+fn test_romanize_segments() {
+    let uroman = Uroman::new();
+
+    assert_eq!(uroman.romanize_segments("", None), "");
+    assert_eq!(
+        uroman.romanize_segments("東京へ  Київ,\u{3000}Hello!", None),
+        "dongjinghe  Kyiv,\u{3000}Hello!"
+    );
+    assert_eq!(uroman.romanize_segments(" Привет ", Some("ukr")), " Pryvet ");
+
+    assert_eq!(
+        uroman.romanize_segments_with_lcodes(
+            "Київ  Київ Київ",
+            &[(0..8, "ukr"), (19..27, "ukr")],
+            None,
+        ),
+        "Kyiv  Kiyiv Kyiv"
+    );
+}
+
+#[test]
+fn test_supported_languages() {
+    use uroman::Script;
+
+    let uroman = Uroman::new();
+    let languages = uroman.supported_languages();
+    assert!(languages.len() > 300);
+    assert!(uroman.is_language_supported("jpn"));
+    assert!(!uroman.is_language_supported("xx1"));
+
+    let ukr = languages.iter().find(|lang| lang.code == "ukr").unwrap();
+    assert_eq!(ukr.name, "Ukrainian");
+    assert_eq!(ukr.scripts, vec![Script::Cyrillic]);
+    let jpn = languages.iter().find(|lang| lang.code == "jpn").unwrap();
+    assert_eq!(jpn.scripts, vec![Script::Hiragana, Script::Katakana, Script::Han]);
+
+    let uroman = Uroman::builder().with_languages(&["ukr", "jpn"]).build().unwrap();
+    let codes: Vec<String> = uroman.supported_languages().into_iter().map(|lang| lang.code).collect();
+    assert_eq!(codes, vec!["jpn", "ukr"]);
+    assert!(!uroman.is_language_supported("rus"));
+}
+
+#[test]
+#[cfg(not(feature = "lang-cyrillic"))]
+fn test_languages_of_disabled_features() {
+    use uroman::BuildError;
+
+    let uroman = Uroman::new();
+    assert!(!uroman.is_language_supported("rus"));
+    assert!(!uroman.supported_languages().iter().any(|lang| lang.code == "ukr"));
+    assert!(uroman.is_language_supported("eng"));
+    assert!(matches!(
+        Uroman::builder().with_languages(&["rus"]).build(),
+        Err(BuildError::UnknownLanguage(lcode)) if lcode == "rus"
+    ));
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "lang-zho",
+    feature = "lang-jpn",
+    feature = "lang-kor",
+    feature = "lang-ara",
+    feature = "lang-cyrillic",
+    feature = "lang-indic"
+)))]
+fn test_build_without_languages() {
+    // The scripts outside the `lang-*` features keep their rules.
+    let uroman = Uroman::new();
+    assert_romanizes_to_str(&uroman, "Αθήνα", Some("ell"), "Athena");
+    assert_romanizes_to_str(&uroman, "ᚺᚨᛚᛚᛟ", None, "hallo");
+    assert_romanizes_to_str(&uroman, "Hello, world!", None, "Hello, world!");
+
+    // The letters of the left-out scripts are passed through.
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Київ");
+    assert_romanizes_to_str(&uroman, "مصر", Some("ara"), "مصر");
+}
+
+#[test]
+fn test_ascii_fold() {
+    let uroman = Uroman::new();
+    assert_romanizes_to_str(&uroman, "ok\u{0F3E}", None, "ok\u{0F3E}");
+
+    let uroman = Uroman::builder().ascii_fold(true).build().unwrap();
+    assert_romanizes_to_str(&uroman, "ok\u{0F3E}", None, "ok");
+    assert_romanizes_to_str(&uroman, "ñandú", None, "nandu");
+    assert_romanizes_to_str(&uroman, "΅ €—", None, "΅ €—");
+}
+
+#[test]
+fn test_preserve_case() {
+    let uroman = Uroman::new();
+    assert_romanizes_to_str(&uroman, "Θεός", None, "THeos");
+
+    let uroman = Uroman::builder().preserve_case(true).build().unwrap();
+    assert_romanizes_to_str(&uroman, "Θεός", None, "Theos");
+    assert_romanizes_to_str(&uroman, "ΘΕΟΣ", None, "ThEOS");
+    assert_romanizes_to_str(&uroman, "Ꜳ", None, "Aa");
+    assert_romanizes_to_str(&uroman, "東京", None, "dongjing");
+
+    let uroman = Uroman::builder()
+        .preserve_case(true)
+        .case_sensitive(false)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "Москва", None, "Moskva");
+}
+
+#[test]
+fn test_normalization() {
+    use uroman::Normalization;
+
+    let decomposed = "\u{1100}\u{1161}";
+
+    let uroman = Uroman::new();
+    assert_romanizes_to_str(&uroman, decomposed, None, "ᄀa");
+
+    let uroman = Uroman::builder()
+        .normalization(Normalization::Nfc)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, decomposed, None, "ga");
+    assert_eq!(
+        uroman.romanize_with_alignment("и\u{0306}a", None),
+        ("ia".to_string(), vec![(0..2, 0..1), (2..3, 1..2)])
+    );
+
+    let uroman = Uroman::builder()
+        .normalization(Normalization::Nfd)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "가", None, "ᄀa");
+}
+
+#[test]
+fn test_normalize_fullwidth() {
+    let uroman = Uroman::new();
+    assert_romanizes_to_str(&uroman, "ｶﾞｷﾞ", Some("jpn"), "kaki");
+
+    let uroman = Uroman::builder().normalize_fullwidth(true).build().unwrap();
+    assert_romanizes_to_str(&uroman, "ｶﾞｷﾞ", Some("jpn"), "gagi");
+    assert_romanizes_to_str(&uroman, "ﾊﾟﾝ", Some("jpn"), "pan");
+    assert_romanizes_to_str(&uroman, "ＡＢＣ！？（）", None, "ABC!?()");
+
+    let edges = uroman
+        .romanize_string::<rom_format::Edges>("１２", None)
+        .to_edges();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].value(), Some(12.0));
+    assert_eq!(edges[0].source_range(), 0..2);
+}
+
+#[test]
+fn test_japanese_scheme() {
+    use uroman::JapaneseScheme;
+
+    let hepburn = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Hepburn)
+        .build()
+        .unwrap();
+    let kunrei = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Kunrei)
+        .build()
+        .unwrap();
+    let wapuro = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Wapuro)
+        .build()
+        .unwrap();
+
+    // (input, Hepburn, Kunrei, Wapuro)
+    let cases = [
+        ("しゃしん", "shashin", "syasin", "shashinn"),
+        ("ちぢむ", "chijimu", "tizimu", "chidimu"),
+        ("つづく", "tsuzuku", "tuzuku", "tsuduku"),
+        ("ふじ", "fuji", "huzi", "fuji"),
+        ("まっちゃ", "matcha", "mattya", "matcha"),
+        ("をかし", "okashi", "okasi", "wokashi"),
+        ("きんえん", "kin'en", "kin'en", "kinnenn"),
+        ("しんよう", "shin'yō", "sin'yô", "shinnyou"),
+        ("とうきょう", "tōkyō", "tôkyô", "toukyou"),
+        ("ラーメン", "rāmen", "râmen", "ra-menn"),
+    ];
+    for (input, expected_hepburn, expected_kunrei, expected_wapuro) in cases {
+        assert_romanizes_to_str(&hepburn, input, Some("jpn"), expected_hepburn);
+        assert_romanizes_to_str(&kunrei, input, Some("jpn"), expected_kunrei);
+        assert_romanizes_to_str(&wapuro, input, Some("jpn"), expected_wapuro);
+    }
+
+    // The scheme only applies to `jpn`.
+    assert_romanizes_to_str(&kunrei, "しゃしん", None, "shashin");
+}
+
+#[test]
+fn test_long_vowels() {
+    use uroman::{JapaneseScheme, LongVowelStyle};
+
+    let uroman = Uroman::builder()
+        .long_vowels(LongVowelStyle::Macron)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "コーヒー", Some("jpn"), "kōhī");
+    assert_romanizes_to_str(&uroman, "おおさか", Some("jpn"), "ōsaka");
+    assert_romanizes_to_str(&uroman, "とうきょう", Some("jpn"), "tōkyō");
+
+    let uroman = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Kunrei)
+        .long_vowels(LongVowelStyle::Macron)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "とうきょう", Some("jpn"), "tōkyō");
+
+    let uroman = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Hepburn)
+        .long_vowels(LongVowelStyle::Circumflex)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "コーヒー", Some("jpn"), "kôhî");
+
+    let uroman = Uroman::builder()
+        .japanese_scheme(JapaneseScheme::Hepburn)
+        .long_vowels(LongVowelStyle::Literal)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "コーヒー", Some("jpn"), "koohii");
+    assert_romanizes_to_str(&uroman, "とうきょう", Some("jpn"), "toukyou");
+}
+
+#[test]
+fn test_korean_scheme() {
+    use uroman::KoreanScheme;
+
+    let rr = Uroman::builder()
+        .korean_scheme(KoreanScheme::RevisedRomanization)
+        .build()
+        .unwrap();
+    let mr = Uroman::builder()
+        .korean_scheme(KoreanScheme::McCuneReischauer)
+        .build()
+        .unwrap();
+
+    // (input, Revised Romanization, McCune-Reischauer)
+    let cases = [
+        ("부산", "busan", "pusan"),
+        ("대구", "daegu", "taegu"),
+        ("한국", "hanguk", "han'guk"),
+        ("서울", "seoul", "sŏul"),
+        ("김치", "gimchi", "kimch'i"),
+        ("학교", "hakgyo", "hakkyo"),
+        ("독립문", "dongnimmun", "tongnimmun"),
+        ("종로", "jongno", "chongno"),
+        ("신라", "silla", "silla"),
+        ("같이", "gachi", "kach'i"),
+        ("좋다", "jota", "chot'a"),
+        ("평양", "pyeongyang", "p'yŏngyang"),
+    ];
+    for (input, expected_rr, expected_mr) in cases {
+        assert_romanizes_to_str(&rr, input, Some("kor"), expected_rr);
+        assert_romanizes_to_str(&mr, input, Some("kor"), expected_mr);
+    }
+
+    // The scheme only applies to `kor`.
+    assert_romanizes_to_str(&mr, "독립문", None, "dogribmun");
+}
+
+#[test]
+fn test_chinese_tones() {
+    use uroman::ChineseTones;
+
+    let marks = Uroman::builder()
+        .chinese_tones(ChineseTones::Marks)
+        .build()
+        .unwrap();
+    let numbers = Uroman::builder()
+        .chinese_tones(ChineseTones::Numbers)
+        .build()
+        .unwrap();
+
+    // (input, tone marks, tone numbers)
+    let cases = [
+        ("中国", "zhōngguó", "zhong1guo2"),
+        ("北京", "běijīng", "bei3jing1"),
+        ("你好", "nǐhǎo", "ni3hao3"),
+        ("学生", "xuéshēng", "xue2sheng1"),
+        ("绿色", "lǜsè", "lv4se4"),
+        ("天安门", "tiān'ānmén", "tian1'an1men2"),
+    ];
+    for (input, expected_marks, expected_numbers) in cases {
+        assert_romanizes_to_str(&marks, input, Some("zho"), expected_marks);
+        assert_romanizes_to_str(&numbers, input, Some("zho"), expected_numbers);
+    }
+
+    // Numbers are kept, and other languages are unaffected.
+    assert_romanizes_to_str(&marks, "三百", Some("zho"), "300");
+    assert_romanizes_to_str(&marks, "中国", None, "zhongguo");
+}
+
+#[test]
+fn test_arabic_scheme() {
+    use uroman::ArabicScheme;
+
+    let default = Uroman::new();
+    let ala_lc = Uroman::builder()
+        .arabic_scheme(ArabicScheme::AlaLc)
+        .build()
+        .unwrap();
+
+    let cases = [
+        // The article is assimilated to sun letters, but not to moon letters.
+        ("الشَّمْس", "ash-shams"),
+        ("الرَّحْمٰن", "ar-raḥmān"),
+        ("القَمَر", "al-qamar"),
+        // Long vowels, tāʼ marbūṭah, hamza and shaddah.
+        ("مَدِينَة", "madīnah"),
+        ("سُؤَال", "suʼāl"),
+        ("مُحَمَّد", "muḥammad"),
+        ("كِتَابًا", "kitāban"),
+        ("بَيْت", "bayt"),
+    ];
+    for (input, expected) in cases {
+        assert_romanizes_to_str(&ala_lc, input, Some("ara"), expected);
+    }
+
+    // Without harakat, only the consonant skeleton is written.
+    assert_romanizes_to_str(&ala_lc, "الشمس", Some("ara"), "ash-shms");
+    assert_romanizes_to_str(&default, "الشمس", Some("ara"), "alshms");
+    assert_romanizes_to_str(&ala_lc, "الشمس", None, "alshms");
+}
+
+#[test]
+fn test_arabic_use_diacritics() {
+    let default = Uroman::new();
+    let diacritics = Uroman::builder()
+        .arabic_use_diacritics(true)
+        .build()
+        .unwrap();
+
+    let cases = [
+        ("كَتَبَ", "kataba", "kataba"),
+        ("مُحَمَّد", "muhamad", "muhammad"),
+        ("كِتَابٌ", "kitaab", "kitabun"),
+        ("مَدِينَةٌ", "madiynaa", "madinatun"),
+        ("مُوسَى", "muwsaa", "musa"),
+        // Without harakat, the consonant skeleton is kept.
+        ("كتب", "ktb", "ktb"),
+        ("مدينة", "mdyna", "mdyna"),
+    ];
+    for (input, expected_default, expected_diacritics) in cases {
+        assert_romanizes_to_str(&default, input, Some("ara"), expected_default);
+        assert_romanizes_to_str(&diacritics, input, Some("ara"), expected_diacritics);
+    }
+}
+
+#[test]
+fn test_cyrillic_scheme() {
+    use uroman::CyrillicScheme;
+
+    let iso9 = Uroman::builder()
+        .cyrillic_scheme(CyrillicScheme::Iso9)
+        .build()
+        .unwrap();
+    let bgn_pcgn = Uroman::builder()
+        .cyrillic_scheme(CyrillicScheme::BgnPcgn)
+        .build()
+        .unwrap();
+
+    let cases = [
+        ("жёлтый", "žëltyj", "zhëltyy"),
+        ("щука", "ŝuka", "shchuka"),
+        ("хлеб", "hleb", "khleb"),
+        ("ель", "elʹ", "yel’"),
+        ("съел", "sʺel", "s”yel"),
+        ("Жуков", "Žukov", "Zhukov"),
+    ];
+    for (input, expected_iso9, expected_bgn_pcgn) in cases {
+        assert_romanizes_to_str(&iso9, input, Some("rus"), expected_iso9);
+        assert_romanizes_to_str(&bgn_pcgn, input, Some("rus"), expected_bgn_pcgn);
+    }
+
+    // ISO 9 maps each letter to a distinct romanization, so that it can be reversed by
+    // matching the longest romanization of a letter.
+    // `ъ` and `ь` have no uppercase romanization.
+    let lowercase = "абвгґдѓђеёєжзѕиіїйјклљмнњопрстќћуўфхцчџшщъыьэюяѣѫ";
+    let uppercase = lowercase
+        .chars()
+        .filter(|c| !"ъь".contains(*c))
+        .flat_map(char::to_uppercase);
+    let mut reverse = std::collections::HashMap::new();
+    for c in lowercase.chars().chain(uppercase) {
+        let rom = iso9
+            .romanize_string::<rom_format::Str>(&c.to_string(), None)
+            .to_string();
+        assert!(
+            reverse.insert(rom.clone(), c).is_none(),
+            "ISO 9 romanization {rom:?} of {c:?} is ambiguous"
+        );
+    }
+
+    let text = "Щука съела жёлтую хурму. Єва і Ґанок з Љубљане.";
+    let romanized = iso9
+        .romanize_string::<rom_format::Str>(text, Some("rus"))
+        .to_string();
+    let rom_chars: Vec<char> = romanized.chars().collect();
+    let mut restored = String::new();
+    let mut i = 0;
+    while i < rom_chars.len() {
+        let (len, c) = (1..=rom_chars.len() - i)
+            .rev()
+            .find_map(|len| {
+                let candidate: String = rom_chars[i..i + len].iter().collect();
+                reverse.get(&candidate).map(|&c| (len, c))
+            })
+            .unwrap_or((1, rom_chars[i]));
+        restored.push(c);
+        i += len;
+    }
+    assert_eq!(restored, text);
+}
+
+#[test]
+fn test_indic_scheme() {
+    use uroman::IndicScheme;
+
+    let iast = Uroman::builder()
+        .indic_scheme(IndicScheme::Iast)
+        .build()
+        .unwrap();
+    let iso = Uroman::builder()
+        .indic_scheme(IndicScheme::Iso15919)
+        .build()
+        .unwrap();
+
+    let cases = [
+        // ऋ, anusvara and the length of ए and ओ differ between the schemes.
+        ("ऋषि", "ṛṣi", "r̥ṣi"),
+        ("संस्कृत", "saṃskṛta", "saṁskr̥ta"),
+        ("एक", "eka", "ēka"),
+        ("ओम्", "om", "ōm"),
+        ("वेद", "veda", "vēda"),
+        ("ॐ", "oṃ", "ōṁ"),
+        // Everything else is shared.
+        ("कृष्ण", "kṛṣṇa", "kr̥ṣṇa"),
+        ("दुःख", "duḥkha", "duḥkha"),
+        ("ज्ञान", "jñāna", "jñāna"),
+        ("हिन्दी", "hindī", "hindī"),
+        ("शिव", "śiva", "śiva"),
+        ("क़िला", "qilā", "qilā"),
+    ];
+    for (input, expected_iast, expected_iso) in cases {
+        assert_romanizes_to_str(&iast, input, Some("san"), expected_iast);
+        assert_romanizes_to_str(&iso, input, Some("san"), expected_iso);
+    }
+}
+
+#[test]
+fn test_tibetan_scheme() {
+    use uroman::TibetanScheme;
+
+    let wylie = Uroman::builder()
+        .tibetan_scheme(TibetanScheme::Wylie)
+        .build()
+        .unwrap();
+
+    // Spellings that the default romanization writes alike must have distinct Wylie
+    // transliterations, so that each can be read back into its Tibetan syllable.
+    let cases = [
+        ("བོད་", "bod"),
+        ("བཀྲ་ཤིས་བདེ་ལེགས།", "bkra shis bde legs/"),
+        ("སངས་རྒྱས", "sangs rgyas"),
+        ("བསྒྲུབས", "bsgrubs"),
+        ("དགའ", "dga'"),
+        ("དག", "dag"),
+        ("གཡག", "g.yag"),
+        ("གྱག", "gyag"),
+        ("ཧཱུྃ", "hU~M"),
+        ("ཨོཾ", "oM"),
+    ];
+    let mut seen = std::collections::HashSet::new();
+    for (input, expected) in cases {
+        assert_romanizes_to_str(&wylie, input, Some("bod"), expected);
+        assert!(seen.insert(expected), "{expected:?} is ambiguous");
+    }
+    assert_romanizes_to_str(&Uroman::new(), "གཡག", Some("bod"), "gyag");
+}
+
+#[test]
+fn test_jyutping() {
+    use uroman::Jyutping;
+
+    let toneless = Uroman::builder()
+        .jyutping(Jyutping::Toneless)
+        .build()
+        .unwrap();
+    let numbers = Uroman::builder()
+        .jyutping(Jyutping::ToneNumbers)
+        .build()
+        .unwrap();
+
+    let cases = [
+        ("我哋講廣東話", "ngodeigonggwongdungwaa", "ngo5dei6gong2gwong2dung1waa6"),
+        ("香港", "hoenggong", "hoeng1gong2"),
+        ("北京", "bakging", "bak1ging1"),
+    ];
+    for (input, expected_toneless, expected_numbers) in cases {
+        assert_romanizes_to_str(&toneless, input, Some("yue"), expected_toneless);
+        assert_romanizes_to_str(&numbers, input, Some("yue"), expected_numbers);
+    }
+
+    // Mandarin keeps its pinyin, as do characters without a Cantonese reading.
+    assert_romanizes_to_str(&numbers, "我哋講廣東話", Some("zho"), "wodiejiangguangdonghua");
+    assert_romanizes_to_str(&numbers, "鬱", Some("yue"), "yu");
+    assert_romanizes_to_str(&numbers, "三百", Some("yue"), "300");
+
+    // The pinyin of characters without a Cantonese reading is marked as such.
+    let edges = numbers.romanize_string::<rom_format::Edges>("香鬱", Some("yue")).to_edges();
+    let types: Vec<&str> = edges.iter().map(|edge| edge.r#type()).collect();
+    assert_eq!(types, vec!["rom jyutping", "rom mandarin-fallback"]);
+}
+
+#[test]
+fn test_edge_alternatives() {
+    let uroman = Uroman::new();
+    let edges = uroman
+        .romanize_string::<rom_format::Alts>("chromosome", None)
+        .to_edges();
+
+    let chr = edges
+        .iter()
+        .find(|edge| !edge.is_alternative() && edge.source_range() == (0..3))
+        .unwrap();
+    assert_eq!(chr.txt(), "chr");
+    assert_eq!(chr.alternatives(&edges), vec!["kr"]);
+
+    let o = edges.iter().find(|edge| edge.source_range() == (3..4)).unwrap();
+    assert!(o.alternatives(&edges).is_empty());
+
+    // Thai consonants get `rom-alt3` alternatives, which are listed like `rom-alt` ones.
+    let edges = uroman.romanize_string::<rom_format::Alts>("กขค", None).to_edges();
+    let alt = edges.iter().find(|edge| edge.r#type() == "rom-alt3").unwrap();
+    assert!(alt.is_alternative());
+    let kho = edges
+        .iter()
+        .find(|edge| !edge.is_alternative() && edge.source_range() == (2..3))
+        .unwrap();
+    assert_eq!(kho.txt(), "k");
+    assert_eq!(kho.alternatives(&edges), vec!["kh"]);
+}
+
+#[test]
+fn test_lattice_nodes() {
+    use uroman::{LatticeNode, RomFormat, RomanizationResult};
+
+    let uroman = Uroman::new();
+    let nodes = uroman
+        .romanize_string::<rom_format::Lattice>("北京", None)
+        .to_nodes();
+    assert_eq!(nodes.len(), 3);
+    for node in &nodes {
+        assert!(node.edges.iter().all(|edge| edge.start() == node.position));
+        assert!(node.edges.windows(2).all(|pair| pair[0].end() >= pair[1].end()));
+    }
+    // Both the word and its characters compete at position 0.
+    let ends: Vec<usize> = nodes[0].edges.iter().map(|edge| edge.end()).collect();
+    assert!(ends.contains(&2) && ends.contains(&1));
+
+    // The dynamic API returns the same lattice, and each edge serializes with its score.
+    let result = uroman.romanize_with_format("北京", None, Some(RomFormat::LatticeNodes));
+    assert_eq!(result, RomanizationResult::Lattice(nodes.clone()));
+    let json: serde_json::Value = serde_json::from_str(&result.to_string().unwrap()).unwrap();
+    assert_eq!(json[0]["position"], 0);
+    assert!(json[0]["edges"][0]["score"].is_number());
+
+    assert!(LatticeNode::from_edges(Vec::new())[0].edges.is_empty());
+
+    // `RomFormat::Lattice` keeps returning the flat edge list.
+    let result = uroman.romanize_with_format("北京", None, Some(RomFormat::Lattice));
+    assert!(matches!(result, RomanizationResult::Edges(_)));
+
+    // The `::lcode` entry of a file has the shape of the nodes.
+    let mut output = Vec::new();
+    uroman
+        .romanize_file(
+            "::lcode ukr Київ".as_bytes(),
+            &mut output,
+            None,
+            RomFormat::LatticeNodes,
+            None,
+            false,
+            true,
+        )
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json[0], serde_json::json!({"position": 0, "edges": [], "lcode": "ukr"}));
+    assert_eq!(json[1]["position"], 0);
+    assert!(json[1]["edges"][0]["score"].is_number());
+}
+
+#[test]
+fn test_romanize_nbest() {
+    let uroman = Uroman::new();
+
+    for (input, lcode) in [("chromosome", None), ("北京", None), ("Привет мир", Some("rus"))] {
+        let nbest = uroman.romanize_nbest(input, lcode, 5);
+        let best = uroman.romanize_string::<rom_format::Str>(input, lcode).to_string();
+        assert_eq!(nbest[0].0, best, "Top n-best result for {input:?}");
+        assert!(nbest.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(nbest.iter().all(|(_, score)| *score <= 0.0));
+    }
+
+    let nbest = uroman.romanize_nbest("chromosome", None, 5);
+    let texts: Vec<&str> = nbest.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(texts, vec!["chromosome", "kromosome"]);
+
+    // 北京 and 北 + 京 both produce "beijing", which counts once.
+    let nbest = uroman.romanize_nbest("北京", None, 5);
+    let texts: Vec<&str> = nbest.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(texts, vec!["beijing", "bei10000000000000000"]);
+
+    assert_eq!(uroman.romanize_nbest("123", None, 2).len(), 2);
+    assert!(uroman.romanize_nbest("chromosome", None, 0).is_empty());
+}
+
+#[test]
+fn test_lattice_to_dot() {
+    let uroman = Uroman::new();
+
+    let dot = uroman.romanize_string::<rom_format::Lattice>("北京", None).to_dot();
+    let lines: Vec<&str> = dot.lines().collect();
+    assert_eq!(lines.first(), Some(&"digraph lattice {"));
+    assert_eq!(lines.last(), Some(&"}"));
+    for position in ["    0;", "    1;", "    2;"] {
+        assert!(lines.contains(&position), "Missing node {position:?} in {dot}");
+    }
+    assert!(lines.contains(&r#"    0 -> 1 [label="bei\nrom\n0.90"];"#));
+    assert!(lines.contains(&r#"    1 -> 2 [label="jing\nrom\n0.90"];"#));
+
+    // Quotes and backslashes in labels are escaped.
+    let dot = uroman.romanize_string::<rom_format::Edges>("\"a\\", None).to_dot();
+    assert!(dot.contains(r#"    0 -> 1 [label="\"\norig\n0.30"];"#), "{dot}");
+    assert!(dot.contains(r#"    2 -> 3 [label="\\\norig\n0.30"];"#), "{dot}");
+}
+
+#[test]
+fn test_min_edge_score() {
+    let uroman = Uroman::new();
+    let pruned = Uroman::builder().min_edge_score(0.6).build().unwrap();
+
+    // The rom-alt alternative of "chr" scores 0.5.
+    let edges = uroman.romanize_string::<rom_format::Lattice>("chromosome", None).to_edges();
+    assert!(edges.iter().any(|edge| edge.is_alternative()));
+    let edges = pruned.romanize_string::<rom_format::Lattice>("chromosome", None).to_edges();
+    assert!(!edges.iter().any(|edge| edge.is_alternative()));
+    assert_eq!(pruned.romanize_nbest("chromosome", None, 5).len(), 1);
+
+    // The inactive numeric edges of 123 are dropped.
+    let edges = uroman.romanize_string::<rom_format::Lattice>("123", None).to_edges();
+    let pruned_edges = pruned.romanize_string::<rom_format::Lattice>("123", None).to_edges();
+    assert!(pruned_edges.len() < edges.len());
+    assert!(pruned_edges.iter().all(|edge| edge.score() >= 0.6));
+    assert_romanizes_to_str(&pruned, "123", None, "123");
+
+    // Edges below the threshold are kept where they are the only way through.
+    let strict = Uroman::builder().min_edge_score(1.0).build().unwrap();
+    for input in ["chromosome", "北京 123", "Привет, мир!", "\u{0378}x"] {
+        assert_romanizes_to_str(
+            &strict,
+            input,
+            None,
+            &uroman.romanize_string::<rom_format::Str>(input, None).to_string(),
+        );
+    }
+}
+
+#[test]
+fn test_edge_value_i128() {
+    let uroman = Uroman::new();
+    let exact_value = |input: &str| {
+        let edges = uroman.romanize_string::<rom_format::Edges>(input, None).to_edges();
+        assert_eq!(edges.len(), 1, "Expected a single edge for {input:?}");
+        edges[0].value_i128()
+    };
+
+    assert_eq!(exact_value("三百"), Some(300));
+    assert_eq!(exact_value("12345678901234567890123"), Some(12345678901234567890123));
+    // 2^53 + 1, which `value` rounds down.
+    assert_eq!(exact_value("9007199254740993"), Some(9007199254740993));
+
+    let input = "九千九百九十九京九千九百九十九兆九千九百九十九億九千九百九十九万九千九百九十九";
+    assert_eq!(exact_value(input), Some(99_999_999_999_999_999_999));
+    assert_romanizes_to_str(&uroman, input, None, "99999999999999999999");
+
+    let edges = uroman.romanize_string::<rom_format::Edges>("abc", None).to_edges();
+    assert!(edges.iter().all(|edge| edge.value_i128().is_none()));
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_edge_value_bigint() {
+    let uroman = Uroman::new();
+    let digits = "1".repeat(60);
+    let edges = uroman.romanize_string::<rom_format::Edges>(&digits, None).to_edges();
+    assert_eq!(edges[0].value_bigint(), digits.parse().ok());
+    assert_eq!(edges[0].value_i128(), None);
+}
+
+#[test]
+fn test_decimal_separators_and_fractions() {
+    let uroman = Uroman::new();
+    let number_edge = |input: &str| {
+        uroman
+            .romanize_string::<rom_format::Edges>(input, None)
+            .to_edges()
+            .into_iter()
+            .find(|edge| edge.is_numeric())
+            .unwrap()
+    };
+
+    for (input, txt, value) in [
+        ("١٢٫٥", "12.5", 12.5),
+        ("۱۲٫۵", "12.5", 12.5),
+        ("12.5", "12.5", 12.5),
+        ("१२.५", "12.5", 12.5),
+        ("½", "1/2", 0.5),
+    ] {
+        let edge = number_edge(input);
+        assert_eq!(edge.txt(), txt, "txt of {input:?}");
+        assert_eq!(edge.orig_txt(), input, "orig_txt of {input:?}");
+        assert_eq!(edge.value(), Some(value), "value of {input:?}");
+        assert_eq!(edge.value_i128(), None, "value_i128 of {input:?}");
+    }
+    let third = number_edge("⅓").value().unwrap();
+    assert!((third - 1.0 / 3.0).abs() < 1e-12);
+
+    // ٫ is only a decimal separator after Arabic-Indic digits.
+    assert_eq!(number_edge("12٫5").value(), Some(12.0));
+    assert_romanizes_to_str(&uroman, "12٫5", None, "12,5");
+    assert_romanizes_to_str(&uroman, "١٢٫٥", None, "12.5");
+
+    // A fraction is not attached to the last group of a number with thousands separators.
+    let edges = uroman.romanize_string::<rom_format::Edges>("1,000.5", None).to_edges();
+    assert!(edges.iter().all(|edge| edge.value() != Some(0.5)));
+    assert_romanizes_to_str(&uroman, "1,000.5", None, "1,000.5");
+    assert_eq!(number_edge("0.5").value(), Some(0.5));
+}
+
+#[test]
+fn test_ordinal_edges() {
+    let uroman = Uroman::new();
+    let numbers = |input: &str, lcode: Option<&str>| -> Vec<(String, bool)> {
+        uroman
+            .romanize_string::<rom_format::Edges>(input, lcode)
+            .to_edges()
+            .into_iter()
+            .filter(|edge| edge.is_numeric())
+            .map(|edge| (edge.txt().to_string(), edge.is_ordinal()))
+            .collect()
+    };
+
+    for (input, lcode) in [
+        ("21st", None),
+        ("11th", Some("eng")),
+        ("1º", Some("spa")),
+        ("2.ª", Some("por")),
+        ("3ᵉ", None),
+        ("第三十", Some("zho")),
+        ("2ème", Some("fra")),
+        ("3番目", Some("jpn")),
+    ] {
+        let edges = numbers(input, lcode);
+        assert_eq!(edges.len(), 1, "{input:?}");
+        assert!(edges[0].1, "{input:?} should be an ordinal");
+    }
+
+    // Cardinals, mismatched suffixes and longer words are not ordinals.
+    for (input, lcode) in [("21", None), ("21th", None), ("1stly", None), ("3番目", None), ("2em", Some("fra"))] {
+        assert!(numbers(input, lcode).iter().all(|(_, ordinal)| !ordinal), "{input:?}");
+    }
+
+    // The romanization is unchanged.
+    assert_romanizes_to_str(&uroman, "21st", None, "21st");
+    let edges = uroman.romanize_string::<rom_format::Edges>("第三十", Some("zho")).to_edges();
+    let ordinal = edges.iter().find(|edge| edge.is_ordinal()).unwrap();
+    assert_eq!(ordinal.get_data().r#type, "ordinal");
+    assert_eq!(ordinal.value(), Some(30.0));
+}
+
+#[test]
+fn test_recognize_roman_numerals() {
+    let uroman = Uroman::builder().recognize_roman_numerals(true).build().unwrap();
+
+    assert_romanizes_to_str(&uroman, "Chapter IV", None, "Chapter 4");
+    assert_romanizes_to_str(&uroman, "MCMLXXXIV", None, "1984");
+    let edges = uroman.romanize_string::<rom_format::Edges>("XLII", None).to_edges();
+    assert_eq!(edges.len(), 1);
+    assert!(edges[0].is_numeric());
+    assert_eq!(edges[0].value(), Some(42.0));
+    assert_eq!(edges[0].orig_txt(), "XLII");
+
+    // Capitalized words, the pronoun I and non-standard numerals are not numbers.
+    for word in ["Mix", "Civil", "I", "IIII", "VX", "IC", "XIVth", "MMMM", "MIX", "DC"] {
+        assert_romanizes_to_str(&uroman, word, None, word);
+    }
+
+    // Common uppercase words are numerals only after a cue word.
+    assert_romanizes_to_str(&uroman, "DC Comics and a MIX", None, "DC Comics and a MIX");
+    assert_romanizes_to_str(&uroman, "Chapter DC", None, "Chapter 600");
+    assert_romanizes_to_str(&uroman, "Vol. MIX", None, "Vol. 1009");
+
+    // The option is off by default.
+    assert_romanizes_to_str(&Uroman::new(), "Chapter IV", None, "Chapter IV");
+}
+
+#[test]
+fn test_numeric_mode() {
+    use uroman::NumericMode;
+
+    let ascii_digits = Uroman::builder().numeric_mode(NumericMode::AsciiDigits).build().unwrap();
+    let passthrough = Uroman::builder().numeric_mode(NumericMode::Passthrough).build().unwrap();
+    let spell = Uroman::new();
+
+    for (input, spelled, ascii, passed) in [
+        ("2024", "2024", "2024", "2024"),
+        ("١٠", "10", "10", "١٠"),
+        ("١٬٠٠٠", "1,000", "1,000", "١٬٠٠٠"),
+        ("٣٫٥", "3.5", "3,5", "٣٫٥"),
+        ("१२.५", "12.5", "12.5", "१२.५"),
+        ("三百", "300", "sanbai", "sanbai"),
+    ] {
+        assert_romanizes_to_str(&spell, input, None, spelled);
+        assert_romanizes_to_str(&ascii_digits, input, None, ascii);
+        assert_romanizes_to_str(&passthrough, input, None, passed);
+    }
+
+    // Digits are not interpreted, so there are no numeric edges.
+    let edges = ascii_digits.romanize_string::<rom_format::Lattice>("١٢٣", None).to_edges();
+    assert!(edges.iter().all(|edge| !edge.is_numeric()));
+    assert_eq!(edges.len(), 3);
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi_romanize() {
+    use std::ffi::{CStr, CString};
+    use uroman::ffi::{uroman_free, uroman_new, uroman_romanize, uroman_string_free};
+
+    let uroman = uroman_new();
+    assert!(!uroman.is_null());
+
+    let text = CString::new("Київ").unwrap();
+    let lcode = CString::new("ukr").unwrap();
+    unsafe {
+        let romanized = uroman_romanize(uroman, text.as_ptr(), lcode.as_ptr());
+        assert_eq!(CStr::from_ptr(romanized).to_str(), Ok("Kyiv"));
+        uroman_string_free(romanized);
+
+        let romanized = uroman_romanize(uroman, text.as_ptr(), std::ptr::null());
+        assert!(!romanized.is_null());
+        uroman_string_free(romanized);
+
+        // Invalid UTF-8 and null arguments return null.
+        let invalid = CString::new(vec![0xff, 0xfe]).unwrap();
+        assert!(uroman_romanize(uroman, invalid.as_ptr(), std::ptr::null()).is_null());
+        assert!(uroman_romanize(uroman, std::ptr::null(), std::ptr::null()).is_null());
+        assert!(uroman_romanize(std::ptr::null(), text.as_ptr(), std::ptr::null()).is_null());
+
+        uroman_string_free(std::ptr::null_mut());
+        uroman_free(uroman);
+        uroman_free(std::ptr::null_mut());
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_romanize() {
+    use uroman::wasm::WasmUroman;
+
+    let uroman = WasmUroman::new();
+    assert_eq!(uroman.romanize("Київ", Some("ukr".to_string())), "Kyiv");
+    assert_eq!(uroman.romanize("Київ", None), "Kiyiv");
+}
+
+#[test]
+fn test_uroman_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Uroman>();
+}
+
+#[test]
+fn test_add_rules_from_str() {
+    let mut uroman = Uroman::new();
+    uroman
+        .add_rules_from_str("# In-house conventions\n::s ß ::t sz\n\n::s г ::t gh ::lcode ukr\n::s г ::t g\n")
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "Straße", None, "Strasze");
+    assert_romanizes_to_str(&uroman, "гора", Some("ukr"), "ghora");
+    assert_romanizes_to_str(&uroman, "гора", Some("rus"), "gora");
+
+    // A later call overrides earlier user rules.
+    uroman.add_rules_from_str("::s ß ::t ss").unwrap();
+    assert_romanizes_to_str(&uroman, "Straße", None, "Strasse");
+
+    // An invalid line is reported and nothing is added.
+    assert!(matches!(
+        uroman.add_rules_from_str("::s ж ::t j\n::t missing source"),
+        Err(uroman::RomanizationError::InvalidRule { line: 2 })
+    ));
+    assert_romanizes_to_str(&uroman, "ж", None, "zh");
+
+    // Other instances keep the built-in rules.
+    assert_romanizes_to_str(&Uroman::new(), "Straße", None, "Strasse");
+}
+
+#[test]
+fn test_add_rule() {
+    let mut uroman = Uroman::new();
+    uroman.add_rule("ж", "j", None);
+    uroman.add_rule("Харків", "Kharkov", Some("rus"));
+
+    assert_romanizes_to_str(&uroman, "жук", None, "juk");
+    assert_romanizes_to_str(&uroman, "Харків", Some("rus"), "Kharkov");
+    // Only the targeted token and language are affected.
+    assert_romanizes_to_str(&uroman, "Харків", Some("ukr"), "Kharkiv");
+    assert_romanizes_to_str(&uroman, "Харкові", Some("rus"), "Kharkovi");
+    assert_romanizes_to_str(&Uroman::new(), "жук", None, "zhuk");
+}
+
+#[test]
+fn test_disable_and_enable_language() {
+    let mut uroman = Uroman::new();
+    uroman.disable_language("ukr").unwrap();
+    assert!(!uroman.is_language_supported("ukr"));
+    assert!(uroman.is_language_supported("rus"));
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kiyiv");
+
+    uroman.enable_language("ukr").unwrap();
+    assert!(uroman.is_language_supported("ukr"));
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kyiv");
+
+    assert_eq!(
+        uroman.disable_language("xx"),
+        Err(uroman::BuildError::UnknownLanguage("xx".to_string()))
+    );
+
+    let mut uroman = Uroman::builder().with_languages(&["rus"]).build().unwrap();
+    uroman.enable_language("ukr").unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kyiv");
+
+    // Codes that rules are restricted to are accepted, and kept when another language
+    // is disabled.
+    let mut uroman = Uroman::new();
+    uroman.disable_language("ukr").unwrap();
+    assert_romanizes_to_str(&uroman, "цар", Some("srp2"), "car");
+    uroman.disable_language("srp2").unwrap();
+    assert_romanizes_to_str(&uroman, "цар", Some("srp2"), "tsar");
+    uroman.enable_language("srp2").unwrap();
+    assert_romanizes_to_str(&uroman, "цар", Some("srp2"), "car");
+
+    // The rules of the instance are restored, including those added to it.
+    let mut uroman = Uroman::from_compiled_bytes(&Uroman::new().to_compiled_bytes()).unwrap();
+    uroman.add_rule("Київ", "Kiev", Some("ukr"));
+    uroman.disable_language("ukr").unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kiyiv");
+    uroman.enable_language("ukr").unwrap();
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kiev");
+}
+
+#[test]
+fn test_export_rules_round_trip() {
+    let mut uroman = Uroman::new();
+    uroman.add_rule("ж", "j", None);
+    uroman.add_rule(" ", " ", Some("ukr"));
+
+    let mut rules = Vec::new();
+    uroman.export_rules(&mut rules).unwrap();
+    let rules = String::from_utf8(rules).unwrap();
+    assert!(rules.lines().any(|line| line == "::s ж ::t j"));
+    assert!(rules.lines().any(|line| line == "::s \" \" ::t \" \" ::lcode ukr"));
+
+    let mut imported = Uroman::new();
+    imported.add_rules_from_str(&rules).unwrap();
+    for (text, lcode) in [("жук", None), ("Київ", Some("ukr")), ("一兆二千万四十二えん", Some("jpn"))] {
+        assert_eq!(
+            imported.romanize_string::<rom_format::Str>(text, lcode).to_string(),
+            uroman.romanize_string::<rom_format::Str>(text, lcode).to_string()
+        );
+    }
+
+    let mut reexported = Vec::new();
+    imported.export_rules(&mut reexported).unwrap();
+    assert_eq!(String::from_utf8(reexported).unwrap(), rules);
+}
+
+#[test]
+fn test_syllable_separator() {
+    use uroman::JapaneseScheme;
+
+    let build = |separator| {
+        Uroman::builder()
+            .japanese_scheme(JapaneseScheme::Hepburn)
+            .syllable_separator(separator)
+            .build()
+            .unwrap()
+    };
+    assert_romanizes_to_str(&build(Some('\'')), "きんえん", Some("jpn"), "kin'en");
+    assert_romanizes_to_str(&build(Some('-')), "きんえん", Some("jpn"), "kin-en");
+    assert_romanizes_to_str(&build(None), "きんえん", Some("jpn"), "kinen");
+    assert_romanizes_to_str(&build(None), "Ӏ", None, "");
+    assert_romanizes_to_str(&build(None), "don't", None, "don't");
+}
+
+#[test]
+fn test_collapse_whitespace() {
+    let uroman = Uroman::builder().collapse_whitespace(true).build().unwrap();
+    assert_romanizes_to_str(&uroman, "  Київ \u{3000}\t Львів  \n Одеса  ", Some("ukr"), "Kyiv Lviv\nOdesa");
+    assert_romanizes_to_str(&uroman, "Київ\r\n\r\nЛьвів", Some("ukr"), "Kyiv\r\n\r\nLviv");
+
+    // Edges are unaffected.
+    let edges = uroman.romanize_string::<rom_format::Edges>("a  b", None).to_edges();
+    assert_eq!(edges.iter().map(|edge| edge.txt()).collect::<String>(), "a  b");
+
+    assert_romanizes_to_str(&Uroman::new(), "a  b ", None, "a  b ");
+}
+
+#[test]
+fn test_collapse_whitespace_in_every_method() {
+    let uroman = Uroman::builder().collapse_whitespace(true).build().unwrap();
+
+    let texts = ["  Київ \u{3000}\t Львів  \n Одеса  ", "Київ \r\n\r\n Львів\r", "a  b", "Ж \r Ж"];
+    for text in texts {
+        let expected = uroman.romanize_string::<rom_format::Str>(text, Some("ukr")).to_string();
+        assert_eq!(uroman.romanize_cow(text, Some("ukr")), expected);
+        assert_eq!(uroman.try_romanize(text, Some("ukr")).unwrap(), expected);
+        assert_eq!(uroman.romanize_with_diagnostics(text, Some("ukr")).0, expected);
+
+        let (romanized, alignment) = uroman.romanize_with_alignment(text, Some("ukr"));
+        assert_eq!(romanized, expected);
+        assert_eq!(alignment.last().unwrap().1.end, expected.len());
+
+        let mut applied = text.to_string();
+        for edit in uroman.romanize_edits(text, Some("ukr")).iter().rev() {
+            applied.replace_range(edit.source.clone(), &edit.replacement);
+        }
+        assert_eq!(applied, expected);
+    }
+}
+
+#[test]
+fn test_preserve_tokens() {
+    let uroman = Uroman::builder()
+        .preserve_tokens(true)
+        .case_sensitive(false)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(
+        &uroman,
+        "Пишіть @Олег_K на oleg.k@приклад.укр або див. https://uk.wikipedia.org/wiki/Київ.",
+        Some("ukr"),
+        "pyshit @Олег_K na oleg.k@приклад.укр abo dyv. https://uk.wikipedia.org/wiki/Київ.",
+    );
+    assert_romanizes_to_str(&uroman, "a@б", None, "a@b");
+
+    let edges = uroman
+        .romanize_string::<rom_format::Edges>("див. www.приклад.укр", Some("ukr"))
+        .to_edges();
+    let verbatim: Vec<_> = edges.iter().filter(|edge| edge.r#type() == "verbatim").collect();
+    assert_eq!(verbatim.len(), 1);
+    assert_eq!(verbatim[0].txt(), "www.приклад.укр");
+
+    assert_romanizes_to_str(&Uroman::new(), "@Олег", Some("ukr"), "@Oleh");
+}
+
+#[test]
+fn test_romanize_html() {
+    let uroman = Uroman::new();
+    let html = concat!(
+        "<!DOCTYPE html><p class=\"город\">Київ &amp; Львів<br/>",
+        "<img src=\"київ.png\" ALT='Київ'> 1 < 2</p>",
+        "<!-- Київ --><script>var s = \"<b>Київ</b>\";</script><STYLE>p { content: \"Київ\" }</STYLE>",
+    );
+    assert_eq!(
+        uroman.romanize_html(html, Some("ukr")),
+        concat!(
+            "<!DOCTYPE html><p class=\"город\">Kyiv &amp; Lviv<br/>",
+            "<img src=\"київ.png\" ALT='Kyiv'> 1 < 2</p>",
+            "<!-- Київ --><script>var s = \"<b>Київ</b>\";</script><STYLE>p { content: \"Київ\" }</STYLE>",
+        )
+    );
+}
+
+#[test]
+fn test_romanize_ruby() {
+    use uroman::RubyGrouping;
+
+    let uroman = Uroman::new();
+    assert_eq!(
+        uroman.romanize_ruby("你好，<世界> & A", Some("zho"), RubyGrouping::Character),
+        "<ruby>你<rt>ni</rt></ruby><ruby>好<rt>hao</rt></ruby>，&lt;\
+         <ruby>世<rt>shi</rt></ruby><ruby>界<rt>jie</rt></ruby>&gt; &amp; A"
+    );
+    assert_eq!(
+        uroman.romanize_ruby("你好，世界", Some("zho"), RubyGrouping::Word),
+        "<ruby>你好<rt>nihao</rt></ruby>，<ruby>世界<rt>shijie</rt></ruby>"
+    );
+}
+
+#[test]
+fn test_romanize_tokens() {
+    let uroman = Uroman::new();
+    let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+        pairs.iter().map(|(s, t)| (s.to_string(), t.to_string())).collect()
+    };
+
+    assert_eq!(
+        uroman.romanize_tokens("Привіт,  світе! 3.14", Some("ukr"), true),
+        pairs(&[
+            ("Привіт", "Pryvit"),
+            (",", ","),
+            ("  ", "  "),
+            ("світе", "svite"),
+            ("!", "!"),
+            (" ", " "),
+            ("3.14", "3.14"),
+        ])
+    );
+    assert_eq!(
+        uroman.romanize_tokens("你好，世界", Some("zho"), false),
+        pairs(&[("你好", "nihao"), ("世界", "shijie")])
+    );
+}
+
+#[test]
+fn test_reversible_round_trip() {
+    let uroman = Uroman::builder().reversible(true).build().unwrap();
+    let text = "Щука, жёлтый ЁЖ і Ґанок; Љубљана — Џон 2024! 北京";
+
+    let romanized = uroman
+        .romanize_string::<rom_format::Str>(text, Some("rus"))
+        .to_string();
+    assert!(!romanized.contains(['Щ', 'ж', 'Ґ', 'Љ']));
+    assert!(romanized.contains("北京"));
+    assert_eq!(uroman.deromanize(&romanized, Some("rus")), text);
+
+    // Latin words and backslashes of the source are escaped, not read as ISO 9.
+    let text = "Щука and Kyiv \\ a/b";
+    let romanized = uroman
+        .romanize_string::<rom_format::Str>(text, Some("rus"))
+        .to_string();
+    assert_eq!(romanized, "Ŝuka \\and\\ \\Kyiv\\ \\\\ \\a\\/\\b\\");
+    assert_eq!(uroman.deromanize(&romanized, Some("rus")), text);
+
+    let text = "བཀྲ་ཤིས་བདེ་ལེགས། Hello";
+    let romanized = uroman
+        .romanize_string::<rom_format::Str>(text, Some("bod"))
+        .to_string();
+    assert!(romanized.starts_with("bkra shis bde legs/"));
+    assert_eq!(uroman.deromanize(&romanized, Some("bod")), text);
+
+    let text = "སངས་རྒྱས་ཀྱི་བསྟན་པ།";
+    let romanized = uroman
+        .romanize_string::<rom_format::Str>(text, Some("bod"))
+        .to_string();
+    assert_eq!(romanized, "sangs rgyas kyi bstan pa/");
+    assert_eq!(uroman.deromanize(&romanized, Some("bod")), text);
+}
+
+#[test]
+fn test_romanize_into() {
+    let uroman = Uroman::builder().cache_capacity(16).build().unwrap();
+    let mut out = String::from("> ");
+
+    uroman.romanize_into("Київ", Some("ukr"), &mut out);
+    assert_eq!(out, "> Kyiv");
+
+    out.clear();
+    uroman.romanize_into("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", None, &mut out);
+    assert_eq!(
+        out,
+        uroman
+            .romanize_string::<rom_format::Str>("ᚺᚨᛚᛚᛟ ᚹᛟᚱᛚᛞ", None)
+            .to_string()
+    );
+}
+
+#[test]
+fn test_ascii_fast_path_matches_lattice() {
+    let uroman = Uroman::new();
+    let ascii: String = (0u8..128)
+        .map(char::from)
+        .filter(|c| !c.is_ascii_digit())
+        .collect();
+    let inputs = [
+        ascii.as_str(),
+        "The quick brown fox jumps over the lazy dog.",
+        "don't  stop\tbelieving\r\n",
+        "MIX of CASE, a@b.c & https://example.org/x?y=z",
+    ];
+
+    for lcode in [None, Some("eng"), Some("jpn"), Some("deu")] {
+        for s in inputs {
+            let edges: String = uroman
+                .romanize_string::<rom_format::Edges>(s, lcode)
+                .into_iter()
+                .map(|edge| edge.txt().to_string())
+                .collect();
+            assert_eq!(
+                uroman.romanize_string::<rom_format::Str>(s, lcode).to_string(),
+                edges,
+                "{s:?} under {lcode:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_from_compiled_file() {
+    let path = std::env::temp_dir().join(format!("uroman-compiled-{}.bin", std::process::id()));
+    let uroman = Uroman::builder().case_sensitive(false).build().unwrap();
+    std::fs::write(&path, uroman.to_compiled_bytes()).unwrap();
+
+    let loaded = Uroman::from_compiled_file(&path).unwrap();
+    assert_romanizes_to_str(&loaded, "Київ", Some("ukr"), "kyiv");
+
+    std::fs::write(&path, b"not compiled").unwrap();
+    let err = Uroman::from_compiled_file(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_edge_score_distinguishes_rules_from_fallbacks() {
+    let uroman = Uroman::new();
+    let edges = uroman.romanize_string::<rom_format::Edges>("ж\u{E000}", None).to_edges();
+
+    assert_eq!(edges.len(), 2);
+    assert!(edges[0].r#type().starts_with("rom"));
+    assert_eq!(edges[0].score(), 0.9);
+    assert!(!edges[1].r#type().starts_with("rom"));
+    assert!(edges[1].score() < edges[0].score());
+}
+
+#[test]
+fn test_emoji_policy() {
+    use uroman::EmojiPolicy;
+
+    let text = "Київ 😀👍🏽 ♥ 🇺🇸 👨‍👩‍👧!";
+    // Format characters such as the zero width joiner are always dropped.
+    assert_romanizes_to_str(&Uroman::new(), text, Some("ukr"), "Kyiv 😀👍🏽 ♥ 🇺🇸 👨👩👧!");
+
+    let drop = Uroman::builder().emoji_policy(EmojiPolicy::Drop).build().unwrap();
+    assert_romanizes_to_str(&drop, text, Some("ukr"), "Kyiv    !");
+
+    let name = Uroman::builder().emoji_policy(EmojiPolicy::Name).build().unwrap();
+    assert_romanizes_to_str(
+        &name,
+        text,
+        Some("ukr"),
+        "Kyiv :grinning_face::thumbs_up_sign: :black_heart_suit: :flag_us: :man::woman::girl:!",
+    );
+    let edges = name.romanize_string::<rom_format::Edges>("👍🏽", None).to_edges();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].r#type(), "symbol");
+}
+
+#[test]
+fn test_combining_mark_order() {
+    let uroman = Uroman::new();
+
+    // גָּבֹהַּ with the dagesh of the final ה typed before and after its patah.
+    assert_romanizes_to_str(&uroman, "גָּבֹה\u{5BC}\u{5B7}", Some("heb"), "gavoah");
+    assert_romanizes_to_str(&uroman, "גָּבֹה\u{5B7}\u{5BC}", Some("heb"), "gavoah");
+    assert_romanizes_to_str(&uroman, "ג\u{5BC}\u{5B8}בֹה\u{5B7}\u{5BC}", Some("heb"), "gavoah");
+
+    // ệ as e + dot below + circumflex and as e + circumflex + dot below.
+    let romanize = |s| uroman.romanize_string::<rom_format::Str>(s, Some("vie")).to_string();
+    assert_eq!(romanize("Vie\u{323}\u{302}t"), romanize("Vie\u{302}\u{323}t"));
+}
+
+#[test]
+fn test_astral_plane_characters() {
+    let uroman = Uroman::new();
+
+    assert_romanizes_to_str(&uroman, "𝐇𝐞𝐥𝐥𝐨 𐌰𐌱", None, "Hello ab");
+    assert_romanizes_to_str(&uroman, "\u{20D79}", Some("zho"), "gang");
+
+    // Edge offsets count characters, so an astral character spans one position.
+    let edges = uroman.romanize_string::<rom_format::Edges>("a𝐀ж", None).to_edges();
+    let spans: Vec<_> = edges.iter().map(|e| (e.start(), e.end())).collect();
+    assert_eq!(spans, vec![(0, 1), (1, 2), (2, 3)]);
+
+    // An astral character escaped as a UTF-16 surrogate pair, as in JSON.
+    let result = uroman
+        .romanize_escaped_with_format("\\ud835\\udc07i \\U0001D407i", None, None)
+        .to_string()
+        .unwrap();
+    assert_eq!(result, "Hi Hi");
+}
+
+#[test]
+fn test_fallback() {
+    use uroman::Fallback;
+
+    let text = "Жук €5, 𓀀!";
+    assert_romanizes_to_str(&Uroman::new(), text, None, "Zhuk €5, 𓀀!");
+
+    let replace = Uroman::builder().fallback(Fallback::Replace("?".to_string())).build().unwrap();
+    assert_romanizes_to_str(&replace, text, None, "Zhuk ?5, ?!");
+
+    let drop = Uroman::builder().fallback(Fallback::Drop).build().unwrap();
+    assert_romanizes_to_str(&drop, text, None, "Zhuk 5, !");
+
+    let edges = drop.romanize_string::<rom_format::Edges>("𓀀", None).to_edges();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].r#type(), "fallback");
+
+    // Private-use and unassigned characters are unromanizable too.
+    let text = "a\u{E000}b\u{0378}";
+    assert_romanizes_to_str(&Uroman::new(), text, None, text);
+    assert_romanizes_to_str(&replace, text, None, "a?b?");
+    assert_romanizes_to_str(&drop, text, None, "ab");
+}
+
+#[test]
+fn test_expand_ligatures() {
+    let uroman = Uroman::builder().expand_ligatures(true).build().unwrap();
+
+    assert_romanizes_to_str(&uroman, "ﬁne", None, "fine");
+    assert_romanizes_to_str(&uroman, "և", Some("hye"), "ev");
+    assert_romanizes_to_str(&uroman, "ﬅ ﬓ", Some("hye"), "st mn");
+    assert_romanizes_to_str(&Uroman::new(), "ﬅ ﬓ", Some("hye"), "ﬅ ﬓ");
+
+    let edges = uroman.romanize_string::<rom_format::Edges>("ﬁ", None).to_edges();
+    assert_eq!(edges.last().unwrap().end(), 2);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_romanize_gzip_file() {
+    use flate2::Compression;
+    use flate2::read::MultiGzDecoder;
+    use flate2::write::GzEncoder;
+    use std::io::{Read, Write};
+    use uroman::RomFormat;
+
+    let dir = std::env::temp_dir();
+    let input = dir.join(format!("uroman-gzip-{}.txt.gz", std::process::id()));
+    let output = dir.join(format!("uroman-gzip-{}.rom.txt.gz", std::process::id()));
+
+    // Two concatenated gzip members.
+    let mut compressed = Vec::new();
+    for text in ["Київ\n", "Αθήνα\n"] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        compressed.extend(encoder.finish().unwrap());
+    }
+    std::fs::write(&input, compressed).unwrap();
+
+    let uroman = Uroman::new();
+    uroman.romanize_gzip_file(&input, &output, None, RomFormat::Str, true).unwrap();
+    let mut romanized = String::new();
+    MultiGzDecoder::new(std::fs::File::open(&output).unwrap())
+        .read_to_string(&mut romanized)
+        .unwrap();
+    assert_eq!(romanized, "Kiyiv\nAthena\n");
+
+    uroman.romanize_gzip_file(&input, &output, None, RomFormat::Str, false).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "Kiyiv\nAthena\n");
+
+    std::fs::remove_file(&input).unwrap();
+    std::fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn test_romanize_sentences() {
+    let uroman = Uroman::new();
+
+    assert_eq!(uroman.romanize_sentences("東京です。大阪です。", Some("jpn")).len(), 2);
+    assert_eq!(uroman.romanize_sentences("هل أنت بخير؟ نعم.", Some("ara")), vec!["hl ant bkhyr?", "n'm."]);
+    assert_eq!(
+        uroman.romanize_sentences("नमस्ते। आप कैसे हैं?", Some("hin")),
+        vec!["namaste.", "aap kaise haim?"]
+    );
+    assert_eq!(uroman.romanize_sentences("Τι κάνεις; Καλά.", Some("ell")), vec!["Ti kaneis;", "Kala."]);
+    assert_eq!(uroman.romanize_sentences("Τι κάνεις; Καλά.", None), vec!["Ti kaneis; Kala."]);
+    assert_eq!(uroman.romanize_sentences("  ", None), Vec::<String>::new());
+}
+
+#[test]
+fn test_romanize_edits() {
+    use uroman::Edit;
+
+    let uroman = Uroman::new();
+    let text = "Hi, Київ і Львів 2024!";
+    let edits = uroman.romanize_edits(text, Some("ukr"));
+    assert_eq!(
+        edits,
+        vec![
+            Edit { source: 4..12, replacement: "Kyiv".to_string() },
+            Edit { source: 13..15, replacement: "i".to_string() },
+            Edit { source: 16..26, replacement: "Lviv".to_string() },
+        ]
+    );
+
+    let mut applied = String::new();
+    let mut end = 0;
+    for edit in &edits {
+        applied.push_str(&text[end..edit.source.start]);
+        applied.push_str(&edit.replacement);
+        end = edit.source.end;
+    }
+    applied.push_str(&text[end..]);
+    assert_eq!(applied, uroman.romanize_string::<rom_format::Str>(text, Some("ukr")).to_string());
+
+    assert!(uroman.romanize_edits("plain ASCII", None).is_empty());
+}
+
+#[test]
+fn test_romanize_edits_of_normalized_text() {
+    use uroman::Edit;
+
+    // The ligature is expanded before romanization, but the edit refers to the input.
+    let uroman = Uroman::builder().expand_ligatures(true).build().unwrap();
+    let edits = uroman.romanize_edits("ﬁЖ", None);
+    assert_eq!(edits, vec![Edit { source: 0..5, replacement: "fiZh".to_string() }]);
+
+    let uroman = Uroman::builder()
+        .expand_ligatures(true)
+        .normalize_fullwidth(true)
+        .build()
+        .unwrap();
+    for text in ["Ж ﬁ Ж", "ﬃ e\u{301} Київ", "ＡＢ ｶﾞ"] {
+        let mut applied = text.to_string();
+        for edit in uroman.romanize_edits(text, Some("ukr")).iter().rev() {
+            applied.replace_range(edit.source.clone(), &edit.replacement);
+        }
+        assert_eq!(applied, uroman.romanize_string::<rom_format::Str>(text, Some("ukr")).to_string());
+    }
+}
+
+#[test]
+fn test_segment_words() {
+    let uroman = Uroman::builder().segment_words(true).build().unwrap();
+
+    assert_romanizes_to_str(&uroman, "สวัสดีครับ", Some("tha"), "swatdii khrap");
+    assert_romanizes_to_str(&uroman, "ภาษาไทย", Some("tha"), "phaasaa thai");
+    assert_romanizes_to_str(&uroman, "ภาษาขแมร์", Some("tha"), "phaasaa khmae");
+    assert_romanizes_to_str(&uroman, "ភាសាខ្មែរ", Some("khm"), "phaasaa khmaero");
+    assert_romanizes_to_str(&uroman, "ສະບາຍດີ", Some("lao"), "sabaanyodii");
+
+    // Other languages, and Thai without `lcode`, are unchanged.
+    assert_romanizes_to_str(&uroman, "สวัสดีครับ", None, "swatdiikrap");
+    assert_romanizes_to_str(&Uroman::new(), "สวัสดีครับ", Some("tha"), "swatdiikrap");
+}
+
+#[test]
+fn test_segment_words_keeps_offsets() {
+    let uroman = Uroman::builder().segment_words(true).build().unwrap();
+    let text = "สวัสดีครับ";
+
+    // The inserted space is an edge that covers no characters of the input.
+    let edges = uroman.romanize_string::<rom_format::Edges>(text, Some("tha")).to_edges();
+    assert_eq!(edges.last().unwrap().end(), text.chars().count());
+    assert!(edges.iter().any(|e| e.start() == e.end() && e.txt() == " "));
+
+    let (romanized, alignment) = uroman.romanize_with_alignment(text, Some("tha"));
+    assert_eq!(romanized, "swatdii khrap");
+    assert_eq!(alignment.last().unwrap().0.end, text.len());
+    for (source, _) in &alignment {
+        assert!(text.get(source.clone()).is_some());
+    }
+
+    let mut applied = text.to_string();
+    for edit in uroman.romanize_edits(text, Some("tha")).iter().rev() {
+        applied.replace_range(edit.source.clone(), &edit.replacement);
+    }
+    assert_eq!(applied, "swatdii khrap");
+    assert_eq!(uroman.romanize_cow(text, Some("tha")), "swatdii khrap");
+    assert_eq!(uroman.romanize_nbest(text, Some("tha"), 1)[0].0, "swatdii khrap");
+}
+
+#[test]
+fn test_ipa_output() {
+    use uroman::OutputTarget;
+
+    let uroman = Uroman::builder().output(OutputTarget::Ipa).build().unwrap();
+    assert_romanizes_to_str(&uroman, "すし", Some("jpn"), "sɯɕi");
+    assert_romanizes_to_str(&uroman, "きょうと がっこう コーヒー", Some("jpn"), "kʲoɯto ɡakkoɯ koːçiː");
+    assert_romanizes_to_str(&uroman, "한국", Some("kor"), "hankuk̚");
+    assert_romanizes_to_str(&uroman, "كِتَاب مُحَمَّد", Some("ara"), "kitaːb muħamːad");
+
+    // Other scripts fall back to Latin, in edges tagged as such.
+    let edges = uroman.romanize_string::<rom_format::Edges>("Ж すし", None).to_edges();
+    let types: Vec<_> = edges.iter().map(|e| e.r#type()).collect();
+    assert_eq!(types, vec!["latin rom", "Zs", "ipa", "ipa"]);
+    assert_eq!(
+        uroman.romanize_string::<rom_format::Str>("كتاب", None).to_string(),
+        Uroman::new().romanize_string::<rom_format::Str>("كتاب", None).to_string()
+    );
+}
+
+#[test]
+fn test_romanize_bytes() {
+    use uroman::{InvalidUtf8, RomanizationError};
+
+    let input = b"\xD0\x9A\xD0\xB8\xFF\xD1\x97\xD0\xB2 ok \xE2\x82";
+    let replace = Uroman::new();
+    assert_eq!(replace.romanize_bytes(input, Some("ukr")).unwrap(), "Ky\u{FFFD}yiv ok \u{FFFD}".as_bytes());
+
+    let skip = Uroman::builder().invalid_utf8(InvalidUtf8::Skip).build().unwrap();
+    assert_eq!(skip.romanize_bytes(input, Some("ukr")).unwrap(), b"Kyiv ok ");
+
+    let error = Uroman::builder().invalid_utf8(InvalidUtf8::Error).build().unwrap();
+    assert!(matches!(
+        error.romanize_bytes(input, Some("ukr")),
+        Err(RomanizationError::InvalidUtf8 { byte_offset: 4 })
+    ));
+    assert_eq!(error.romanize_bytes("Київ".as_bytes(), Some("ukr")).unwrap(), b"Kyiv");
+}
+
+#[test]
+fn test_wade_giles() {
+    use uroman::ChineseScheme;
+
+    let pinyin = Uroman::new();
+    let wade_giles = Uroman::builder()
+        .chinese_scheme(ChineseScheme::WadeGiles)
+        .build()
+        .unwrap();
+
+    // (input, pinyin, Wade-Giles)
+    let cases = [
+        ("北京", "beijing", "pei-ching"),
+        ("台北", "taibei", "t'ai-pei"),
+        ("上海", "shanghai", "shang-hai"),
+        ("广州", "guangzhou", "kuang-chou"),
+        ("学习", "xuexi", "hsüeh-hsi"),
+        ("北京 上海", "beijing shanghai", "pei-ching shang-hai"),
+        // 百 is also read as the number 100.
+        ("百色", "bose", "pai-sê"),
+    ];
+    for (input, expected_pinyin, expected_wade_giles) in cases {
+        assert_romanizes_to_str(&pinyin, input, Some("zho"), expected_pinyin);
+        assert_romanizes_to_str(&wade_giles, input, Some("zho"), expected_wade_giles);
+    }
+}
+
+#[test]
+fn test_greek_scheme() {
+    use uroman::GreekScheme;
+
+    let classical = Uroman::builder()
+        .greek_scheme(GreekScheme::Classical)
+        .build()
+        .unwrap();
+    let modern = Uroman::builder()
+        .greek_scheme(GreekScheme::ModernBgnPcgn)
+        .build()
+        .unwrap();
+
+    // (input, classical, modern BGN/PCGN)
+    let cases = [
+        // Rough breathing and iota subscript.
+        ("ᾅδης", "haidēs", "adis"),
+        ("ᾍδης", "Haidēs", "Adis"),
+        ("Ὅμηρος", "Homēros", "Omiros"),
+        ("οὗτος", "houtos", "outos"),
+        ("τῷ λόγῳ", "tōi logōi", "to logo"),
+        ("ῥήτωρ", "rhētōr", "ritor"),
+        ("Πύρρος", "Pyrrhos", "Pyrros"),
+        // Diphthongs and consonant clusters.
+        ("εὐαγγέλιον", "euangelion", "evangelion"),
+        ("ευχαριστώ", "eucharistō", "efcharisto"),
+        ("μπίρα", "mpira", "bira"),
+        ("Πάντα", "Panta", "Panda"),
+        ("Θεσσαλονίκη", "Thessalonikē", "Thessaloniki"),
+        ("ΑΘΗΝΑ", "ATHĒNA", "ATHINA"),
+    ];
+    for (input, expected_classical, expected_modern) in cases {
+        for lcode in ["ell", "grc"] {
+            assert_romanizes_to_str(&classical, input, Some(lcode), expected_classical);
+            assert_romanizes_to_str(&modern, input, Some(lcode), expected_modern);
+        }
+    }
+
+    // Without a scheme, the table rules apply.
+    assert_romanizes_to_str(&Uroman::new(), "ᾅδης", Some("grc"), "ades");
+}
+
+#[test]
+fn test_hebrew_niqqud() {
+    let uroman = Uroman::builder().hebrew_niqqud(true).build().unwrap();
+
+    // (pointed, vocalized, unpointed, skeleton)
+    let cases = [
+        ("שָׁלוֹם", "shalom", "שלום", "shlvm"),
+        ("בָּרוּךְ", "barukh", "ברוך", "brvkh"),
+        ("מֶלֶךְ", "melekh", "מלך", "mlkh"),
+        ("יִשְׂרָאֵל", "yisrael", "ישראל", "yshrl"),
+        ("סֵפֶר", "sefer", "ספר", "sfr"),
+        ("רוּחַ", "ruach", "רוח", "rvch"),
+    ];
+    for (pointed, vocalized, unpointed, skeleton) in cases {
+        assert_romanizes_to_str(&uroman, pointed, Some("heb"), vocalized);
+        assert_romanizes_to_str(&uroman, unpointed, Some("heb"), skeleton);
+    }
+
+    // A dagesh distinguishes the plosives from the fricatives.
+    assert_romanizes_to_str(&uroman, "פֶּה", Some("heb"), "peh");
+    assert_romanizes_to_str(&uroman, "כֹּהֵן", Some("heb"), "kohen");
+    assert_romanizes_to_str(&uroman, "אָבִי", Some("heb"), "avi");
+
+    // Without the option, the romanization tables apply.
+    assert_romanizes_to_str(&Uroman::new(), "בָּרוּךְ", Some("heb"), "baruke");
+}
+
+#[test]
+fn test_georgian_scheme() {
+    use uroman::GeorgianScheme;
+
+    let national = Uroman::builder()
+        .georgian_scheme(GeorgianScheme::National)
+        .build()
+        .unwrap();
+    let iso = Uroman::builder()
+        .georgian_scheme(GeorgianScheme::Iso)
+        .build()
+        .unwrap();
+
+    // (letter, national, ISO) for every letter the schemes write differently.
+    let differing = [
+        ('კ', "k'", "ḳ"),
+        ('პ', "p'", "p\u{0323}"),
+        ('ტ', "t'", "ṭ"),
+        ('ყ', "q'", "q\u{0323}"),
+        ('წ', "ts'", "c\u{0323}"),
+        ('ჭ', "ch'", "č\u{0323}"),
+        ('ჟ', "zh", "ž"),
+        ('შ', "sh", "š"),
+        ('ჩ', "ch", "č"),
+        ('ც', "ts", "c"),
+        ('ძ', "dz", "ʒ"),
+        ('ხ', "kh", "x"),
+        ('ღ', "gh", "ɣ"),
+        ('ჯ', "j", "ǯ"),
+    ];
+    for (letter, expected_national, expected_iso) in differing {
+        assert_ne!(expected_national, expected_iso);
+        let letter = letter.to_string();
+        assert_romanizes_to_str(&national, &letter, Some("kat"), expected_national);
+        assert_romanizes_to_str(&iso, &letter, Some("kat"), expected_iso);
+    }
+
+    // (input, national, ISO)
+    let words = [
+        ("თბილისი", "tbilisi", "tbilisi"),
+        ("კაცი", "k'atsi", "ḳaci"),
+        ("ჭაჭა", "ch'ach'a", "č\u{0323}ač\u{0323}a"),
+        ("ᲡᲐᲥᲐᲠᲗᲕᲔᲚᲝ", "SAKARTVELO", "SAKARTVELO"),
+        ("Ჭ", "Ch'", "Č\u{0323}"),
+    ];
+    for (input, expected_national, expected_iso) in words {
+        assert_romanizes_to_str(&national, input, Some("kat"), expected_national);
+        assert_romanizes_to_str(&iso, input, Some("kat"), expected_iso);
+    }
+}
+
+#[test]
+fn test_arabic_assimilate_article() {
+    let article = Uroman::builder()
+        .arabic_assimilate_article(true)
+        .build()
+        .unwrap();
+    let with_diacritics = Uroman::builder()
+        .arabic_assimilate_article(true)
+        .arabic_use_diacritics(true)
+        .build()
+        .unwrap();
+
+    // (input, assimilated, assimilated with diacritics)
+    let cases = [
+        // Sun letters.
+        ("الشمس", "ash-shms", "ash-shms"),
+        ("الشَّمْس", "ash-shams", "ash-shams"),
+        ("النور", "an-nwr", "an-nwr"),
+        // Moon letters.
+        ("القمر", "al-qmr", "al-qmr"),
+        ("القَمَر", "al-qamar", "al-qamar"),
+        ("الكتاب", "al-ktab", "al-ktab"),
+    ];
+    for (input, expected, expected_diacritics) in cases {
+        assert_romanizes_to_str(&article, input, Some("ara"), expected);
+        assert_romanizes_to_str(&with_diacritics, input, Some("ara"), expected_diacritics);
+    }
+
+    // Without the option, the article is written as in the romanization tables.
+    assert_romanizes_to_str(&Uroman::new(), "الشمس", Some("ara"), "alshms");
+}
+
+#[test]
+fn test_deterministic_tie_break() {
+    let uroman = Uroman::new();
+
+    // Every lattice hashes its edges with new random keys, so repeated runs would expose
+    // any dependence on the iteration order of the edge sets.
+    let inputs = ["chromosome", "Schwarz", "123", "北京", "ありがとう"];
+    for input in inputs {
+        let first = uroman.romanize_string::<rom_format::Lattice>(input, None).to_edges();
+        let first_str = uroman.romanize_string::<rom_format::Str>(input, None).to_string();
+        for _ in 0..20 {
+            let edges = uroman.romanize_string::<rom_format::Lattice>(input, None).to_edges();
+            assert_eq!(edges, first, "edges of {input}");
+            let s = uroman.romanize_string::<rom_format::Str>(input, None).to_string();
+            assert_eq!(s, first_str, "romanization of {input}");
+        }
+    }
+
+    // Within a span, edges are ordered by decreasing score, so "chr" comes before its
+    // rom-alt alternative.
+    let edges = uroman.romanize_string::<rom_format::Lattice>("chromosome", None).to_edges();
+    for pair in edges.windows(2) {
+        if pair[0].source_range() == pair[1].source_range() {
+            assert!(pair[0].score() >= pair[1].score());
+        }
+    }
+}
+
+#[test]
+fn test_romanize_char() {
+    let uroman = Uroman::new();
+
+    // (prev, ch, lcode, expected)
+    let cases = [
+        ("", 'か', Some("jpn"), "ka"),
+        // Gemination is romanized with the small tsu before it.
+        ("っ", 'か', Some("jpn"), "kka"),
+        ("にほ", 'ん', Some("jpn"), "n"),
+        ("", 'क', Some("hin"), "ka"),
+        ("क", '\u{093F}', Some("hin"), "i"),
+        ("ш", 'т', Some("rus"), "t"),
+        ("Hello wor", 'l', None, "l"),
+        // Context beyond the last 8 characters is not consulted.
+        ("a very long sentence before っ", 'か', Some("jpn"), "kka"),
+    ];
+    for (prev, ch, lcode, expected) in cases {
+        assert_eq!(uroman.romanize_char(prev, ch, lcode), expected, "{prev:?} + {ch:?}");
+    }
+
+    // Typing a word character by character yields its romanization.
+    let word = "Москва";
+    let typed: String = word
+        .char_indices()
+        .map(|(i, ch)| uroman.romanize_char(&word[..i], ch, Some("rus")))
+        .collect();
+    assert_eq!(typed, "Moskva");
+}
+
+#[test]
+fn test_romanize_is_identity() {
+    let uroman = Uroman::new();
+
+    // (input, lcode, identity)
+    let cases = [
+        ("Hello, world!", None, true),
+        ("", None, true),
+        ("Kyiv", Some("ukr"), true),
+        ("Київ", Some("ukr"), false),
+        ("東京", Some("jpn"), false),
+        // Digits are romanized, but to themselves.
+        ("2024", None, true),
+        ("✨", None, true),
+    ];
+    for (input, lcode, identity) in cases {
+        assert_eq!(uroman.romanize_is_identity(input, lcode), identity, "{input}");
+        let (romanized, is_identity) = uroman.romanize_with_identity(input, lcode);
+        assert_eq!(is_identity, identity, "{input}");
+        assert_eq!(
+            romanized,
+            uroman.romanize_escaped_with_format(input, lcode, None).to_string().unwrap()
+        );
+    }
+
+    // Options that change ASCII text are taken into account.
+    let lowercase = Uroman::builder().case_sensitive(false).build().unwrap();
+    assert!(!lowercase.romanize_is_identity("Hello", None));
+    let collapse = Uroman::builder().collapse_whitespace(true).build().unwrap();
+    assert!(!collapse.romanize_is_identity("a  b", None));
+    assert!(collapse.romanize_is_identity("a b", None));
+}
+
+#[test]
+fn test_max_expansion_ratio() {
+    use uroman::RomanizationError;
+
+    let uroman = Uroman::builder().max_expansion_ratio(1.5).build().unwrap();
+
+    // Щ is 2 bytes and romanizes to 4.
+    assert!(matches!(
+        uroman.romanize_checked("ЩЩЩЩ", Some("rus")),
+        Err(RomanizationError::ExpansionLimit { input_len: 8, limit: 12 })
+    ));
+    assert_eq!(uroman.romanize_checked("Москва", Some("rus")).unwrap(), "Moskva");
+
+    // The methods that cannot fail truncate at the limit.
+    let truncated = uroman.romanize_escaped_with_format("ЩЩЩЩ", None, None).to_string().unwrap();
+    assert_eq!(truncated, "ShchShchShch");
+    assert!(matches!(
+        uroman.romanize_bytes("ЩЩЩЩ".as_bytes(), None),
+        Err(RomanizationError::ExpansionLimit { .. })
+    ));
+
+    // Without a limit, the romanization is unbounded.
+    assert_eq!(
+        Uroman::new().romanize_checked("ЩЩЩЩ", None).unwrap(),
+        "ShchShchShchShch"
+    );
+
+    // Ratios that would empty every output are rejected, as are invalid edge scores.
+    for ratio in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        assert!(matches!(
+            Uroman::builder().max_expansion_ratio(ratio).build(),
+            Err(uroman::BuildError::InvalidOption { option: "max_expansion_ratio", .. })
+        ));
+    }
+    for score in [-0.1, f32::NAN] {
+        assert!(matches!(
+            Uroman::builder().min_edge_score(score).build(),
+            Err(uroman::BuildError::InvalidOption { option: "min_edge_score", .. })
+        ));
+    }
+}
+
+#[test]
+fn test_max_expansion_ratio_in_every_method() {
+    use uroman::{AnnotationGranularity, RomanizationError};
+
+    let uroman = Uroman::builder().max_expansion_ratio(1.5).build().unwrap();
+    let text = "ЩЩЩЩ";
+    let truncated = "ShchShchShch";
+
+    assert_romanizes_to_str(&uroman, text, None, truncated);
+    assert!(matches!(
+        uroman.try_romanize(text, None),
+        Err(RomanizationError::ExpansionLimit { input_len: 8, limit: 12 })
+    ));
+    assert_eq!(uroman.romanize_cow(text, None), truncated);
+    assert_eq!(uroman.romanize_with_diagnostics(text, None).0, truncated);
+
+    let (romanized, alignment) = uroman.romanize_with_alignment(text, None);
+    assert_eq!(romanized, truncated);
+    assert_eq!(alignment.last().unwrap().1, 12..12);
+
+    let edits = uroman.romanize_edits(text, None);
+    assert_eq!(edits.iter().map(|edit| edit.replacement.as_str()).collect::<String>(), truncated);
+    let annotated = uroman.romanize_annotated(text, None, AnnotationGranularity::Token);
+    assert!(!annotated.contains("ShchShchShchShch"));
+
+    // The limit can end inside the romanization of a character.
+    let uroman = Uroman::builder().max_expansion_ratio(0.5).build().unwrap();
+    assert_eq!(uroman.romanize_cow("Жё", None), "Zh");
+}
+
+#[test]
+fn test_edge_source_script() {
+    use uroman::{Edge, Script};
+
+    let uroman = Uroman::new();
+    let edges = uroman
+        .romanize_string::<rom_format::Edges>("Ж 北京 12!", None)
+        .to_edges();
+    let scripts: Vec<(&str, Option<Script>)> = edges
+        .iter()
+        .map(|edge| (edge.txt(), edge.source_script()))
+        .collect();
+    assert_eq!(
+        scripts,
+        [
+            ("Zh", Some(Script::Cyrillic)),
+            (" ", Some(Script::Common)),
+            ("beijing", Some(Script::Han)),
+            (" ", Some(Script::Common)),
+            ("12", Some(Script::Common)),
+            ("!", Some(Script::Common)),
+        ]
+    );
+
+    let lattice = uroman
+        .romanize_string::<rom_format::Lattice>("Ж", None)
+        .to_edges();
+    assert!(lattice.iter().all(|edge| edge.source_script() == Some(Script::Cyrillic)));
+
+    let edge = Edge::new_regular(0, 1, "zh".to_string(), "rom".to_string());
+    assert_eq!(edge.source_script(), None);
+}
+
+#[test]
+fn test_romanize_file_jsonl() {
+    use uroman::RomFormat;
+
+    let uroman = Uroman::new();
+    let input = "Київ\n\\u0416\n";
+
+    let mut output = Vec::new();
+    uroman
+        .romanize_file(input.as_bytes(), &mut output, Some("ukr"), RomFormat::Jsonl, None, true, true)
+        .unwrap();
+    let records: Vec<serde_json::Value> = std::str::from_utf8(&output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+
+    assert_eq!(records[0]["orig"], "Київ");
+    assert_eq!(records[0]["rom"], "Kyiv");
+    let edges = records[0]["edges"].as_array().unwrap();
+    let txt: String = edges.iter().map(|edge| edge["txt"].as_str().unwrap()).collect();
+    assert_eq!(txt, "Kyiv");
+    assert_eq!(edges[0]["start"], 0);
+
+    // The original line keeps its escapes, which are decoded for the romanization.
+    assert_eq!(records[1]["orig"], "\\u0416");
+    assert_eq!(records[1]["rom"], "Zh");
+    assert_eq!(records[1]["edges"][0]["end"], 1);
+    assert!(records[1].get("lcode").is_none());
+
+    // `rom` and the edges agree under the options of the string output, and a directive
+    // is given as a field.
+    let uroman = Uroman::builder().collapse_whitespace(true).build().unwrap();
+    let input = "::lcode rus Щ  ы \n";
+    let mut output = Vec::new();
+    uroman
+        .romanize_file(input.as_bytes(), &mut output, None, RomFormat::Jsonl, None, false, true)
+        .unwrap();
+    let record: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(record["lcode"], "rus");
+    assert_eq!(record["orig"], "Щ  ы ");
+    assert_eq!(record["rom"], "Shch y");
+    let edges = record["edges"].as_array().unwrap();
+    let txt: String = edges.iter().map(|edge| edge["txt"].as_str().unwrap()).collect();
+    assert_eq!(txt, "Shch y");
+}
+
+#[test]
+fn test_schemes_by_lcode() {
+    use std::collections::HashMap;
+    use uroman::{BuildError, CyrillicScheme};
+
+    let schemes = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(lcode, name)| (lcode.to_string(), name.to_string()))
+            .collect()
+    };
+    let uroman = Uroman::builder()
+        .cyrillic_scheme(CyrillicScheme::Iso9)
+        .schemes(schemes(&[("kor", "mccune"), ("zho", "wade-giles"), ("rus", "bgn-pcgn")]))
+        .build()
+        .unwrap();
+
+    assert_romanizes_to_str(&uroman, "부산", Some("kor"), "pusan");
+    assert_romanizes_to_str(&uroman, "北京", Some("zho"), "pei-ching");
+    // The scheme for `rus` does not change the Cyrillic scheme of other languages.
+    assert_romanizes_to_str(&uroman, "щука", Some("rus"), "shchuka");
+    assert_romanizes_to_str(&uroman, "щука", Some("bul"), "ŝuka");
+    assert_romanizes_to_str(&uroman, "北京", Some("yue"), "beijing");
+
+    // `default` restores the table rules.
+    let defaults = Uroman::builder()
+        .korean_scheme(uroman::KoreanScheme::RevisedRomanization)
+        .schemes(schemes(&[("kor", "default")]))
+        .build()
+        .unwrap();
+    let table = Uroman::new()
+        .romanize_string::<rom_format::Str>("독립문", Some("kor"))
+        .to_string();
+    assert_romanizes_to_str(&defaults, "독립문", Some("kor"), &table);
+
+    for (lcode, name) in [("zho", "mccune"), ("kor", "Mccune"), ("eng", "default")] {
+        assert_eq!(
+            Uroman::builder().schemes(schemes(&[(lcode, name)])).build().err(),
+            Some(BuildError::UnknownScheme {
+                lcode: lcode.to_string(),
+                scheme: name.to_string()
+            })
+        );
+    }
+}
+
+#[test]
+fn test_romanize_annotated() {
+    use uroman::AnnotationGranularity::{Sentence, Token};
+
+    let uroman = Uroman::new();
+
+    // (input, lcode, per token, per sentence)
+    let cases = [
+        ("東京", Some("jpn"), "dongjing (東京)", "dongjing (東京)"),
+        (
+            "Київ і Львів!",
+            Some("ukr"),
+            "Kyiv (Київ) i (і) Lviv (Львів)!",
+            "Kyiv i Lviv! (Київ і Львів!)",
+        ),
+        (
+            "Київ. Kyiv.  Львів?",
+            Some("ukr"),
+            "Kyiv (Київ). Kyiv.  Lviv (Львів)?",
+            "Kyiv. (Київ.) Kyiv.  Lviv? (Львів?)",
+        ),
+        ("Hello, 2024!", None, "Hello, 2024!", "Hello, 2024!"),
+        ("", None, "", ""),
+    ];
+    for (input, lcode, expected_token, expected_sentence) in cases {
+        assert_eq!(uroman.romanize_annotated(input, lcode, Token), expected_token, "{input}");
+        assert_eq!(
+            uroman.romanize_annotated(input, lcode, Sentence),
+            expected_sentence,
+            "{input}"
+        );
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_romanize_stream() {
+    use bytes::Bytes;
+    use futures_core::Stream;
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    struct Chunks(VecDeque<Bytes>);
+
+    impl Stream for Chunks {
+        type Item = Bytes;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+            Poll::Ready(self.0.pop_front())
+        }
+    }
+
+    let uroman = Uroman::new();
+    let input = "Москва\nこんにちは रामायण\nЩука";
+    let expected = uroman.romanize_string::<rom_format::Str>(input, None).to_string();
+
+    // Every split of the input, including splits inside a character, romanizes the same.
+    for split in 1..input.len() {
+        let (head, tail) = input.as_bytes().split_at(split);
+        let chunks = Chunks(VecDeque::from([
+            Bytes::copy_from_slice(head),
+            Bytes::copy_from_slice(tail),
+        ]));
+        let mut stream = uroman.romanize_stream(chunks, None);
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut romanized = Vec::new();
+        while let Poll::Ready(Some(chunk)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            romanized.push(chunk);
+        }
+        assert_eq!(romanized.concat(), expected, "split at {split}");
+        assert!(romanized.iter().all(|chunk| !chunk.is_empty()));
+    }
+
+    // A long line is romanized before it ends, up to a space.
+    let line = "Щука ".repeat(20_000);
+    let chunks = Chunks(line.as_bytes().chunks(999).map(Bytes::copy_from_slice).collect());
+    let mut stream = uroman.romanize_stream(chunks, None);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut romanized = Vec::new();
+    while let Poll::Ready(Some(chunk)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        romanized.push(chunk);
+    }
+    assert!(romanized.len() > 1);
+    assert_eq!(romanized.concat(), "Shchuka ".repeat(20_000));
+}
+
+#[test]
+fn test_output_alphabet() {
+    let mut uroman = Uroman::new();
+    let alphabet = uroman.output_alphabet(None);
+    assert!(('a'..='z').chain('A'..='Z').chain('0'..='9').all(|c| alphabet.contains(&c)));
+    assert!(alphabet.contains(&'\''));
+
+    // The rules of a language only add to its own alphabet.
+    uroman.add_rule("ж", "ẑ", Some("ukr"));
+    assert!(uroman.output_alphabet(Some("ukr")).contains(&'ẑ'));
+    assert!(!uroman.output_alphabet(Some("rus")).contains(&'ẑ'));
+    assert!(!uroman.output_alphabet(None).contains(&'ẑ'));
+
+    // Every character of a romanization is in the alphabet of its language.
+    for (input, lcode) in [("Київ", Some("ukr")), ("こんにちは", Some("jpn")), ("北京 2024", None)] {
+        let romanized = uroman.romanize_string::<rom_format::Str>(input, lcode).to_string();
+        let alphabet = uroman.output_alphabet(lcode);
+        assert!(romanized.chars().all(|c| c == ' ' || alphabet.contains(&c)), "{input}");
+    }
+
+    // Options that change the romanization change the alphabet.
+    let folded = Uroman::builder()
+        .ascii_fold(true)
+        .case_sensitive(false)
+        .syllable_separator(None)
+        .build()
+        .unwrap()
+        .output_alphabet(None);
+    assert!(folded.len() < alphabet.len());
+    assert!(!folded.iter().any(|c| c.is_ascii_uppercase()));
+    assert!(!folded.contains(&'\''));
+}
+
+#[test]
+fn test_validate_rules() {
+    use uroman::RuleWarning;
+
+    let uroman = Uroman::new();
+    let rules = "# Ukrainian place names\n\
+                 ::s Київ ::t Kyiv ::lcode ukr\n\
+                 ::s Київ ::t Kyiv ::lcode ukr\n\
+                 ::s Київ ::t Kiev ::lcode ukr\n\
+                 ::s Київ ::t Kiev\n\
+                 \n\
+                 ::s ївщ ::t yivshch\n\
+                 ::s щ ::t shch ::lcode xxx\n\
+                 ::s ґ\n";
+    let warnings = uroman.validate_rules_str(rules).unwrap();
+    assert_eq!(
+        warnings,
+        [
+            RuleWarning::DuplicateRule { line: 3, first_line: 2, source: "Київ".to_string() },
+            RuleWarning::ConflictingRule { line: 4, first_line: 2, source: "Київ".to_string() },
+            RuleWarning::OverlappingSources {
+                line: 7,
+                other_line: 2,
+                source: "ївщ".to_string(),
+                other_source: "Київ".to_string(),
+            },
+            RuleWarning::UnknownLanguage { line: 8, lcode: "xxx".to_string() },
+            RuleWarning::MissingTarget { line: 9, source: "ґ".to_string() },
+        ]
+    );
+    assert_eq!(warnings[0].to_string(), "Line 3 repeats the rule for 'Київ' on line 2");
+
+    // Validating neither adds the rules nor hides parse errors.
+    assert_romanizes_to_str(&uroman, "Київ", Some("ukr"), "Kyiv");
+    assert!(matches!(
+        uroman.validate_rules_str("::t Kyiv"),
+        Err(uroman::RomanizationError::InvalidRule { line: 1 })
+    ));
+
+    let path = std::env::temp_dir().join("uroman_validate_rules.txt");
+    std::fs::write(&path, rules).unwrap();
+    assert_eq!(uroman.validate_rules_file(&path).unwrap(), warnings);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_normalize_joiners() {
+    let mut uroman = Uroman::builder().normalize_joiners(true).build().unwrap();
+    uroman.add_rules_from_str("::s क्ष ::t ksh ::lcode hin").unwrap();
+
+    // A joiner only selects the half form of क, so the rule for the conjunct applies.
+    assert_romanizes_to_str(&uroman, "क्षमा", Some("hin"), "kshamaa");
+    assert_romanizes_to_str(&uroman, "क्\u{200D}षमा", Some("hin"), "kshamaa");
+    // A non-joiner prevents the conjunct, so its letters are romanized separately.
+    assert_romanizes_to_str(&uroman, "क्\u{200C}षमा", Some("hin"), "kssamaa");
+
+    // Non-joiners between the letters of Persian words and joiners of emoji are kept.
+    let (_, alignment) = uroman.romanize_with_alignment("می\u{200C}خواهم", Some("fas"));
+    assert_eq!(alignment.last().unwrap().0.end, "می\u{200C}خواهم".len());
+    let (_, alignment) = uroman.romanize_with_alignment("👩\u{200D}💻", None);
+    assert_eq!(alignment.last().unwrap().0.end, "👩\u{200D}💻".len());
+
+    // Without the option, a joiner prevents the conjunct rule as a non-joiner does.
+    let mut uroman = Uroman::new();
+    uroman.add_rules_from_str("::s क्ष ::t ksh ::lcode hin").unwrap();
+    assert_romanizes_to_str(&uroman, "क्\u{200D}षमा", Some("hin"), "kssamaa");
+}
+
+#[test]
+fn test_romanize_key() {
+    let uroman = Uroman::builder()
+        .indic_scheme(uroman::IndicScheme::Iast)
+        .build()
+        .unwrap();
+    assert_romanizes_to_str(&uroman, "ऋषि संस्कृत", None, "ṛṣi saṃskṛta");
+    assert_eq!(uroman.romanize_key(" ऋषि\n\tसंस्कृत ", None), "rsi samskrta");
+
+    // The key is the output of the folding options with its whitespace collapsed.
+    let folding = Uroman::builder()
+        .indic_scheme(uroman::IndicScheme::Iast)
+        .ascii_fold(true)
+        .case_sensitive(false)
+        .build()
+        .unwrap();
+    for (input, lcode) in [("Ærø  Київ", Some("ukr")), ("ΑΘΗΝΑ\n\nऋषि", None), ("", None)] {
+        let folded = folding.romanize_string::<rom_format::Str>(input, lcode).to_string();
+        let collapsed = folded.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert_eq!(uroman.romanize_key(input, lcode), collapsed, "{input}");
+    }
+}
+
+#[test]
+fn test_traditional_mongolian() {
+    let uroman = Uroman::builder().traditional_mongolian(true).build().unwrap();
+    assert_romanizes_to_str(&uroman, "ᠮᠣᠩᠭᠣᠯ", Some("mon"), "mongγol");
+    assert_romanizes_to_str(&uroman, "ᠬᠠᠭᠠᠨ ᠬᠡᠭᠡᠷ᠎ᠡ", Some("mon"), "qaγan kegere");
+    assert_romanizes_to_str(&uroman, "ᠥᠪᠦᠯ ᠴᠢᠯᠠᠭᠤ", Some("mon"), "öbül čilaγu");
+
+    // Variation selectors only choose a form of the letter before them.
+    assert_romanizes_to_str(&uroman, "ᠮᠣᠩᠭ᠋ᠣᠯ", Some("mon"), "mongγol");
+    // A suffix joined by a narrow no-break space follows a hyphen.
+    assert_romanizes_to_str(&uroman, "ᠭᠡᠷ\u{202F}ᠦᠨ", Some("mon"), "ger-ün");
+
+    // Mongolian in Cyrillic and other languages are unaffected.
+    assert_romanizes_to_str(&uroman, "Монгол", Some("mon"), "Mongol");
+    assert_romanizes_to_str(&uroman, "ᠮᠣᠩᠭᠣᠯ", None, "monggol");
+}
+
+#[test]
+fn test_ascii_fast_path_with_user_rules() {
+    let mut uroman = Uroman::new();
+    uroman.add_rule("abc", "xyz", None);
+    uroman.add_rules_from_str("::s q ::t k").unwrap();
+
+    for input in ["abc", "abc def", "qat", "no rule here"] {
+        let edges: String = uroman
+            .romanize_string::<rom_format::Edges>(input, None)
+            .to_edges()
+            .iter()
+            .map(|edge| edge.txt())
+            .collect();
+        assert_romanizes_to_str(&uroman, input, None, &edges);
+        assert_eq!(uroman.romanize_with_identity(input, None).0, edges);
+    }
+    assert_romanizes_to_str(&uroman, "abc", None, "xyz");
+    assert!(!uroman.romanize_is_identity("abc", None));
+}